@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -19,4 +20,130 @@ pub enum Error {
     
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
-}
\ No newline at end of file
+}
+
+// Why a task failed, as workflows branch on it. The names are stable: they
+// are matched by string in workflow conditions, so only ever add to this list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    // Worth retrying as is: interrupted, reset or otherwise flaky I/O
+    TransientIo,
+    NotFound,
+    // Bad params or bad data; retrying the same task fails the same way
+    Validation,
+    // Not allowed: permissions, paths outside the sandbox, credentials
+    Auth,
+    Timeout,
+    // A configured limit (size, count, cost) was reached
+    Budget,
+    Cancelled,
+    // Not mapped yet; a gap in an executor's mapping
+    Unknown,
+}
+
+impl ErrorCategory {
+    pub const ALL: &'static [ErrorCategory] = &[
+        ErrorCategory::TransientIo,
+        ErrorCategory::NotFound,
+        ErrorCategory::Validation,
+        ErrorCategory::Auth,
+        ErrorCategory::Timeout,
+        ErrorCategory::Budget,
+        ErrorCategory::Cancelled,
+        ErrorCategory::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::TransientIo => "transient_io",
+            ErrorCategory::NotFound => "not_found",
+            ErrorCategory::Validation => "validation",
+            ErrorCategory::Auth => "auth",
+            ErrorCategory::Timeout => "timeout",
+            ErrorCategory::Budget => "budget",
+            ErrorCategory::Cancelled => "cancelled",
+            ErrorCategory::Unknown => "unknown",
+        }
+    }
+
+    // Category for an I/O error kind; kinds with no clear meaning are Unknown
+    pub fn from_io_kind(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind;
+        match kind {
+            ErrorKind::NotFound => ErrorCategory::NotFound,
+            ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => ErrorCategory::Auth,
+            ErrorKind::TimedOut => ErrorCategory::Timeout,
+            ErrorKind::InvalidData
+            | ErrorKind::InvalidInput
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::AlreadyExists
+            | ErrorKind::DirectoryNotEmpty
+            | ErrorKind::NotADirectory
+            | ErrorKind::IsADirectory
+            | ErrorKind::InvalidFilename => ErrorCategory::Validation,
+            ErrorKind::StorageFull | ErrorKind::QuotaExceeded | ErrorKind::FileTooLarge => ErrorCategory::Budget,
+            ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::ResourceBusy
+            | ErrorKind::StaleNetworkFileHandle
+            | ErrorKind::Deadlock => ErrorCategory::TransientIo,
+            _ => ErrorCategory::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Parsing is the registry check for condition strings: a typo is an error
+impl std::str::FromStr for ErrorCategory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCategory::ALL.iter().copied().find(|c| c.as_str() == s).ok_or_else(|| {
+            let known: Vec<&str> = ErrorCategory::ALL.iter().map(|c| c.as_str()).collect();
+            Error::InvalidConfig(format!("Unknown error category '{}' (known: {})", s, known.join(", ")))
+        })
+    }
+}
+
+// Unknown means a mapping is missing; say so, so the gap gets noticed
+fn warn_if_unknown(error: &Error, category: ErrorCategory) -> ErrorCategory {
+    if category == ErrorCategory::Unknown {
+        tracing::warn!(error = %error, "Error has no category mapping; reporting it as unknown");
+    }
+    category
+}
+
+impl Error {
+    pub fn category(&self) -> ErrorCategory {
+        warn_if_unknown(self, self.mapped_category())
+    }
+
+    fn mapped_category(&self) -> ErrorCategory {
+        match self {
+            Error::Io(e) => ErrorCategory::from_io_kind(e.kind()),
+            Error::Serialization(_) => ErrorCategory::Validation,
+            Error::TaskNotFound(_) => ErrorCategory::NotFound,
+            Error::PermissionDenied(_) => ErrorCategory::Auth,
+            Error::Timeout => ErrorCategory::Timeout,
+            Error::InvalidConfig(_) => ErrorCategory::Validation,
+        }
+    }
+
+    // For executors with their own notion of failures: the first matching
+    // rule decides, anything else falls back to `category`
+    pub fn category_with(&self, rules: &[fn(&Error) -> Option<ErrorCategory>]) -> ErrorCategory {
+        let category = rules.iter().find_map(|rule| rule(self)).unwrap_or_else(|| self.mapped_category());
+        warn_if_unknown(self, category)
+    }
+}
@@ -2,6 +2,6 @@ pub mod error;
 pub mod task; 
 pub mod result; 
 
-pub use error::Error; 
+pub use error::{Error, ErrorCategory}; 
 pub use result::Result;
 pub use task::{Task, TaskId, TaskStatus};
\ No newline at end of file
@@ -0,0 +1,436 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::traits::{Executor, ExecutionResult};
+
+const DEFAULT_LEASE_MS: u64 = 30_000;
+const WAIT_POLL: Duration = Duration::from_millis(25);
+
+fn expires_after(now: DateTime<Utc>, ms: u64, name: &str) -> Result<DateTime<Utc>> {
+    i64::try_from(ms).ok()
+        .and_then(TimeDelta::try_milliseconds)
+        .and_then(|delta| now.checked_add_signed(delta))
+        .ok_or_else(|| Error::InvalidConfig(format!("{} of {} is out of range", name, ms)))
+}
+
+// One file per key under the cache directory, named by the key's hash. A hit
+// bumps the file's mtime, which is what LRU eviction orders by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    value: Value,
+    written_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    provenance: Provenance,
+}
+
+// Who wrote an entry: the writing task, and the run when the caller says
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Provenance {
+    task_id: String,
+    run_id: Option<String>,
+}
+
+// A claim to populate a missing key, so concurrent misses don't all fetch
+#[derive(Serialize, Deserialize)]
+struct Lease {
+    expires_at: DateTime<Utc>,
+}
+
+impl Entry {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|at| at > now)
+    }
+}
+
+enum Lookup {
+    Hit(Entry),
+    // Miss; true when this caller holds (or needs no) populate lease
+    Miss(bool),
+}
+
+// Local key/value cache with TTLs for memoizing slow lookups across runs.
+// Every operation holds an exclusive lock on `<dir>/.lock`, and entries are
+// published with an atomic rename, so racing processes never see torn entries.
+pub struct CacheExecutor {
+    dir: PathBuf,
+    max_bytes: Option<u64>,
+}
+
+impl CacheExecutor {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, max_bytes: None }
+    }
+
+    // Evict least recently used entries once the cache grows past this size
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+}
+
+fn lock(dir: &Path) -> Result<std::fs::File> {
+    std::fs::create_dir_all(dir)?;
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(".lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.json", hex::encode(Sha256::digest(key.as_bytes()))))
+}
+
+fn lease_path(dir: &Path, key: &str) -> PathBuf {
+    entry_path(dir, key).with_extension("lease")
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<u64> {
+    let data = serde_json::to_vec(value)?;
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+    let mut tmp = std::fs::File::create(&tmp_path)?;
+    tmp.write_all(&data)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(data.len() as u64)
+}
+
+fn remove(path: &Path) -> Result<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Fresh entry for `key`, or None after dropping an expired one
+fn lookup(dir: &Path, key: &str, now: DateTime<Utc>) -> Result<Option<Entry>> {
+    let path = entry_path(dir, key);
+    match read_json::<Entry>(&path)? {
+        Some(entry) if entry.is_fresh(now) => {
+            std::fs::File::options().write(true).open(&path)?.set_modified(SystemTime::now())?;
+            Ok(Some(entry))
+        }
+        Some(_) => {
+            remove(&path)?;
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+// Every stored entry with its path, size and last use
+fn scan(dir: &Path) -> Result<Vec<(PathBuf, Entry, u64, SystemTime)>> {
+    let mut entries = Vec::new();
+    let listing = match std::fs::read_dir(dir) {
+        Ok(listing) => listing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(entries),
+        Err(e) => return Err(e.into()),
+    };
+    for item in listing {
+        let path = item?.path();
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let metadata = std::fs::metadata(&path)?;
+        if let Some(entry) = read_json::<Entry>(&path)? {
+            entries.push((path, entry, metadata.len(), metadata.modified()?));
+        }
+    }
+    Ok(entries)
+}
+
+// Drop expired entries, then least recently used ones until under `max_bytes`.
+// `keep` (the entry just written) is never evicted.
+fn evict(dir: &Path, max_bytes: u64, keep: &Path, now: DateTime<Utc>) -> Result<Vec<String>> {
+    let mut evicted = Vec::new();
+    let mut live = Vec::new();
+    for (path, entry, size, used) in scan(dir)? {
+        if entry.is_fresh(now) {
+            live.push((path, entry.key, size, used));
+        } else {
+            remove(&path)?;
+        }
+    }
+    let mut total: u64 = live.iter().map(|(_, _, size, _)| size).sum();
+    live.sort_by_key(|(_, _, _, used)| *used);
+    for (path, key, size, _) in live {
+        if total <= max_bytes {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        remove(&path)?;
+        total -= size;
+        evicted.push(key);
+    }
+    Ok(evicted)
+}
+
+#[async_trait]
+impl Executor for CacheExecutor {
+    fn name(&self) -> &str {
+        "cache"
+    }
+
+    fn validate(&self, task: &Task) -> Result<()> {
+        if task.executor != self.name() {
+            return Err(Error::InvalidConfig(
+                format!("Wrong executor: expected 'cache', got '{}'", task.executor)
+            ));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, task: &Task) -> Result<ExecutionResult> {
+        self.validate(task)?;
+
+        match task.operation.as_str() {
+            "get" => self.get(task).await,
+            "put" => self.put(task).await,
+            "get_or_populate" => self.get_or_populate(task).await,
+            "invalidate" => self.invalidate(task).await,
+            _ => Err(Error::InvalidConfig(
+                format!("Unknown operation: {}", task.operation)
+            )),
+        }
+    }
+}
+
+fn hit_output(key: &str, entry: Option<Entry>) -> Value {
+    match entry {
+        Some(entry) => json!({
+            "key": key,
+            "hit": true,
+            "value": entry.value,
+            "written_at": entry.written_at,
+            "expires_at": entry.expires_at,
+            "provenance": entry.provenance,
+        }),
+        None => json!({ "key": key, "hit": false, "value": null }),
+    }
+}
+
+impl CacheExecutor {
+    async fn get(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let dir = self.dir.clone();
+        let key = params.key.clone();
+        let entry = blocking(move || {
+            let _guard = lock(&dir)?;
+            lookup(&dir, &key, Utc::now())
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(hit_output(&params.key, entry)),
+            error: None,
+        })
+    }
+
+    async fn put(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+            value: Value,
+            // No TTL keeps the entry until it is invalidated or evicted
+            ttl_ms: Option<u64>,
+            run_id: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let now = Utc::now();
+        let entry = Entry {
+            key: params.key.clone(),
+            value: params.value,
+            written_at: now,
+            expires_at: params.ttl_ms.map(|ms| expires_after(now, ms, "ttl_ms")).transpose()?,
+            provenance: Provenance { task_id: task.id.to_string(), run_id: params.run_id },
+        };
+        let size = serde_json::to_vec(&entry)?.len() as u64;
+        if let Some(max_bytes) = self.max_bytes.filter(|max| size > *max) {
+            return Err(Error::InvalidConfig(format!(
+                "Entry for '{}' is {} bytes, over the cache limit of {}", params.key, size, max_bytes
+            )));
+        }
+
+        let (dir, max_bytes) = (self.dir.clone(), self.max_bytes);
+        let expires_at = entry.expires_at;
+        let evicted = blocking(move || {
+            let _guard = lock(&dir)?;
+            let path = entry_path(&dir, &entry.key);
+            write_json(&path, &entry)?;
+            // Whoever was waiting on a populate lease can now read the entry
+            remove(&lease_path(&dir, &entry.key))?;
+            match max_bytes {
+                Some(max_bytes) => evict(&dir, max_bytes, &path, now),
+                None => Ok(Vec::new()),
+            }
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "key": params.key,
+                "bytes": size,
+                "expires_at": expires_at,
+                "evicted": evicted,
+            })),
+            error: None,
+        })
+    }
+
+    // A hit returns the value. On a miss `populate: true` tells this run to
+    // fetch and `put`. With `wait_ms`, only the first missing run gets
+    // `populate: true`; the others wait up to `wait_ms` for its value, and
+    // fall back to populating themselves if it does not arrive in time.
+    async fn get_or_populate(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+            wait_ms: Option<u64>,
+            // How long a populate claim holds if its run never puts
+            #[serde(default = "default_lease_ms")]
+            lease_ms: u64,
+        }
+
+        fn default_lease_ms() -> u64 { DEFAULT_LEASE_MS }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        // Reject a lease that can't be represented before anything is claimed
+        expires_after(Utc::now(), params.lease_ms, "lease_ms")?;
+        let deadline = params.wait_ms.map(|ms| tokio::time::Instant::now() + Duration::from_millis(ms));
+        let lookup_result = loop {
+            let dir = self.dir.clone();
+            let key = params.key.clone();
+            let (stampede, lease_ms) = (params.wait_ms.is_some(), params.lease_ms);
+            let found = blocking(move || {
+                let _guard = lock(&dir)?;
+                let now = Utc::now();
+                if let Some(entry) = lookup(&dir, &key, now)? {
+                    return Ok(Lookup::Hit(entry));
+                }
+                if !stampede {
+                    return Ok(Lookup::Miss(true));
+                }
+                let lease = lease_path(&dir, &key);
+                let held = read_json::<Lease>(&lease)?.is_some_and(|l| l.expires_at > now);
+                if held {
+                    return Ok(Lookup::Miss(false));
+                }
+                write_json(&lease, &Lease { expires_at: expires_after(now, lease_ms, "lease_ms")? })?;
+                Ok(Lookup::Miss(true))
+            })
+            .await?;
+            match (found, deadline) {
+                (Lookup::Miss(false), Some(deadline)) if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(WAIT_POLL).await;
+                }
+                (Lookup::Miss(false), _) => break Lookup::Miss(true),
+                (found, _) => break found,
+            }
+        };
+
+        let output = match lookup_result {
+            Lookup::Hit(entry) => {
+                let mut output = hit_output(&params.key, Some(entry));
+                output["populate"] = json!(false);
+                output
+            }
+            Lookup::Miss(populate) => {
+                let mut output = hit_output(&params.key, None);
+                output["populate"] = json!(populate);
+                output
+            }
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    async fn invalidate(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: Option<String>,
+            prefix: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let dir = self.dir.clone();
+        let invalidated = match (params.key, params.prefix) {
+            (Some(key), None) => blocking(move || {
+                let _guard = lock(&dir)?;
+                remove(&lease_path(&dir, &key))?;
+                Ok(if remove(&entry_path(&dir, &key))? { vec![key] } else { Vec::new() })
+            })
+            .await?,
+            (None, Some(prefix)) => blocking(move || {
+                let _guard = lock(&dir)?;
+                let mut removed = Vec::new();
+                for (path, entry, _, _) in scan(&dir)? {
+                    if entry.key.starts_with(&prefix) {
+                        remove(&path)?;
+                        removed.push(entry.key);
+                    }
+                }
+                removed.sort();
+                Ok(removed)
+            })
+            .await?,
+            _ => return Err(Error::InvalidConfig(
+                "invalidate takes exactly one of 'key' or 'prefix'".to_string()
+            )),
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({ "invalidated": invalidated })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use async_compression::tokio::{bufread, write};
+use async_compression::Level;
+use std::path::Path;
+use std::pin::Pin;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite};
+
+pub type CodecReader = Pin<Box<dyn AsyncRead + Send>>;
+pub type CodecWriter = Pin<Box<dyn AsyncWrite + Send>>;
+pub type CodecSource = Pin<Box<dyn AsyncBufRead + Send>>;
+
+// Longest magic number of any codec; read this much to sniff a file
+pub const SNIFF_LEN: usize = 6;
+
+// A streaming compression format. Encoders must be shut down to write their
+// trailer; decoders fail with InvalidData on input in another format.
+pub trait Codec: Send + Sync {
+    fn name(&self) -> &'static str;
+    // Without the leading dot
+    fn extension(&self) -> &'static str;
+    fn magic(&self) -> &'static [u8];
+    fn levels(&self) -> std::ops::RangeInclusive<i32>;
+    fn encoder(&self, inner: CodecWriter, level: Option<i32>) -> CodecWriter;
+    fn decoder(&self, inner: CodecSource) -> CodecReader;
+
+    fn check_level(&self, level: i32) -> Result<(), String> {
+        if self.levels().contains(&level) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} level must be between {} and {}",
+                self.name(), self.levels().start(), self.levels().end()
+            ))
+        }
+    }
+}
+
+fn level(level: Option<i32>) -> Level {
+    level.map(Level::Precise).unwrap_or(Level::Default)
+}
+
+struct Gzip;
+
+impl Codec for Gzip {
+    fn name(&self) -> &'static str { "gzip" }
+    fn extension(&self) -> &'static str { "gz" }
+    fn magic(&self) -> &'static [u8] { &[0x1f, 0x8b] }
+    fn levels(&self) -> std::ops::RangeInclusive<i32> { 0..=9 }
+
+    fn encoder(&self, inner: CodecWriter, level: Option<i32>) -> CodecWriter {
+        Box::pin(write::GzipEncoder::with_quality(inner, self::level(level)))
+    }
+
+    fn decoder(&self, inner: CodecSource) -> CodecReader {
+        let mut decoder = bufread::GzipDecoder::new(inner);
+        // `cat a.gz b.gz > c.gz` is a valid gzip file
+        decoder.multiple_members(true);
+        Box::pin(decoder)
+    }
+}
+
+#[cfg(feature = "zstd")]
+struct Zstd;
+
+#[cfg(feature = "zstd")]
+impl Codec for Zstd {
+    fn name(&self) -> &'static str { "zstd" }
+    fn extension(&self) -> &'static str { "zst" }
+    fn magic(&self) -> &'static [u8] { &[0x28, 0xb5, 0x2f, 0xfd] }
+    fn levels(&self) -> std::ops::RangeInclusive<i32> { 1..=22 }
+
+    fn encoder(&self, inner: CodecWriter, level: Option<i32>) -> CodecWriter {
+        Box::pin(write::ZstdEncoder::with_quality(inner, self::level(level)))
+    }
+
+    fn decoder(&self, inner: CodecSource) -> CodecReader {
+        let mut decoder = bufread::ZstdDecoder::new(inner);
+        decoder.multiple_members(true);
+        Box::pin(decoder)
+    }
+}
+
+#[cfg(feature = "xz")]
+struct Xz;
+
+#[cfg(feature = "xz")]
+impl Codec for Xz {
+    fn name(&self) -> &'static str { "xz" }
+    fn extension(&self) -> &'static str { "xz" }
+    fn magic(&self) -> &'static [u8] { &[0xfd, b'7', b'z', b'X', b'Z', 0x00] }
+    fn levels(&self) -> std::ops::RangeInclusive<i32> { 0..=9 }
+
+    fn encoder(&self, inner: CodecWriter, level: Option<i32>) -> CodecWriter {
+        Box::pin(write::XzEncoder::with_quality(inner, self::level(level)))
+    }
+
+    fn decoder(&self, inner: CodecSource) -> CodecReader {
+        let mut decoder = bufread::XzDecoder::new(inner);
+        decoder.multiple_members(true);
+        Box::pin(decoder)
+    }
+}
+
+static CODECS: &[&dyn Codec] = &[
+    &Gzip,
+    #[cfg(feature = "zstd")]
+    &Zstd,
+    #[cfg(feature = "xz")]
+    &Xz,
+];
+
+pub fn available() -> Vec<&'static str> {
+    CODECS.iter().map(|c| c.name()).collect()
+}
+
+pub fn by_name(name: &str) -> Option<&'static dyn Codec> {
+    CODECS.iter().copied().find(|c| c.name().eq_ignore_ascii_case(name))
+}
+
+pub fn from_extension(path: &Path) -> Option<&'static dyn Codec> {
+    let ext = path.extension()?.to_str()?;
+    CODECS.iter().copied().find(|c| c.extension().eq_ignore_ascii_case(ext))
+}
+
+pub fn sniff(header: &[u8]) -> Option<&'static dyn Codec> {
+    CODECS.iter().copied().find(|c| header.starts_with(c.magic()))
+}
+
+// The one detection rule every operation uses: an explicit name wins, then the
+// content's magic bytes, then the file extension.
+pub fn detect(
+    name: Option<&str>,
+    path: &Path,
+    header: &[u8],
+) -> Result<Option<&'static dyn Codec>, String> {
+    match name {
+        Some(name) => by_name(name).map(Some).ok_or_else(|| format!(
+            "Unknown codec '{}' (available: {})", name, available().join(", ")
+        )),
+        None => Ok(sniff(header).or_else(|| from_extension(path))),
+    }
+}
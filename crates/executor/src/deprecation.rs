@@ -0,0 +1,129 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::traits::ExecutionResult;
+
+// Operation `alias` is dispatched as `target`, optionally reshaping its params
+// first. Aliases without `deprecated_since` are permanent spellings.
+pub struct OperationAlias {
+    pub alias: &'static str,
+    pub target: &'static str,
+    pub deprecated_since: Option<&'static str>,
+    pub transform: Option<fn(Value) -> Result<Value>>,
+}
+
+// A param of `operation` being phased out. With a replacement the value is
+// moved over unless the task already sets the replacement.
+pub struct DeprecatedParam {
+    pub operation: &'static str,
+    pub param: &'static str,
+    pub since: &'static str,
+    pub replacement: Option<&'static str>,
+}
+
+// One deprecated spelling a task used; reported in the result's `warnings`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Deprecation {
+    pub kind: &'static str,
+    pub operation: String,
+    pub name: String,
+    pub since: &'static str,
+    pub replacement: Option<&'static str>,
+    pub message: String,
+}
+
+// Rewrite `task` into its current spelling. In strict mode any deprecated
+// spelling is an error instead, so CI can catch them before they are removed.
+pub fn resolve<'a>(
+    task: &'a Task,
+    aliases: &[OperationAlias],
+    params: &[DeprecatedParam],
+    strict: bool,
+) -> Result<(Cow<'a, Task>, Vec<Deprecation>)> {
+    let mut task = Cow::Borrowed(task);
+    let mut warnings = Vec::new();
+
+    if let Some(alias) = aliases.iter().find(|a| a.alias == task.operation) {
+        if let Some(since) = alias.deprecated_since {
+            warnings.push(Deprecation {
+                kind: "operation",
+                operation: alias.alias.to_string(),
+                name: alias.alias.to_string(),
+                since,
+                replacement: Some(alias.target),
+                message: format!(
+                    "Operation '{}' is deprecated since {}; use '{}'", alias.alias, since, alias.target
+                ),
+            });
+        }
+        let owned = task.to_mut();
+        owned.operation = alias.target.to_string();
+        if let Some(transform) = alias.transform {
+            owned.params = transform(std::mem::take(&mut owned.params))?;
+        }
+    }
+
+    let operation = task.operation.clone();
+    for deprecated in params.iter().filter(|p| p.operation == operation) {
+        if task.params.get(deprecated.param).is_none() {
+            continue;
+        }
+        warnings.push(Deprecation {
+            kind: "param",
+            operation: deprecated.operation.to_string(),
+            name: deprecated.param.to_string(),
+            since: deprecated.since,
+            replacement: deprecated.replacement,
+            message: match deprecated.replacement {
+                Some(replacement) => format!(
+                    "Param '{}' of '{}' is deprecated since {}; use '{}'",
+                    deprecated.param, deprecated.operation, deprecated.since, replacement
+                ),
+                None => format!(
+                    "Param '{}' of '{}' is deprecated since {}",
+                    deprecated.param, deprecated.operation, deprecated.since
+                ),
+            },
+        });
+        if let (Some(replacement), Some(map)) = (deprecated.replacement, task.to_mut().params.as_object_mut()) {
+            let value = map.remove(deprecated.param).unwrap_or_default();
+            map.entry(replacement).or_insert(value);
+        }
+    }
+
+    if strict && !warnings.is_empty() {
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        return Err(Error::InvalidConfig(format!("Deprecated usage: {}", messages.join("; "))));
+    }
+    Ok((task, warnings))
+}
+
+// Add `warnings` to the result's output object, after any warnings the
+// operation reported itself. Any other output is wrapped as `value` rather than
+// losing the warnings.
+pub fn attach_warnings(mut result: ExecutionResult, warnings: Vec<Deprecation>) -> Result<ExecutionResult> {
+    if warnings.is_empty() {
+        return Ok(result);
+    }
+    let Value::Array(mut warnings) = serde_json::to_value(warnings)? else {
+        unreachable!("a Vec serializes to an array");
+    };
+    result.output = Some(match result.output.take() {
+        Some(Value::Object(mut map)) => match map.get_mut("warnings") {
+            Some(Value::Array(existing)) => {
+                existing.append(&mut warnings);
+                Value::Object(map)
+            }
+            None => {
+                map.insert("warnings".to_string(), Value::Array(warnings));
+                Value::Object(map)
+            }
+            Some(_) => serde_json::json!({ "value": Value::Object(map), "warnings": warnings }),
+        },
+        Some(other) => serde_json::json!({ "value": other, "warnings": warnings }),
+        None => serde_json::json!({ "warnings": warnings }),
+    });
+    Ok(result)
+}
@@ -1,18 +1,146 @@
 use async_trait::async_trait;
 use local_automation_common::{Error, Result, Task};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 
+use crate::deprecation::{attach_warnings, resolve, OperationAlias};
+use crate::sanitize::{sanitize_filenames, SanitizeOptions};
+use crate::state::{compare_marks, StateStore};
 use crate::traits::{Executor, ExecutionResult};
 
+mod append_csv;
+mod atomic;
+mod backup;
+mod binary;
+mod changes;
+mod compress;
+mod concat;
+mod consistency;
+mod convert;
+mod copy_dir;
+mod copy_large;
+mod csv_delta;
+mod csv_select;
+mod dialect;
+mod diff;
+mod distinct;
+mod edit;
+mod external_sort;
+mod follow;
+mod format;
+mod glob;
+mod hash;
+mod impact;
+mod internal;
+mod json_stream;
+mod listing;
+mod lines;
+mod lock;
+mod merge;
+mod ndjson;
+mod onboard;
+mod patch;
+mod preview;
+mod profile;
+mod quarantine;
+mod query;
+mod replace;
+mod resumable;
+mod sketch;
+mod snapshot;
+mod stat;
+mod sync;
+mod tar;
+mod toml;
+mod txn;
+mod typed_csv;
+mod xml;
+mod yaml;
+mod zip;
+
+pub use consistency::WriteConsistency;
+pub use impact::ImpactLimits;
+
+use atomic::AtomicWrite;
+use backup::BackupParams;
+use consistency::ExpectRecent;
+use csv_select::{ColumnRef, RowFilter, Selection};
+use dialect::CsvDialect;
+use edit::EditSession;
+use format::{FormatParams, ValueFormatter};
+use impact::ConfirmImpact;
+use lock::HeldLock;
+use preview::preview_budget;
+use resumable::ResumeOptions;
+use txn::Transaction;
+
 pub struct FileExecutor {
     base_path: PathBuf,
+    locks: Mutex<HashMap<String, HeldLock>>,
+    edit_sessions: Arc<Mutex<HashMap<String, EditSession>>>,
+    transactions: Arc<Mutex<HashMap<String, Transaction>>>,
+    state: Option<Arc<StateStore>>,
+    quarantine: Option<PathBuf>,
+    consistency: WriteConsistency,
+    impact_limits: Option<ImpactLimits>,
+    strict_deprecations: bool,
+    syncs: AtomicU64,
 }
 
 impl FileExecutor {
     pub fn new(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            locks: Mutex::new(HashMap::new()),
+            edit_sessions: Arc::new(Mutex::new(HashMap::new())),
+            transactions: Arc::new(Mutex::new(HashMap::new())),
+            state: None,
+            quarantine: None,
+            consistency: WriteConsistency::default(),
+            impact_limits: None,
+            strict_deprecations: false,
+            syncs: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_write_consistency(mut self, consistency: WriteConsistency) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
+    pub fn with_impact_limits(mut self, limits: ImpactLimits) -> Self {
+        self.impact_limits = Some(limits);
+        self
+    }
+
+    // Fail tasks that use deprecated operation names instead of warning
+    pub fn with_strict_deprecations(mut self, strict: bool) -> Self {
+        self.strict_deprecations = strict;
+        self
+    }
+
+    pub fn with_state_store(mut self, store: Arc<StateStore>) -> Self {
+        self.state = Some(store);
+        self
+    }
+
+    // Relative paths are taken from base_path
+    pub fn with_quarantine_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.quarantine = Some(self.base_path.join(dir.into()));
+        self
+    }
+
+    // Stored high-water mark for `key`; params referencing state need a store
+    async fn state_mark(&self, key: &str) -> Result<Option<serde_json::Value>> {
+        let store = self.state.as_ref().ok_or_else(|| Error::InvalidConfig(
+            "This FileExecutor has no state store configured".to_string()
+        ))?;
+        store.get(key).await
     }
     
     fn resolve_path(&self, path: &str) -> Result<PathBuf> {
@@ -27,6 +155,33 @@ impl FileExecutor {
         
         Ok(self.base_path.join(path))
     }
+
+    // Create the missing directories above `path`. The nearest existing ancestor
+    // must resolve inside base_path, so a symlinked directory can't lead the new
+    // ones elsewhere. True when anything was created.
+    async fn create_parents(&self, path: &Path) -> Result<bool> {
+        let Some(parent) = path.parent() else {
+            return Ok(false);
+        };
+        let mut existing = parent;
+        while fs::symlink_metadata(existing).await.is_err() {
+            match existing.parent() {
+                Some(up) => existing = up,
+                None => break,
+            }
+        }
+        if existing == parent {
+            return Ok(false);
+        }
+        let base = fs::canonicalize(&self.base_path).await?;
+        if !fs::canonicalize(existing).await?.starts_with(&base) {
+            return Err(Error::PermissionDenied(format!(
+                "{} resolves outside the base path", existing.display()
+            )));
+        }
+        fs::create_dir_all(parent).await?;
+        Ok(true)
+    }
 }
 
 #[async_trait]
@@ -46,20 +201,100 @@ impl Executor for FileExecutor {
     
     async fn execute(&self, task: &Task) -> Result<ExecutionResult> {
         self.validate(task)?;
-        
+        let (task, warnings) = resolve(task, ALIASES, &[], self.strict_deprecations)?;
+        let task = task.as_ref();
+        if let Some(budget) = preview_budget(&task.params)? {
+            return attach_warnings(self.preview(task, budget).await?, warnings);
+        }
+
+        // Writes tagged with a transaction go to its staging area
+        let result = match self.txn_prepare(task).await? {
+            Some(staged) => {
+                let result = self.dispatch(&staged.task).await?;
+                self.txn_record(staged, result)?
+            }
+            None => self.dispatch(task).await?,
+        };
+        attach_warnings(result, warnings)
+    }
+}
+
+impl FileExecutor {
+    async fn dispatch(&self, task: &Task) -> Result<ExecutionResult> {
         match task.operation.as_str() {
             "read" => self.read_file(task).await,
+            "read_lines" => self.read_lines(task).await,
             "read_csv" => self.read_csv(task).await,
+            "read_csv_typed" => self.read_csv_typed(task).await,
             "read_json" => self.read_json(task).await,
+            "query_json" => self.query_json(task).await,
+            "read_json_stream" => self.read_json_stream(task).await,
+            "read_ndjson" => self.read_ndjson(task).await,
+            "write_ndjson" => self.write_ndjson(task).await,
+            "json_array_length" => self.json_array_length(task).await,
             "write" => self.write_file(task).await,
+            "append" => self.append_file(task).await,
+            "replace" => self.replace(task).await,
             "delete" => self.delete_file(task).await,
             "move" => self.move_file(task).await,
             "copy" => self.copy_file(task).await,
-            "list" => self.list_dir(task).await,
+            "copy_dir" => self.copy_dir(task).await,
+            "copy_large" => self.copy_large(task).await,
+            "sync_dir" => self.sync_dir(task).await,
+            "list_dir" => self.list_dir(task).await,
+            "list_detailed" => self.list_detailed(task).await,
             "write_json" => self.write_json(task).await,
+            "merge_json" => self.merge_json(task).await,
+            "apply_json_patch" => self.apply_json_patch(task).await,
+            "read_toml" => self.read_toml(task).await,
+            "write_toml" => self.write_toml(task).await,
+            "read_xml" => self.read_xml(task).await,
+            "read_yaml" => self.read_yaml(task).await,
+            "write_yaml" => self.write_yaml(task).await,
             "write_csv"  => self.write_csv(task).await,
+            "append_csv" => self.append_csv(task).await,
+            "csv_to_json" => self.csv_to_json(task).await,
+            "json_to_csv" => self.json_to_csv(task).await,
+            "concat"     => self.concat(task).await,
+            "zip" => self.zip(task).await,
+            "unzip" => self.unzip(task).await,
+            "tar" => self.tar(task).await,
+            "untar" => self.untar(task).await,
+            "concat_csv" => self.concat_csv(task).await,
             "create_dir" => self.create_dir(task).await,
+            "delete_dir" => self.delete_dir(task).await,
             "exists"     => self.exists(task).await,
+            "stat" => self.stat(task).await,
+            "touch" => self.touch(task).await,
+            "acquire_lock" => self.acquire_lock(task).await,
+            "release_lock" => self.release_lock(task).await,
+            "profile" => self.profile(task).await,
+            "distinct" => self.distinct(task).await,
+            "follow" => self.follow(task).await,
+            "compress" => self.compress(task).await,
+            "decompress" => self.decompress(task).await,
+            "edit_begin" => self.edit_begin(task).await,
+            "edit_apply" => self.edit_apply(task).await,
+            "edit_commit" => self.edit_commit(task).await,
+            "edit_abort" => self.edit_abort(task).await,
+            "quarantine" => self.quarantine(task).await,
+            "list_quarantine" => self.list_quarantine(task).await,
+            "requeue_from_quarantine" => self.requeue_from_quarantine(task).await,
+            "sanitize_filename" => self.sanitize_filename(task).await,
+            "import_existing" => self.import_existing(task).await,
+            "changes_since" => self.changes_since(task).await,
+            "changes_confirm" => self.changes_confirm(task).await,
+            "snapshot" => self.snapshot(task).await,
+            "csv_delta" => self.csv_delta(task).await,
+            "read_bytes" => self.read_bytes(task).await,
+            "write_bytes" => self.write_bytes(task).await,
+            "hash" => self.hash(task).await,
+            "diff" => self.diff(task).await,
+            "glob" => self.glob(task).await,
+            "txn_begin" => self.txn_begin(task).await,
+            "txn_commit" => self.txn_commit(task).await,
+            "txn_rollback" => self.txn_rollback(task).await,
+            "txn_recover" => self.txn_recover(task).await,
             _ => Err(Error::InvalidConfig(
                 format!("Unknown operation: {}", task.operation)
             )),
@@ -67,19 +302,55 @@ impl Executor for FileExecutor {
     }
 }
 
+const ALIASES: &[OperationAlias] = &[
+    OperationAlias { alias: "list", target: "list_dir", deprecated_since: Some("0.2.0"), transform: None },
+    OperationAlias { alias: "gzip", target: "compress", deprecated_since: None, transform: Some(gzip_codec) },
+    OperationAlias { alias: "gunzip", target: "decompress", deprecated_since: None, transform: Some(gzip_codec) },
+];
+
+// gzip/gunzip are compress/decompress pinned to the gzip codec
+fn gzip_codec(mut params: serde_json::Value) -> Result<serde_json::Value> {
+    let Some(map) = params.as_object_mut() else {
+        return Ok(params);
+    };
+    match map.get("codec").and_then(|c| c.as_str()) {
+        None | Some("gzip") => {
+            map.insert("codec".to_string(), serde_json::json!("gzip"));
+            Ok(params)
+        }
+        Some(other) => Err(Error::InvalidConfig(format!(
+            "gzip/gunzip always use the gzip codec, got '{}'; use compress/decompress", other
+        ))),
+    }
+}
+
+fn mtime_mark(metadata: &std::fs::Metadata) -> Result<serde_json::Value> {
+    let modified: chrono::DateTime<chrono::Utc> = metadata.modified()?.into();
+    Ok(serde_json::json!(modified.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)))
+}
+
 // Implementasi operations
 impl FileExecutor {
     async fn read_file(&self, task: &Task) -> Result<ExecutionResult> {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            expect_recent: Option<ExpectRecent>,
+            #[serde(default)]
+            decompress: bool,
+            codec: Option<String>,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
-        let content = fs::read_to_string(&full_path).await?;
+        if let Some(barrier) = &params.expect_recent {
+            self.await_recent(&full_path, barrier).await?;
+        }
+        let content = self
+            .read_to_string_decoded(&full_path, params.decompress, params.codec.as_deref())
+            .await?;
         
         Ok(ExecutionResult {
             success: true,
@@ -92,45 +363,80 @@ impl FileExecutor {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            #[serde(default)]
+            decompress: bool,
+            codec: Option<String>,
+            #[serde(flatten)]
+            dialect: CsvDialect,
+            // Projection by header name or zero-based index, in output order
+            columns: Option<Vec<ColumnRef>>,
+            filter: Option<RowFilter>,
+            // Paging over the rows that pass `filter`
+            #[serde(default)]
+            offset: usize,
+            limit: Option<usize>,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
-        let content = fs::read_to_string(&full_path).await?;
+        let content = self
+            .read_to_string_decoded(&full_path, params.decompress, params.codec.as_deref())
+            .await?;
         
-        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let mut reader = params.dialect.reader()?.from_reader(content.as_bytes());
         
         //Get headers
-        let headers: Vec<String> = reader
-            .headers()
-            .map_err(|e| Error::Io(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                e.to_string()
-            )))?
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+        let headers: Option<Vec<String>> = match params.dialect.has_headers {
+            true => Some(reader
+                .headers()
+                .map_err(|e| Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    e.to_string()
+                )))?
+                .iter()
+                .map(|s| s.to_string())
+                .collect()),
+            false => None,
+        };
+        let selection = Selection::new(params.columns.as_deref(), params.filter.as_ref(), headers.as_deref())?;
         
         //Get data rows (without headers)
         let mut rows = Vec::new();
+        let (mut total_rows, mut matched_rows, mut width) = (0usize, 0usize, 0usize);
         for result in reader.records() {
             let record = result.map_err(|e| Error::Io(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 e.to_string()
             )))?;
-            
-            let row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
-            rows.push(row);
+            total_rows += 1;
+            if !selection.matches(&record) {
+                continue;
+            }
+            matched_rows += 1;
+            if matched_rows <= params.offset || params.limit.is_some_and(|l| rows.len() >= l) {
+                continue;
+            }
+            width = width.max(record.len());
+            rows.push(selection.project(&record));
         }
+
+        //Headerless files get col_0, col_1, ... up to the widest returned row
+        let headers = match (headers, selection.columns()) {
+            (Some(headers), _) => selection.project_headers(headers),
+            (None, Some(columns)) => columns.iter().map(|i| format!("col_{}", i)).collect(),
+            (None, None) => dialect::synthesized_headers(width),
+        };
         
         //Return both headers and rows
         Ok(ExecutionResult {
             success: true,
             output: Some(serde_json::json!({
                 "headers": headers,
-                "rows": rows
+                "rows": rows,
+                "total_rows": total_rows,
+                "matched_rows": matched_rows,
             })),
             error: None,
         })
@@ -161,36 +467,110 @@ impl FileExecutor {
         struct Params {
             path: String,
             content: String,
+            // Write a temp file beside `path` and rename it into place
+            #[serde(default)]
+            atomic: bool,
+            #[serde(default)]
+            create_parents: bool,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
-        fs::write(&full_path, params.content.as_bytes()).await?;
+        let created_parents = params.create_parents && self.create_parents(&full_path).await?;
+        let backup = self.backup(&params.path, &params.backup).await?;
+        let bytes = match params.atomic {
+            true => AtomicWrite::begin(&full_path).await?.finish(params.content.as_bytes()).await?,
+            false => {
+                fs::write(&full_path, params.content.as_bytes()).await?;
+                params.content.len() as u64
+            }
+        };
+        self.settle(&full_path, Some(bytes)).await?;
         
         Ok(ExecutionResult {
             success: true,
-            output: Some(serde_json::json!({ "path": full_path })),
+            output: Some(serde_json::json!({
+                "path": full_path,
+                "atomic": params.atomic,
+                "created_parents": created_parents,
+                "backup": backup,
+            })),
             error: None,
         })
     }
-    
+
+    async fn append_file(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            content: String,
+            // Terminate non-empty content with '\n' if it isn't already
+            #[serde(default)]
+            newline: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let mut content = params.content;
+        if params.newline && !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&full_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.flush().await?;
+        let len = file.metadata().await?.len();
+        drop(file);
+        self.settle(&full_path, Some(len)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "path": full_path,
+                "appended_bytes": content.len(),
+                "len": len,
+            })),
+            error: None,
+        })
+    }
+
     async fn delete_file(&self, task: &Task) -> Result<ExecutionResult> {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            confirm_impact: Option<ConfirmImpact>,
+            // Report what would be removed without removing it
+            #[serde(default)]
+            dry_run: bool,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
+        if params.dry_run {
+            let impact = self.measure_impact(vec![full_path.clone()]).await?;
+            return Ok(ExecutionResult {
+                success: true,
+                output: Some(serde_json::json!({ "path": full_path, "dry_run": true, "impact": impact })),
+                error: None,
+            });
+        }
+        self.guard_impact("delete", &[&full_path], params.confirm_impact).await?;
+        let backup = self.backup(&params.path, &params.backup).await?;
         fs::remove_file(&full_path).await?;
+        self.settle_removed(&full_path).await?;
         
         Ok(ExecutionResult {
             success: true,
-            output: None,
+            output: Some(serde_json::json!({ "path": full_path, "backup": backup })),
             error: None,
         })
     }
@@ -200,6 +580,8 @@ impl FileExecutor {
         struct Params {
             from: String,
             to: String,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
@@ -207,14 +589,17 @@ impl FileExecutor {
     
     let from_path = self.resolve_path(&params.from)?;
     let to_path = self.resolve_path(&params.to)?;
+    let backup = self.backup(&params.to, &params.backup).await?;
     
-    fs::copy(&from_path, &to_path).await?;
+    let copied = fs::copy(&from_path, &to_path).await?;
+    self.settle(&to_path, Some(copied)).await?;
     
     Ok(ExecutionResult {
         success: true,
         output: Some(serde_json::json!({
             "from": from_path,
-            "to": to_path
+            "to": to_path,
+            "backup": backup,
         })),
         error: None,
     })
@@ -225,6 +610,8 @@ impl FileExecutor {
         struct Params {
             from: String,
             to: String,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
 
         let params:Params = serde_json::from_value(task.params.clone())
@@ -232,14 +619,18 @@ impl FileExecutor {
 
         let from_path = self.resolve_path(&params.from)?;
         let to_path = self.resolve_path(&params.to)?;
+        let backup = self.backup(&params.to, &params.backup).await?;
 
         fs::rename(&from_path, &to_path).await?;
+        self.settle(&to_path, None).await?;
+        self.settle_removed(&from_path).await?;
 
         Ok(ExecutionResult {
             success: true,
             output: Some(serde_json::json!({
                 "from": from_path,
-                "to": to_path
+                "to": to_path,
+                "backup": backup,
             })),
             error: None,
         })
@@ -249,22 +640,47 @@ impl FileExecutor {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            newer_than_state: Option<String>,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
+        let mark = match &params.newer_than_state {
+            Some(key) => Some(self.state_mark(key).await?),
+            None => None,
+        };
         let mut entries = fs::read_dir(&full_path).await?;
         
         let mut files = Vec::new();
+        let mut high_water_mark = mark.clone().flatten();
+        let internal = self.internal_paths();
         while let Some(entry) = entries.next_entry().await? {
+            if internal.contains(&entry.path()) {
+                continue;
+            }
+            if let Some(mark) = &mark {
+                let modified = mtime_mark(&entry.metadata().await?)?;
+                if mark.as_ref().is_some_and(|m| compare_marks(&modified, m) != Some(std::cmp::Ordering::Greater)) {
+                    continue;
+                }
+                if high_water_mark.as_ref().is_none_or(|h| compare_marks(&modified, h) == Some(std::cmp::Ordering::Greater)) {
+                    high_water_mark = Some(modified);
+                }
+            }
             files.push(entry.file_name().to_string_lossy().to_string());
         }
         
+        let mut output = serde_json::json!({ "files": files });
+        if mark.is_some() {
+            // Newest mtime seen; store it with set_state once the files are processed
+            output["high_water_mark"] = serde_json::json!(high_water_mark);
+        }
+        
         Ok(ExecutionResult {
             success: true,
-            output: Some(serde_json::json!({ "files": files })),
+            output: Some(output),
             error: None,
         })
     }
@@ -274,18 +690,42 @@ impl FileExecutor {
         struct Params {
             path: String,
             data: serde_json::Value,
+            #[serde(default)]
+            atomic: bool,
+            #[serde(default)]
+            create_parents: bool,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
+        let created_parents = params.create_parents && self.create_parents(&full_path).await?;
+        let backup = self.backup(&params.path, &params.backup).await?;
+        let staged = match params.atomic {
+            true => Some(AtomicWrite::begin(&full_path).await?),
+            false => None,
+        };
         let json_string = serde_json::to_string_pretty(&params.data)?;
-        fs::write(&full_path, json_string.as_bytes()).await?;
+        let bytes = match staged {
+            Some(staged) => staged.finish(json_string.as_bytes()).await?,
+            None => {
+                fs::write(&full_path, json_string.as_bytes()).await?;
+                json_string.len() as u64
+            }
+        };
+        self.settle(&full_path, Some(bytes)).await?;
         
         Ok(ExecutionResult {
             success: true,
-            output: Some(serde_json::json!({ "path": full_path })),
+            output: Some(serde_json::json!({
+                "path": full_path,
+                "atomic": params.atomic,
+                "created_parents": created_parents,
+                "backup": backup,
+            })),
             error: None,
         })
     }
@@ -294,23 +734,109 @@ impl FileExecutor {
         #[derive(Deserialize)]
         struct Params {
             path: String,
-            headers: Vec<String>,
-            rows: Vec<Vec<String>>,
+            // Required unless `has_headers` is false
+            headers: Option<Vec<String>>,
+            rows: Vec<Vec<serde_json::Value>>,
+            #[serde(flatten)]
+            format: FormatParams,
+            #[serde(flatten)]
+            dialect: CsvDialect,
+            // Checkpoint progress to `<path>.progress.json` and continue from it
+            #[serde(default)]
+            resume: bool,
+            #[serde(default = "default_checkpoint_rows")]
+            checkpoint_rows: usize,
+            #[serde(default)]
+            rows_offset: u64,
+            #[serde(default = "default_finalize")]
+            finalize: bool,
+            expected_total_rows: Option<u64>,
+            #[serde(default)]
+            atomic: bool,
+            #[serde(default)]
+            create_parents: bool,
+            #[serde(flatten)]
+            backup: BackupParams,
         }
+
+        fn default_checkpoint_rows() -> usize { 1000 }
+        fn default_finalize() -> bool { true }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
+        let formatter = ValueFormatter::from_params(&params.format)?;
+        let headers = match (params.dialect.has_headers, params.headers) {
+            (true, Some(headers)) => headers,
+            (true, None) => return Err(Error::InvalidConfig(
+                "headers is required unless has_headers is false".to_string()
+            )),
+            (false, _) => Vec::new(),
+        };
+        let builder = params.dialect.writer()?;
+        if params.resume && params.atomic {
+            return Err(Error::InvalidConfig(
+                "atomic cannot be combined with resume, which continues a partial file".to_string()
+            ));
+        }
+        if params.resume && params.backup.backup {
+            return Err(Error::InvalidConfig(
+                "backup cannot be combined with resume, which continues a partial file".to_string()
+            ));
+        }
+
+        let created_parents = params.create_parents && self.create_parents(&full_path).await?;
+
+        if params.resume {
+            let rows: Vec<Vec<String>> = params.rows.iter()
+                .map(|row| row.iter().map(|v| formatter.render(v)).collect())
+                .collect();
+            let options = ResumeOptions {
+                checkpoint_rows: params.checkpoint_rows,
+                rows_offset: params.rows_offset,
+                finalize: params.finalize,
+                expected_total_rows: params.expected_total_rows,
+            };
+            let path = full_path.clone();
+            let dialect = params.dialect;
+            let outcome = tokio::task::spawn_blocking(move || {
+                resumable::write_csv_resumable(&path, &headers, &rows, &dialect, &options)
+            })
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+            self.settle(&full_path, None).await?;
+
+            return Ok(ExecutionResult {
+                success: true,
+                output: Some(serde_json::json!({
+                    "path": full_path,
+                    "rows_written": outcome.rows_written,
+                    "total_rows": outcome.total_rows,
+                    "resumed_from": outcome.resumed_from,
+                    "complete": params.finalize,
+                    "created_parents": created_parents,
+                })),
+                error: None,
+            });
+        }
         
-        let mut wtr = csv::Writer::from_writer(vec![]);
-        wtr.write_record(&params.headers)
-            .map_err(|e| Error::Io(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                e.to_string()
-            )))?;
+        let backup = self.backup(&params.path, &params.backup).await?;
+        let staged = match params.atomic {
+            true => Some(AtomicWrite::begin(&full_path).await?),
+            false => None,
+        };
+        let mut wtr = builder.from_writer(vec![]);
+        if !headers.is_empty() {
+            wtr.write_record(&headers)
+                .map_err(|e| Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    e.to_string()
+                )))?;
+        }
         
         for row in params.rows {
+            let row: Vec<String> = row.iter().map(|v| formatter.render(v)).collect();
             wtr.write_record(&row)
                 .map_err(|e| Error::Io(std::io::Error::new(
                     std::io::ErrorKind::InvalidData,
@@ -324,11 +850,24 @@ impl FileExecutor {
                 e.to_string()
             )))?;
         
-        fs::write(&full_path, data).await?;
+        let len = match staged {
+            Some(staged) => staged.finish(&data).await?,
+            None => {
+                let len = data.len() as u64;
+                fs::write(&full_path, data).await?;
+                len
+            }
+        };
+        self.settle(&full_path, Some(len)).await?;
         
         Ok(ExecutionResult {
             success: true,
-            output: Some(serde_json::json!({ "path": full_path })),
+            output: Some(serde_json::json!({
+                "path": full_path,
+                "atomic": params.atomic,
+                "created_parents": created_parents,
+                "backup": backup,
+            })),
             error: None,
         })
     }
@@ -344,6 +883,7 @@ impl FileExecutor {
         
         let full_path = self.resolve_path(&params.path)?;
         fs::create_dir_all(&full_path).await?;
+        self.settle(&full_path, None).await?;
         
         Ok(ExecutionResult {
             success: true,
@@ -352,16 +892,117 @@ impl FileExecutor {
         })
     }
     
+    async fn delete_dir(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            recursive: bool,
+            #[serde(default)]
+            ignore_missing: bool,
+            confirm_impact: Option<ConfirmImpact>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let is_base = match (fs::canonicalize(&full_path).await, fs::canonicalize(&self.base_path).await) {
+            (Ok(path), Ok(base)) => path == base,
+            _ => full_path == self.base_path,
+        };
+        if is_base {
+            return Err(Error::PermissionDenied("Refusing to delete the base path".to_string()));
+        }
+
+        let metadata = match fs::symlink_metadata(&full_path).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && params.ignore_missing => {
+                return Ok(ExecutionResult {
+                    success: true,
+                    output: Some(serde_json::json!({ "path": full_path, "deleted": false })),
+                    error: None,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if !metadata.is_dir() {
+            return Err(Error::InvalidConfig(format!(
+                "{} is not a directory; use delete for files", params.path
+            )));
+        }
+
+        if params.recursive {
+            self.guard_impact("delete_dir", &[&full_path], params.confirm_impact).await?;
+            fs::remove_dir_all(&full_path).await?;
+        } else {
+            match fs::remove_dir(&full_path).await {
+                Err(e) if e.kind() == std::io::ErrorKind::DirectoryNotEmpty => {
+                    return Err(Error::InvalidConfig(format!(
+                        "Directory {} is not empty; pass recursive: true to delete its contents", params.path
+                    )));
+                }
+                result => result?,
+            }
+        }
+        self.settle_removed(&full_path).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "path": full_path, "deleted": true })),
+            error: None,
+        })
+    }
+
+    // Create an empty file, or bump the mtime of an existing one
+    async fn touch(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            create_parents: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        if params.create_parents {
+            self.create_parents(&full_path).await?;
+        }
+        let target = full_path.clone();
+        let created = tokio::task::spawn_blocking(move || -> std::io::Result<bool> {
+            let created = !target.exists();
+            let file = std::fs::OpenOptions::new().append(true).create(true).open(&target)?;
+            let now = std::time::SystemTime::now();
+            file.set_times(std::fs::FileTimes::new().set_accessed(now).set_modified(now))?;
+            Ok(created)
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&full_path, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "path": full_path, "created": created })),
+            error: None,
+        })
+    }
+
     async fn exists(&self, task: &Task) -> Result<ExecutionResult> {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            expect_recent: Option<ExpectRecent>,
         }
         
         let params: Params = serde_json::from_value(task.params.clone())
             .map_err(|e| Error::InvalidConfig(e.to_string()))?;
         
         let full_path = self.resolve_path(&params.path)?;
+        if let Some(barrier) = &params.expect_recent {
+            self.await_recent(&full_path, barrier).await?;
+        }
         let exists = full_path.exists();
         
         Ok(ExecutionResult {
@@ -370,4 +1011,49 @@ impl FileExecutor {
             error: None,
         })
     }
-}
\ No newline at end of file
+
+    async fn sanitize_filename(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: Option<String>,
+            names: Option<Vec<String>>,
+            #[serde(flatten)]
+            options: SanitizeOptions,
+        }
+        
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        params.options.validate().map_err(Error::InvalidConfig)?;
+        
+        let (names, single) = match (params.name, params.names) {
+            (Some(name), None) => (vec![name], true),
+            (None, Some(names)) => (names, false),
+            _ => return Err(Error::InvalidConfig(
+                "Provide exactly one of 'name' or 'names'".to_string()
+            )),
+        };
+        
+        let results: Vec<serde_json::Value> = sanitize_filenames(&names, &params.options)
+            .into_iter()
+            .zip(&names)
+            .map(|((output, collided), input)| serde_json::json!({
+                "input": input,
+                "output": output,
+                "changed": output != *input,
+                "collided": collided,
+            }))
+            .collect();
+        
+        let output = if single {
+            results.into_iter().next().unwrap_or_default()
+        } else {
+            serde_json::json!({ "results": results })
+        };
+        
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
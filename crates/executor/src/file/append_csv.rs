@@ -0,0 +1,111 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use super::format::{FormatParams, ValueFormatter};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+fn csv_error(e: csv::Error) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+}
+
+struct Appended {
+    headers_written: bool,
+    len: u64,
+}
+
+// Only the header row of an existing file is read; the rest is never touched
+fn append(path: PathBuf, display: String, headers: Option<Vec<String>>, rows: Vec<Vec<String>>) -> Result<Appended> {
+    let existing_len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if existing_len == 0 && headers.is_none() {
+        return Err(Error::InvalidConfig(format!(
+            "{} does not exist yet; pass 'headers' to create it", display
+        )));
+    }
+    let mut file = std::fs::OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+
+    let (columns, headers_written, needs_newline) = if existing_len == 0 {
+        (headers.as_ref().map_or(0, Vec::len), true, false)
+    } else {
+        let existing = csv::Reader::from_reader(&file).headers().map_err(csv_error)?.clone();
+        if let Some(headers) = &headers {
+            if !headers.iter().eq(existing.iter()) {
+                return Err(Error::InvalidConfig(format!(
+                    "headers {:?} do not match the existing header of {}: {:?}",
+                    headers, display, existing.iter().collect::<Vec<_>>()
+                )));
+            }
+        }
+        let mut last = [0u8];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+        (existing.len(), false, last[0] != b'\n')
+    };
+
+    if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != columns) {
+        return Err(Error::InvalidConfig(format!(
+            "Row {} has {} columns but {} has {}", i, row.len(), display, columns
+        )));
+    }
+
+    let prefix = match needs_newline {
+        true => vec![b'\n'],
+        false => Vec::new(),
+    };
+    let mut writer = csv::Writer::from_writer(prefix);
+    if headers_written {
+        writer.write_record(headers.iter().flatten()).map_err(csv_error)?;
+    }
+    for row in &rows {
+        writer.write_record(row).map_err(csv_error)?;
+    }
+    let data = writer.into_inner().map_err(|e| Error::Io(std::io::Error::other(e.to_string())))?;
+    file.write_all(&data)?;
+    file.flush()?;
+
+    Ok(Appended { headers_written, len: existing_len + data.len() as u64 })
+}
+
+impl FileExecutor {
+    // Append records to a CSV, creating it with `headers` when it is missing or empty
+    pub(super) async fn append_csv(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            headers: Option<Vec<String>>,
+            rows: Vec<Vec<serde_json::Value>>,
+            #[serde(flatten)]
+            format: FormatParams,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let formatter = ValueFormatter::from_params(&params.format)?;
+        let full_path = self.resolve_path(&params.path)?;
+        let rows: Vec<Vec<String>> = params.rows.iter()
+            .map(|row| row.iter().map(|v| formatter.render(v)).collect())
+            .collect();
+        let rows_appended = rows.len();
+
+        let (path, display) = (full_path.clone(), params.path.clone());
+        let appended = tokio::task::spawn_blocking(move || append(path, display, params.headers, rows))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&full_path, Some(appended.len)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "rows_appended": rows_appended,
+                "headers_written": appended.headers_written,
+                "len": appended.len,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use local_automation_common::{Error, Result};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+// A write that lands in a hidden temp file next to `target` and is renamed
+// over it only once complete, so readers see the old file or the new one and
+// never a torn one. Dropping it before `finish` removes the temp file.
+pub(super) struct AtomicWrite {
+    target: PathBuf,
+    temp: PathBuf,
+    file: Option<fs::File>,
+}
+
+impl AtomicWrite {
+    pub async fn begin(target: &Path) -> Result<Self> {
+        let name = target.file_name().ok_or_else(|| Error::InvalidConfig(format!(
+            "{} does not name a file", target.display()
+        )))?;
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+        let temp = target.with_file_name(temp_name);
+        let file = fs::OpenOptions::new().write(true).create_new(true).open(&temp).await?;
+        Ok(Self { target: target.to_path_buf(), temp, file: Some(file) })
+    }
+
+    // Existing files keep their permissions across the replacement
+    pub async fn finish(mut self, data: &[u8]) -> Result<u64> {
+        let mut file = self.file.take().expect("file is only taken by finish");
+        file.write_all(data).await?;
+        file.sync_all().await?;
+        drop(file);
+        if let Ok(metadata) = fs::metadata(&self.target).await {
+            fs::set_permissions(&self.temp, metadata.permissions()).await?;
+        }
+        fs::rename(&self.temp, &self.target).await?;
+        // Nothing left to clean up
+        self.temp = PathBuf::new();
+        Ok(data.len() as u64)
+    }
+}
+
+impl Drop for AtomicWrite {
+    fn drop(&mut self) {
+        self.file.take();
+        if !self.temp.as_os_str().is_empty() {
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
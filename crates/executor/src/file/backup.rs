@@ -0,0 +1,116 @@
+use chrono::Utc;
+use local_automation_common::{Error, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use super::FileExecutor;
+
+// Copy-before-overwrite settings accepted by the destructive operations.
+// `backup_suffix` is appended to the file name; a `{timestamp}` in it expands to
+// the current UTC time, giving every run its own backup.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct BackupParams {
+    #[serde(default)]
+    pub backup: bool,
+    pub backup_suffix: Option<String>,
+    // Backups to retain: older ones are rotated out instead of being clobbered
+    pub keep: Option<usize>,
+}
+
+const TIMESTAMP: &str = "{timestamp}";
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+// Matches what STAMP_FORMAT renders, e.g. 20260102T030405678Z
+fn is_stamp(s: &str) -> bool {
+    s.len() == 19 && s.char_indices().all(|(i, c)| match i {
+        8 => c == 'T',
+        18 => c == 'Z',
+        _ => c.is_ascii_digit(),
+    })
+}
+
+impl FileExecutor {
+    // Copy the file at `path` aside before it is replaced or removed. None when
+    // backups are off or there is no file there yet.
+    pub(super) async fn backup(&self, path: &str, params: &BackupParams) -> Result<Option<PathBuf>> {
+        if !params.backup {
+            return Ok(None);
+        }
+        let suffix = params.backup_suffix.as_deref().unwrap_or(".bak");
+        if suffix.is_empty() || suffix.contains(['/', '\\']) {
+            return Err(Error::InvalidConfig(format!(
+                "backup_suffix must be a non-empty file name suffix, got '{}'", suffix
+            )));
+        }
+        if params.keep == Some(0) {
+            return Err(Error::InvalidConfig("keep must be at least 1".to_string()));
+        }
+
+        let target = self.resolve_path(path)?;
+        match fs::metadata(&target).await {
+            Ok(metadata) if metadata.is_file() => {}
+            Ok(_) => return Err(Error::InvalidConfig(format!(
+                "{} is not a file; only files can be backed up", path
+            ))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let backup = match suffix.split_once(TIMESTAMP) {
+            Some((before, after)) => {
+                let stamp = Utc::now().format(STAMP_FORMAT);
+                let backup = self.resolve_path(&format!("{}{}{}{}", path, before, stamp, after))?;
+                fs::copy(&target, &backup).await?;
+                if let Some(keep) = params.keep {
+                    self.prune_backups(&target, before, after, keep).await?;
+                }
+                backup
+            }
+            None => {
+                let numbered = |i: usize| match i {
+                    0 => self.resolve_path(&format!("{}{}", path, suffix)),
+                    i => self.resolve_path(&format!("{}{}.{}", path, suffix, i)),
+                };
+                // <name>.bak -> <name>.bak.1 -> ... the oldest falls off the end
+                for i in (1..params.keep.unwrap_or(1)).rev() {
+                    match fs::rename(numbered(i - 1)?, numbered(i)?).await {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                let backup = numbered(0)?;
+                fs::copy(&target, &backup).await?;
+                backup
+            }
+        };
+        self.settle(&backup, None).await?;
+        Ok(Some(backup))
+    }
+
+    // Timestamped backups sort by age, so the newest `keep` are the last names
+    async fn prune_backups(&self, target: &Path, before: &str, after: &str, keep: usize) -> Result<()> {
+        let (Some(dir), Some(name)) = (target.parent(), target.file_name()) else {
+            return Ok(());
+        };
+        let prefix = format!("{}{}", name.to_string_lossy(), before);
+        let mut backups = Vec::new();
+        let mut entries = fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let stamp = file_name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(after));
+            if stamp.is_some_and(is_stamp) {
+                backups.push(file_name);
+            }
+        }
+        backups.sort();
+        let excess = backups.len().saturating_sub(keep);
+        for file_name in &backups[..excess] {
+            let path = dir.join(file_name);
+            fs::remove_file(&path).await?;
+            self.settle_removed(&path).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// Binary content crosses the task boundary as standard (padded) base64
+impl FileExecutor {
+    pub(super) async fn read_bytes(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            offset: u64,
+            // Defaults to the rest of the file
+            length: Option<u64>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let mut file = fs::File::open(&full_path).await?;
+        let size = file.metadata().await?.len();
+        let offset = params.offset.min(size);
+        let length = params.length.unwrap_or(size).min(size - offset);
+
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        let mut content = vec![0u8; length as usize];
+        file.read_exact(&mut content).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "content": STANDARD.encode(&content),
+                "bytes": content.len(),
+                "offset": offset,
+                "size": size,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn write_bytes(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            content: String,
+            #[serde(default)]
+            create_parents: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let content = STANDARD.decode(params.content.trim())
+            .map_err(|e| Error::InvalidConfig(format!("content is not valid base64: {}", e)))?;
+        let full_path = self.resolve_path(&params.path)?;
+        let created_parents = params.create_parents && self.create_parents(&full_path).await?;
+        fs::write(&full_path, &content).await?;
+        self.settle(&full_path, Some(content.len() as u64)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": full_path,
+                "bytes": content.len(),
+                "created_parents": created_parents,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,247 @@
+use globset::{Glob, GlobMatcher};
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use super::glob::relative_string;
+use super::internal::InternalPaths;
+use super::{mtime_mark, FileExecutor};
+use crate::traits::ExecutionResult;
+
+const DEFAULT_MAX_CHANGES: usize = 10_000;
+
+// Size and mtime mark per relative path
+type Index = BTreeMap<String, (u64, serde_json::Value)>;
+
+// What `changes_since` keeps under its state key: the index as of the last
+// confirmed scan, and the latest scan waiting for confirmation
+#[derive(Default, Serialize, Deserialize)]
+struct ChangeState {
+    filter: Option<String>,
+    confirmed: Index,
+    pending: Option<PendingScan>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PendingScan {
+    scan_id: String,
+    index: Index,
+}
+
+fn scan(root: PathBuf, internal: InternalPaths, filter: Option<GlobMatcher>) -> Result<Index> {
+    let mut index = Index::new();
+    let walker = WalkDir::new(&root)
+        .follow_links(false)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| !internal.contains(e.path()));
+    for entry in walker {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Some(relative) = relative_string(&root, entry.path()) else {
+            continue;
+        };
+        if filter.as_ref().is_some_and(|f| !f.is_match(&relative)) {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|e| Error::Io(e.into()))?;
+        index.insert(relative, (metadata.len(), mtime_mark(&metadata)?));
+    }
+    Ok(index)
+}
+
+impl FileExecutor {
+    async fn load_change_state(&self, key: &str) -> Result<ChangeState> {
+        match self.state_mark(key).await? {
+            Some(value) => serde_json::from_value(value).map_err(|e| Error::InvalidConfig(format!(
+                "State key '{}' does not hold a change index: {}", key, e
+            ))),
+            None => Ok(ChangeState::default()),
+        }
+    }
+
+    // Files created, modified or deleted under `path` since the last confirmed
+    // scan. Nothing is recorded as seen until `changes_confirm`, so a failed
+    // run sees the same changes again. Large change sets come in pages of
+    // `max_changes` from one consistent scan, continued with `cursor`.
+    pub(super) async fn changes_since(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            state_key: String,
+            // Glob on paths relative to `path`; fixed per state key
+            filter: Option<String>,
+            #[serde(default = "default_max_changes")]
+            max_changes: usize,
+            cursor: Option<String>,
+        }
+
+        fn default_max_changes() -> usize { DEFAULT_MAX_CHANGES }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.max_changes == 0 {
+            return Err(Error::InvalidConfig("max_changes must be at least 1".to_string()));
+        }
+        let store = self.state.clone().ok_or_else(|| Error::InvalidConfig(
+            "This FileExecutor has no state store configured".to_string()
+        ))?;
+        let mut state = self.load_change_state(&params.state_key).await?;
+        if !state.confirmed.is_empty() && state.filter != params.filter {
+            return Err(Error::InvalidConfig(format!(
+                "State key '{}' was built with filter {:?}; use a new key for a different filter",
+                params.state_key, state.filter
+            )));
+        }
+
+        let (scan_id, after) = match &params.cursor {
+            Some(cursor) => {
+                let (scan_id, after) = cursor.split_once('/').ok_or_else(|| {
+                    Error::InvalidConfig(format!("Invalid cursor: {}", cursor))
+                })?;
+                if state.pending.as_ref().is_none_or(|p| p.scan_id != scan_id) {
+                    return Err(Error::InvalidConfig(
+                        "Cursor belongs to a scan that is no longer pending; start over without a cursor".to_string()
+                    ));
+                }
+                (scan_id.to_string(), Some(after.to_string()))
+            }
+            None => {
+                let filter = match &params.filter {
+                    Some(pattern) => Some(
+                        Glob::new(pattern)
+                            .map_err(|e| Error::InvalidConfig(format!("Invalid filter: {}", e)))?
+                            .compile_matcher(),
+                    ),
+                    None => None,
+                };
+                let root = self.resolve_path(&params.path)?;
+                let internal = self.internal_paths();
+                let index = tokio::task::spawn_blocking(move || scan(root, internal, filter))
+                    .await
+                    .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+                let scan_id = uuid::Uuid::new_v4().to_string();
+                state.filter = params.filter.clone();
+                state.pending = Some(PendingScan { scan_id: scan_id.clone(), index });
+                store.set(&params.state_key, serde_json::to_value(&state)?).await?;
+                (scan_id, None)
+            }
+        };
+
+        let pending = &state.pending.as_ref().expect("pending scan recorded above").index;
+        let paths = state.confirmed.keys().chain(pending.keys())
+            .filter(|p| after.as_ref().is_none_or(|a| p.as_str() > a.as_str()))
+            .collect::<std::collections::BTreeSet<_>>();
+        let (mut created, mut modified, mut deleted) = (Vec::new(), Vec::new(), Vec::new());
+        let mut returned = 0;
+        let mut last = None;
+        let mut truncated = false;
+        for path in paths {
+            let bucket = match (state.confirmed.get(path), pending.get(path)) {
+                (None, Some(_)) => &mut created,
+                (Some(_), None) => &mut deleted,
+                (Some(old), Some(new)) if old != new => &mut modified,
+                _ => continue,
+            };
+            if returned == params.max_changes {
+                truncated = true;
+                break;
+            }
+            bucket.push(path.clone());
+            returned += 1;
+            last = Some(path.clone());
+        }
+
+        // Confirming up to `through` records everything in this page and before it
+        let through = match truncated {
+            true => last.clone(),
+            false => None,
+        };
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "backend": "index",
+                "scan_id": scan_id,
+                "created": created,
+                "modified": modified,
+                "deleted": deleted,
+                "truncated": truncated,
+                "through": through,
+                "next_cursor": through.as_ref().map(|last| format!("{}/{}", scan_id, last)),
+            })),
+            error: None,
+        })
+    }
+
+    // Record a pending scan as seen, entirely or up to and including `through`
+    pub(super) async fn changes_confirm(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            state_key: String,
+            scan_id: String,
+            through: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let store = self.state.clone().ok_or_else(|| Error::InvalidConfig(
+            "This FileExecutor has no state store configured".to_string()
+        ))?;
+        let (key, scan_id, through) = (params.state_key.clone(), params.scan_id, params.through);
+        let (applied, complete) = store.transact(&params.state_key, move |current| {
+            let mut state: ChangeState = match current {
+                Some(value) => serde_json::from_value(value.clone()).map_err(|e| Error::InvalidConfig(format!(
+                    "State key '{}' does not hold a change index: {}", key, e
+                )))?,
+                None => ChangeState::default(),
+            };
+            let Some(pending) = state.pending.take().filter(|p| p.scan_id == scan_id) else {
+                return Err(Error::InvalidConfig(format!(
+                    "Scan {} is not pending for '{}'", scan_id, key
+                )));
+            };
+
+            let in_range = |path: &String| through.as_ref().is_none_or(|t| path <= t);
+            let mut applied = 0u64;
+            let paths: Vec<String> = state.confirmed.keys().chain(pending.index.keys())
+                .filter(|p| in_range(p))
+                .cloned()
+                .collect();
+            for path in paths {
+                let before = state.confirmed.get(&path).cloned();
+                match pending.index.get(&path) {
+                    Some(entry) => state.confirmed.insert(path.clone(), entry.clone()),
+                    None => state.confirmed.remove(&path),
+                };
+                if state.confirmed.get(&path) != before.as_ref() {
+                    applied += 1;
+                }
+            }
+            // A partial confirmation keeps the scan so later pages can follow
+            let complete = through.is_none();
+            if !complete {
+                state.pending = Some(pending);
+            }
+            Ok((Some(serde_json::to_value(&state)?), (applied, complete)))
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "state_key": params.state_key,
+                "applied": applied,
+                "complete": complete,
+            })),
+            error: None,
+        })
+    }
+}
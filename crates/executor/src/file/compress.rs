@@ -0,0 +1,242 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+use super::FileExecutor;
+use crate::codec::{self, Codec, CodecReader};
+use crate::traits::ExecutionResult;
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+// Pick the codec for an existing file and make sure its content really is in
+// that format, so decoding never produces garbage.
+pub(super) fn resolve_codec(name: Option<&str>, path: &Path, header: &[u8]) -> Result<Option<&'static dyn Codec>> {
+    let codec = codec::detect(name, path, header).map_err(Error::InvalidConfig)?;
+    if let Some(codec) = codec {
+        if !header.starts_with(codec.magic()) {
+            return Err(invalid_data(format!("{} is not {} data", path.display(), codec.name())));
+        }
+    }
+    Ok(codec)
+}
+
+impl FileExecutor {
+    // Open `path` for streaming reads. With `decompress`, compressed content is
+    // decoded on the fly and plain files are read as they are.
+    pub(super) async fn open_reader(
+        &self,
+        path: &Path,
+        decompress: bool,
+        codec_name: Option<&str>,
+    ) -> Result<(CodecReader, Option<&'static str>)> {
+        let mut source = BufReader::new(fs::File::open(path).await?);
+        if !decompress {
+            return Ok((Box::pin(source), None));
+        }
+        let header = source.fill_buf().await?;
+        let header = header[..header.len().min(codec::SNIFF_LEN)].to_vec();
+        match resolve_codec(codec_name, path, &header)? {
+            Some(codec) => Ok((codec.decoder(Box::pin(source)), Some(codec.name()))),
+            None => Ok((Box::pin(source), None)),
+        }
+    }
+
+    pub(super) async fn read_to_string_decoded(
+        &self,
+        path: &Path,
+        decompress: bool,
+        codec_name: Option<&str>,
+    ) -> Result<String> {
+        let (mut reader, codec) = self.open_reader(path, decompress, codec_name).await?;
+        let mut content = String::new();
+        reader.read_to_string(&mut content).await.map_err(|e| match codec {
+            Some(codec) => invalid_data(format!("Failed to decode {} as {}: {}", path.display(), codec, e)),
+            None => Error::Io(e),
+        })?;
+        Ok(content)
+    }
+
+    pub(super) async fn compress(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            dest: Option<String>,
+            codec: Option<String>,
+            level: Option<i32>,
+            #[serde(default)]
+            delete_source: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        // Explicit codec, else the one dest's extension names, else gzip
+        let codec = match (&params.codec, &params.dest) {
+            (Some(name), _) => codec::by_name(name),
+            (None, Some(dest)) => codec::from_extension(Path::new(dest)).or(codec::by_name("gzip")),
+            (None, None) => codec::by_name("gzip"),
+        }
+        .ok_or_else(|| Error::InvalidConfig(format!(
+            "Unknown codec (available: {})", codec::available().join(", ")
+        )))?;
+        if let Some(level) = params.level {
+            codec.check_level(level).map_err(Error::InvalidConfig)?;
+        }
+        self.compress_with(codec, &params.path, params.dest.as_deref(), params.level, params.delete_source)
+            .await
+    }
+
+    pub(super) async fn compress_with(
+        &self,
+        codec: &'static dyn Codec,
+        path: &str,
+        dest: Option<&str>,
+        level: Option<i32>,
+        delete_source: bool,
+    ) -> Result<ExecutionResult> {
+        let source_path = self.resolve_path(path)?;
+        let dest_path = match dest {
+            Some(dest) => self.resolve_path(dest)?,
+            None => {
+                let mut name = source_path.as_os_str().to_os_string();
+                name.push(".");
+                name.push(codec.extension());
+                PathBuf::from(name)
+            }
+        };
+        if dest_path == source_path {
+            return Err(Error::InvalidConfig("dest must differ from path".to_string()));
+        }
+
+        let mut source = fs::File::open(&source_path).await?;
+        let out = BufWriter::new(fs::File::create(&dest_path).await?);
+        let mut encoder = codec.encoder(Box::pin(out), level);
+        let copied = async {
+            let n = tokio::io::copy(&mut source, &mut encoder).await?;
+            encoder.shutdown().await?;
+            Ok::<_, std::io::Error>(n)
+        }
+        .await;
+        drop(encoder);
+        // Never leave a truncated archive behind
+        let original_bytes = match copied {
+            Ok(n) => n,
+            Err(e) => {
+                let _ = fs::remove_file(&dest_path).await;
+                return Err(e.into());
+            }
+        };
+
+        let compressed_bytes = fs::metadata(&dest_path).await?.len();
+        self.settle(&dest_path, Some(compressed_bytes)).await?;
+        if delete_source {
+            fs::remove_file(&source_path).await?;
+            self.settle_removed(&source_path).await?;
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "path": source_path,
+                "dest": dest_path,
+                "codec": codec.name(),
+                "original_bytes": original_bytes,
+                "compressed_bytes": compressed_bytes,
+                "deleted_source": delete_source,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn decompress(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            dest: Option<String>,
+            codec: Option<String>,
+            #[serde(default)]
+            delete_source: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        self.decompress_with(
+            params.codec.as_deref(),
+            &params.path,
+            params.dest.as_deref(),
+            params.delete_source,
+        )
+        .await
+    }
+
+    pub(super) async fn decompress_with(
+        &self,
+        codec_name: Option<&str>,
+        path: &str,
+        dest: Option<&str>,
+        delete_source: bool,
+    ) -> Result<ExecutionResult> {
+        let source_path = self.resolve_path(path)?;
+        let mut source = BufReader::new(fs::File::open(&source_path).await?);
+        let header = source.fill_buf().await?;
+        let header = header[..header.len().min(codec::SNIFF_LEN)].to_vec();
+        let codec = resolve_codec(codec_name, &source_path, &header)?.ok_or_else(|| {
+            invalid_data(format!("{} is not a recognized compressed file", source_path.display()))
+        })?;
+
+        let dest_path = match dest {
+            Some(dest) => self.resolve_path(dest)?,
+            None => {
+                let stripped = source_path.extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case(codec.extension()));
+                if !stripped {
+                    return Err(Error::InvalidConfig(format!(
+                        "{} has no .{} suffix to strip; pass dest", path, codec.extension()
+                    )));
+                }
+                source_path.with_extension("")
+            }
+        };
+
+        let compressed_bytes = fs::metadata(&source_path).await?.len();
+        let mut decoder = codec.decoder(Box::pin(source));
+        let mut out = BufWriter::new(fs::File::create(&dest_path).await?);
+        let copied = tokio::io::copy(&mut decoder, &mut out).await;
+        let original_bytes = match copied {
+            Ok(n) => n,
+            Err(e) => {
+                drop(out);
+                let _ = fs::remove_file(&dest_path).await;
+                return Err(invalid_data(format!(
+                    "Failed to decode {} as {}: {}", source_path.display(), codec.name(), e
+                )));
+            }
+        };
+        out.flush().await?;
+        drop(out);
+
+        self.settle(&dest_path, Some(original_bytes)).await?;
+        if delete_source {
+            fs::remove_file(&source_path).await?;
+            self.settle_removed(&source_path).await?;
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "path": source_path,
+                "dest": dest_path,
+                "codec": codec.name(),
+                "original_bytes": original_bytes,
+                "compressed_bytes": compressed_bytes,
+                "deleted_source": delete_source,
+            })),
+            error: None,
+        })
+    }
+}
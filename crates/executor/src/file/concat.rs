@@ -0,0 +1,211 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HeaderMode {
+    // Every source must carry exactly the first source's header
+    #[default]
+    Strict,
+    // Union of all columns by name, missing cells filled with `fill_value`
+    ByName,
+}
+
+fn csv_error(e: csv::Error) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+}
+
+impl FileExecutor {
+    // Resolve either an explicit ordered source list or a glob pattern into files
+    pub(super) async fn concat_sources(
+        &self,
+        sources: Option<Vec<String>>,
+        pattern: Option<String>,
+    ) -> Result<Vec<(String, PathBuf)>> {
+        let resolved = match (sources, pattern) {
+            (Some(sources), None) => sources
+                .into_iter()
+                .map(|s| {
+                    let path = self.resolve_path(&s)?;
+                    Ok((s, path))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            (None, Some(pattern)) => self
+                .glob_matches(&pattern)
+                .await?
+                .into_iter()
+                .filter(|m| !m.is_dir)
+                .map(|m| (m.relative, m.path))
+                .collect(),
+            _ => {
+                return Err(Error::InvalidConfig(
+                    "Provide exactly one of 'sources' or 'pattern'".to_string()
+                ))
+            }
+        };
+        if resolved.is_empty() {
+            return Err(Error::InvalidConfig("No source files to concatenate".to_string()));
+        }
+        Ok(resolved)
+    }
+
+    pub(super) async fn concat_csv(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            sources: Option<Vec<String>>,
+            pattern: Option<String>,
+            dest: String,
+            #[serde(default)]
+            header_mode: HeaderMode,
+            #[serde(default)]
+            fill_value: String,
+            provenance_column: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let sources = self.concat_sources(params.sources, params.pattern).await?;
+        let dest_path = self.resolve_path(&params.dest)?;
+        if sources.iter().any(|(_, p)| *p == dest_path) {
+            return Err(Error::InvalidConfig("dest must not be one of the sources".to_string()));
+        }
+
+        let output_path = dest_path.clone();
+        let (columns, counts) = tokio::task::spawn_blocking(move || {
+            // Check every header before creating dest so a mismatch writes nothing
+            let mut headers = Vec::new();
+            for (_, path) in &sources {
+                let mut reader = csv::Reader::from_path(path).map_err(csv_error)?;
+                let header: Vec<String> = reader.headers().map_err(csv_error)?
+                    .iter().map(|s| s.to_string()).collect();
+                headers.push(header);
+            }
+
+            let columns = match params.header_mode {
+                HeaderMode::Strict => {
+                    let differing: Vec<&str> = sources.iter().zip(&headers)
+                        .filter(|(_, h)| **h != headers[0])
+                        .map(|((name, _), _)| name.as_str())
+                        .collect();
+                    if !differing.is_empty() {
+                        return Err(Error::InvalidConfig(format!(
+                            "Header mismatch with {} (expected {:?}) in: {}",
+                            sources[0].0, headers[0], differing.join(", ")
+                        )));
+                    }
+                    headers[0].clone()
+                }
+                HeaderMode::ByName => {
+                    let mut columns: Vec<String> = Vec::new();
+                    for header in &headers {
+                        for column in header {
+                            if !columns.contains(column) {
+                                columns.push(column.clone());
+                            }
+                        }
+                    }
+                    columns
+                }
+            };
+
+            let mut writer = csv::Writer::from_path(&dest_path).map_err(csv_error)?;
+            let mut out_header = columns.clone();
+            if let Some(provenance) = &params.provenance_column {
+                out_header.push(provenance.clone());
+            }
+            writer.write_record(&out_header).map_err(csv_error)?;
+
+            let mut counts = Vec::new();
+            for ((name, path), header) in sources.iter().zip(&headers) {
+                // Position of each output column in this source, if present
+                let mapping: Vec<Option<usize>> = columns.iter()
+                    .map(|c| header.iter().position(|h| h == c))
+                    .collect();
+
+                let mut reader = csv::Reader::from_path(path).map_err(csv_error)?;
+                let mut rows = 0u64;
+                for record in reader.records() {
+                    let record = record.map_err(csv_error)?;
+                    let mut row: Vec<&str> = mapping.iter()
+                        .map(|i| i.and_then(|i| record.get(i)).unwrap_or(&params.fill_value))
+                        .collect();
+                    if params.provenance_column.is_some() {
+                        row.push(name);
+                    }
+                    writer.write_record(&row).map_err(csv_error)?;
+                    rows += 1;
+                }
+                counts.push(serde_json::json!({ "path": name, "rows": rows }));
+            }
+            writer.flush()?;
+            Ok((columns, counts))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        self.settle(&output_path, None).await?;
+        let total_rows: u64 = counts.iter().map(|c| c["rows"].as_u64().unwrap_or(0)).sum();
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "path": output_path,
+                "columns": columns,
+                "sources": counts,
+                "total_rows": total_rows,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn concat(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            sources: Option<Vec<String>>,
+            pattern: Option<String>,
+            dest: String,
+            separator: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let sources = self.concat_sources(params.sources, params.pattern).await?;
+        let dest_path = self.resolve_path(&params.dest)?;
+        if sources.iter().any(|(_, p)| *p == dest_path) {
+            return Err(Error::InvalidConfig("dest must not be one of the sources".to_string()));
+        }
+
+        let mut dest = fs::File::create(&dest_path).await?;
+        let mut counts = Vec::new();
+        let mut total_bytes = 0u64;
+        for (i, (name, path)) in sources.iter().enumerate() {
+            if let (true, Some(separator)) = (i > 0, &params.separator) {
+                dest.write_all(separator.as_bytes()).await?;
+                total_bytes += separator.len() as u64;
+            }
+            let mut source = fs::File::open(path).await?;
+            let bytes = tokio::io::copy(&mut source, &mut dest).await?;
+            total_bytes += bytes;
+            counts.push(serde_json::json!({ "path": name, "bytes": bytes }));
+        }
+        dest.flush().await?;
+        self.settle(&dest_path, Some(total_bytes)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "path": dest_path,
+                "sources": counts,
+                "total_bytes": total_bytes,
+            })),
+            error: None,
+        })
+    }
+}
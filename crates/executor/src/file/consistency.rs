@@ -0,0 +1,137 @@
+use local_automation_common::{Error, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::fs;
+
+use super::FileExecutor;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// How hard mutating operations work to make their effects visible to the next step.
+// Network filesystems in particular may serve stale metadata without this. Every
+// operation that writes a caller's path settles it; internal bookkeeping (lock
+// files, edit working copies, sort scratch runs) is exempt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteConsistency {
+    #[default]
+    Relaxed,
+    // fsync the touched file and its parent directory after every mutation
+    Synced,
+    // Synced, then re-read the metadata and check the size before reporting success
+    Verified,
+}
+
+// `expect_recent` barrier for read-class operations: wait until `path` (default:
+// the operation's own path) shows a modification within the last `within_ms`.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct ExpectRecent {
+    path: Option<String>,
+    within_ms: u64,
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+fn sync_path(path: &Path) -> std::io::Result<()> {
+    match std::fs::File::open(path) {
+        Ok(file) => file.sync_all(),
+        // Directories can't be opened for syncing on every platform
+        Err(_) if path.is_dir() => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+impl FileExecutor {
+    fn record_syncs(&self, n: u64) {
+        self.syncs.fetch_add(n, Ordering::Relaxed);
+    }
+
+    // Number of extra fsyncs issued by the consistency mode so far, so the cost of
+    // Synced/Verified is visible to whoever configures it.
+    pub fn sync_count(&self) -> u64 {
+        self.syncs.load(Ordering::Relaxed)
+    }
+
+    // Called after a file or directory was created or rewritten
+    pub(super) async fn settle(&self, path: &Path, expected_len: Option<u64>) -> Result<()> {
+        if self.consistency == WriteConsistency::Relaxed {
+            return Ok(());
+        }
+
+        let target = path.to_path_buf();
+        let parent = path.parent().map(Path::to_path_buf);
+        tokio::task::spawn_blocking(move || {
+            sync_path(&target)?;
+            if let Some(parent) = parent {
+                sync_path(&parent)?;
+            }
+            Ok::<_, std::io::Error>(())
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.record_syncs(2);
+
+        if self.consistency == WriteConsistency::Verified {
+            let metadata = fs::metadata(path).await?;
+            if let (Some(expected), true) = (expected_len, metadata.is_file()) {
+                if metadata.len() != expected {
+                    return Err(Error::Io(std::io::Error::other(format!(
+                        "Write verification failed for {}: expected {} bytes, found {}",
+                        path.display(), expected, metadata.len()
+                    ))));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Called after a path was removed or renamed away
+    pub(super) async fn settle_removed(&self, path: &Path) -> Result<()> {
+        if self.consistency == WriteConsistency::Relaxed {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent().map(Path::to_path_buf) {
+            tokio::task::spawn_blocking(move || sync_path(&parent))
+                .await
+                .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+            self.record_syncs(1);
+        }
+
+        if self.consistency == WriteConsistency::Verified && fs::try_exists(path).await? {
+            return Err(Error::Io(std::io::Error::other(format!(
+                "Removal verification failed: {} still exists", path.display()
+            ))));
+        }
+        Ok(())
+    }
+
+    pub(super) async fn await_recent(&self, default_path: &Path, barrier: &ExpectRecent) -> Result<()> {
+        let path: PathBuf = match &barrier.path {
+            Some(p) => self.resolve_path(p)?,
+            None => default_path.to_path_buf(),
+        };
+        let window = Duration::from_millis(barrier.within_ms);
+        let deadline = Instant::now() + Duration::from_millis(barrier.timeout_ms);
+
+        loop {
+            if let Ok(metadata) = fs::metadata(&path).await {
+                let age = SystemTime::now()
+                    .duration_since(metadata.modified()?)
+                    .unwrap_or(Duration::ZERO);
+                if age <= window {
+                    return Ok(());
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::fs;
+
+use super::dialect::{synthesized_headers, CsvDialect};
+use super::format::{FormatParams, ValueFormatter};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+fn invalid_data(message: impl ToString) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string()))
+}
+
+impl FileExecutor {
+    // CSV -> JSON array of objects keyed by header, or by col_0, col_1, ...
+    // for files read with `has_headers: false`
+    pub(super) async fn csv_to_json(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            from: String,
+            to: String,
+            #[serde(flatten)]
+            dialect: CsvDialect,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let from = self.resolve_path(&params.from)?;
+        let to = self.resolve_path(&params.to)?;
+        let content = fs::read_to_string(&from).await?;
+
+        let mut reader = params.dialect.reader()?.from_reader(content.as_bytes());
+        let headers: Option<Vec<String>> = match params.dialect.has_headers {
+            true => Some(reader.headers().map_err(invalid_data)?.iter().map(String::from).collect()),
+            false => None,
+        };
+        let mut records = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(invalid_data)?;
+            let synthesized;
+            let names = match &headers {
+                Some(headers) => headers,
+                None => {
+                    synthesized = synthesized_headers(record.len());
+                    &synthesized
+                }
+            };
+            let object: Map<String, Value> = names.iter()
+                .zip(record.iter())
+                .map(|(h, v)| (h.to_string(), Value::String(v.to_string())))
+                .collect();
+            records.push(Value::Object(object));
+        }
+
+        let rows = records.len();
+        let data = serde_json::to_string_pretty(&records)?;
+        fs::write(&to, data.as_bytes()).await?;
+        self.settle(&to, Some(data.len() as u64)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "from": from,
+                "to": to,
+                "rows": rows,
+            })),
+            error: None,
+        })
+    }
+
+    // JSON array of objects -> CSV. Columns are the union of keys unless
+    // `columns` fixes them, in order of first appearance across objects; keys
+    // within one object come out sorted, as serde_json maps are. Absent keys
+    // become empty cells
+    pub(super) async fn json_to_csv(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            from: String,
+            to: String,
+            columns: Option<Vec<String>>,
+            #[serde(flatten)]
+            format: FormatParams,
+            #[serde(flatten)]
+            dialect: CsvDialect,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let formatter = ValueFormatter::from_params(&params.format)?;
+        let from = self.resolve_path(&params.from)?;
+        let to = self.resolve_path(&params.to)?;
+        let content = fs::read_to_string(&from).await?;
+        let value: Value = serde_json::from_str(&content)?;
+
+        let Value::Array(items) = value else {
+            return Err(invalid_data(format!("{} does not contain a JSON array", params.from)));
+        };
+        let objects = items.iter().enumerate()
+            .map(|(i, item)| item.as_object().ok_or_else(|| invalid_data(format!(
+                "Item {} in {} is not an object", i, params.from
+            ))))
+            .collect::<Result<Vec<_>>>()?;
+
+        let columns = match params.columns {
+            Some(columns) => columns,
+            None => {
+                let mut columns: Vec<String> = Vec::new();
+                for object in &objects {
+                    for key in object.keys() {
+                        if !columns.contains(key) {
+                            columns.push(key.clone());
+                        }
+                    }
+                }
+                columns
+            }
+        };
+
+        let mut writer = params.dialect.writer()?.from_writer(vec![]);
+        if params.dialect.has_headers {
+            writer.write_record(&columns).map_err(invalid_data)?;
+        }
+        for object in &objects {
+            let row = columns.iter().map(|c| object.get(c).map(|v| formatter.render(v)).unwrap_or_default());
+            writer.write_record(row).map_err(invalid_data)?;
+        }
+        let data = writer.into_inner().map_err(invalid_data)?;
+
+        let len = data.len() as u64;
+        fs::write(&to, data).await?;
+        self.settle(&to, Some(len)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "from": from,
+                "to": to,
+                "rows": objects.len(),
+                "columns": columns,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,152 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+enum Entry {
+    Dir(PathBuf),
+    File { from: PathBuf, to: PathBuf },
+    Link { target: PathBuf, to: PathBuf },
+}
+
+impl FileExecutor {
+    pub(super) async fn copy_dir(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            from: String,
+            to: String,
+            #[serde(default)]
+            overwrite: bool,
+            // Copy what symlinks point to (inside base_path only) instead of
+            // recreating the links
+            #[serde(default)]
+            follow_symlinks: bool,
+            // Leave symlinks out entirely
+            #[serde(default)]
+            skip_symlinks: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let from_path = self.resolve_path(&params.from)?;
+        let to_path = self.resolve_path(&params.to)?;
+        if !fs::metadata(&from_path).await?.is_dir() {
+            return Err(Error::InvalidConfig(format!("{} is not a directory", params.from)));
+        }
+        if to_path.starts_with(&from_path) {
+            return Err(Error::InvalidConfig("to must not be inside from".to_string()));
+        }
+        let base = fs::canonicalize(&self.base_path).await?;
+
+        // Plan the whole copy first so a conflict fails before anything is written
+        let mut plan = vec![Entry::Dir(to_path.clone())];
+        let mut skipped_symlinks = 0u64;
+        let mut visited = HashSet::from([fs::canonicalize(&from_path).await?]);
+        let internal = self.internal_paths();
+        let mut pending = vec![(from_path.clone(), to_path.clone())];
+        while let Some((dir, dest)) = pending.pop() {
+            let mut entries = fs::read_dir(&dir).await?;
+            let mut children = Vec::new();
+            while let Some(entry) = entries.next_entry().await? {
+                children.push(entry);
+            }
+            children.sort_by_key(|e| e.file_name());
+            for entry in children {
+                let source = entry.path();
+                if internal.contains(&source) {
+                    continue;
+                }
+                let target = dest.join(entry.file_name());
+                let mut file_type = entry.file_type().await?;
+                if file_type.is_symlink() {
+                    if params.skip_symlinks {
+                        skipped_symlinks += 1;
+                        continue;
+                    }
+                    if !params.follow_symlinks {
+                        plan.push(Entry::Link { target: fs::read_link(&source).await?, to: target });
+                        continue;
+                    }
+                    let resolved = fs::canonicalize(&source).await?;
+                    if !resolved.starts_with(&base) {
+                        return Err(Error::PermissionDenied(format!(
+                            "Symlink {} points outside the base path", source.display()
+                        )));
+                    }
+                    file_type = fs::metadata(&resolved).await?.file_type();
+                    if file_type.is_dir() && !visited.insert(resolved) {
+                        return Err(Error::InvalidConfig(format!("Symlink loop at {}", source.display())));
+                    }
+                }
+                if file_type.is_dir() {
+                    plan.push(Entry::Dir(target.clone()));
+                    pending.push((source, target));
+                } else {
+                    plan.push(Entry::File { from: source, to: target });
+                }
+            }
+        }
+
+        if !params.overwrite {
+            for entry in &plan {
+                if let Entry::File { to, .. } | Entry::Link { to, .. } = entry {
+                    if fs::symlink_metadata(to).await.is_ok() {
+                        return Err(Error::InvalidConfig(format!(
+                            "{} already exists; pass overwrite: true", to.display()
+                        )));
+                    }
+                }
+            }
+        }
+
+        let (mut files, mut bytes, mut dirs, mut symlinks) = (0u64, 0u64, 0u64, 0u64);
+        for entry in plan {
+            match entry {
+                Entry::Dir(dir) => {
+                    fs::create_dir_all(&dir).await?;
+                    dirs += 1;
+                }
+                Entry::File { from, to } => {
+                    bytes += fs::copy(&from, &to).await?;
+                    files += 1;
+                }
+                Entry::Link { target, to } => {
+                    if fs::symlink_metadata(&to).await.is_ok() {
+                        fs::remove_file(&to).await?;
+                    }
+                    #[cfg(unix)]
+                    {
+                        fs::symlink(&target, &to).await?;
+                        symlinks += 1;
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        let _ = target;
+                        skipped_symlinks += 1;
+                    }
+                }
+            }
+        }
+        self.settle(&to_path, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "from": params.from,
+                "to": params.to,
+                "files": files,
+                "bytes": bytes,
+                "dirs": dirs,
+                "symlinks": symlinks,
+                "skipped_symlinks": skipped_symlinks,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+// Feed the first `len` bytes of `file` (from its current position) into `hasher`
+async fn hash_prefix(file: &mut fs::File, len: u64, hasher: &mut Sha256, buf: &mut [u8]) -> Result<()> {
+    let mut remaining = len;
+    while remaining > 0 {
+        let want = remaining.min(buf.len() as u64) as usize;
+        let n = file.read(&mut buf[..want]).await?;
+        if n == 0 {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "file shrank while hashing",
+            )));
+        }
+        hasher.update(&buf[..n]);
+        remaining -= n as u64;
+    }
+    Ok(())
+}
+
+async fn sha256_file(path: &Path, buf: &mut [u8]) -> Result<String> {
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    loop {
+        let n = file.read(buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+impl FileExecutor {
+    pub(super) async fn copy_large(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            from: String,
+            to: String,
+            #[serde(default = "default_chunk_size")]
+            chunk_size: usize,
+            #[serde(default = "default_true")]
+            resume: bool,
+            #[serde(default = "default_true")]
+            verify: bool,
+            #[serde(default)]
+            preserve_mtime: bool,
+        }
+
+        fn default_chunk_size() -> usize { DEFAULT_CHUNK_SIZE }
+        fn default_true() -> bool { true }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        if params.chunk_size == 0 {
+            return Err(Error::InvalidConfig("chunk_size must be greater than zero".to_string()));
+        }
+
+        let from_path = self.resolve_path(&params.from)?;
+        let to_path = self.resolve_path(&params.to)?;
+
+        let mut source = fs::File::open(&from_path).await?;
+        let source_meta = source.metadata().await?;
+        let total_bytes = source_meta.len();
+        let mut buf = vec![0u8; params.chunk_size];
+
+        // A partial destination is only trusted if its bytes match the source prefix
+        let existing = match fs::metadata(&to_path).await {
+            Ok(meta) if params.resume && meta.len() <= total_bytes => meta.len(),
+            _ => 0,
+        };
+        let mut source_hasher = Sha256::new();
+        let mut resumed_from = 0;
+        if existing > 0 {
+            let mut dest = fs::File::open(&to_path).await?;
+            let mut dest_hasher = Sha256::new();
+            hash_prefix(&mut dest, existing, &mut dest_hasher, &mut buf).await?;
+            let mut prefix_hasher = Sha256::new();
+            hash_prefix(&mut source, existing, &mut prefix_hasher, &mut buf).await?;
+
+            if prefix_hasher.clone().finalize() == dest_hasher.finalize() {
+                resumed_from = existing;
+                source_hasher = prefix_hasher;
+            } else {
+                source.seek(std::io::SeekFrom::Start(0)).await?;
+            }
+        }
+
+        let mut dest = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&to_path)
+            .await?;
+        dest.set_len(resumed_from).await?;
+        dest.seek(std::io::SeekFrom::Start(resumed_from)).await?;
+
+        let mut bytes_copied = 0u64;
+        loop {
+            let n = source.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            source_hasher.update(&buf[..n]);
+            dest.write_all(&buf[..n]).await?;
+            bytes_copied += n as u64;
+        }
+        dest.flush().await?;
+        dest.sync_all().await?;
+
+        if params.preserve_mtime {
+            let dest = dest.into_std().await;
+            dest.set_modified(source_meta.modified()?)?;
+        }
+        self.settle(&to_path, Some(total_bytes)).await?;
+
+        let checksum = hex::encode(source_hasher.finalize());
+        if params.verify {
+            let dest_checksum = sha256_file(&to_path, &mut buf).await?;
+            if dest_checksum != checksum {
+                return Ok(ExecutionResult {
+                    success: false,
+                    output: Some(serde_json::json!({
+                        "from": from_path,
+                        "to": to_path,
+                        "source_sha256": checksum,
+                        "dest_sha256": dest_checksum,
+                    })),
+                    error: Some("Checksum mismatch after copy".to_string()),
+                });
+            }
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "from": from_path,
+                "to": to_path,
+                "total_bytes": total_bytes,
+                "bytes_copied": bytes_copied,
+                "resumed_from": resumed_from,
+                "sha256": checksum,
+                "verified": params.verify,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,357 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::external_sort::{ExternalSorter, Scratch, SortedRecords};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DuplicatePolicy {
+    #[default]
+    Error,
+    // The row closest to the end of the file stands for the key
+    LastWins,
+    // Rows sharing a key are paired up in file order; extras are added/removed
+    EmitAll,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ModifiedFormat {
+    // Key columns, then old_<col> and new_<col> for every other column
+    #[default]
+    Both,
+    // Key columns, then a JSON map of only the changed columns
+    Changes,
+}
+
+#[derive(Deserialize, Default)]
+struct DeltaDest {
+    added: Option<String>,
+    removed: Option<String>,
+    modified: Option<String>,
+}
+
+fn invalid_data(e: impl ToString) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+}
+
+struct SortedCsv {
+    records: SortedRecords,
+    rows: u64,
+    runs_spilled: usize,
+    pending: Option<Vec<String>>,
+}
+
+impl SortedCsv {
+    // Sort `path` by key, with each row's fields reordered to `columns`. Records
+    // are [key, sequence, row] so rows sharing a key stay in file order.
+    fn open(path: &Path, columns: &[String], key_columns: &[String], scratch: Scratch, memory_limit: usize) -> Result<Self> {
+        let mut reader = csv::Reader::from_path(path).map_err(invalid_data)?;
+        let header: Vec<String> = reader.headers().map_err(invalid_data)?.iter().map(str::to_string).collect();
+        let mut sorted_header = header.clone();
+        let mut expected = columns.to_vec();
+        sorted_header.sort();
+        expected.sort();
+        if sorted_header != expected {
+            return Err(Error::InvalidConfig(format!(
+                "{} has columns {:?}, expected {:?}", path.display(), header, columns
+            )));
+        }
+        let order: Vec<usize> = columns.iter()
+            .map(|c| header.iter().position(|h| h == c).expect("same column set"))
+            .collect();
+        let keys: Vec<usize> = key_columns.iter()
+            .map(|k| columns.iter().position(|c| c == k).expect("validated against current"))
+            .collect();
+
+        let mut sorter = ExternalSorter::new(scratch, 3, memory_limit);
+        let mut rows = 0u64;
+        for record in reader.records() {
+            let record = record.map_err(invalid_data)?;
+            let row: Vec<&str> = order.iter().map(|&i| record.get(i).unwrap_or("")).collect();
+            let key: Vec<&str> = keys.iter().map(|&i| row[i]).collect();
+            sorter.insert(vec![
+                serde_json::to_string(&key)?,
+                format!("{:020}", rows),
+                serde_json::to_string(&row)?,
+            ])?;
+            rows += 1;
+        }
+        let (records, runs_spilled) = sorter.finish()?;
+        Ok(Self { records, rows, runs_spilled, pending: None })
+    }
+
+    // All rows of the next key, in file order
+    fn next_group(&mut self) -> Result<Option<(String, Vec<Vec<String>>)>> {
+        let Some(first) = self.pending.take().map(Ok).or_else(|| self.records.next().transpose()).transpose()? else {
+            return Ok(None);
+        };
+        let key = first[0].clone();
+        let mut rows = vec![serde_json::from_str(&first[2])?];
+        while let Some(record) = self.records.next()? {
+            if record[0] != key {
+                self.pending = Some(record);
+                break;
+            }
+            rows.push(serde_json::from_str(&record[2])?);
+        }
+        Ok(Some((key, rows)))
+    }
+}
+
+struct Outputs {
+    added: Option<csv::Writer<File>>,
+    removed: Option<csv::Writer<File>>,
+    modified: Option<csv::Writer<File>>,
+}
+
+fn writer(path: &Option<PathBuf>, header: &[String]) -> Result<Option<csv::Writer<File>>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let mut writer = csv::Writer::from_path(path).map_err(invalid_data)?;
+    writer.write_record(header).map_err(invalid_data)?;
+    Ok(Some(writer))
+}
+
+fn write_row(writer: &mut Option<csv::Writer<File>>, row: &[String]) -> Result<()> {
+    if let Some(writer) = writer {
+        writer.write_record(row).map_err(invalid_data)?;
+    }
+    Ok(())
+}
+
+impl FileExecutor {
+    // Rows added, removed and modified between two full CSV dumps, matched on
+    // key columns. Both sides are externally sorted by key and merge-joined, so
+    // neither has to fit in memory.
+    pub(super) async fn csv_delta(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            current: String,
+            // Defaults to the `current` of the last run recorded under state_key
+            previous: Option<String>,
+            key_columns: Vec<String>,
+            #[serde(default)]
+            dest: DeltaDest,
+            #[serde(default)]
+            duplicates: DuplicatePolicy,
+            #[serde(default)]
+            modified_format: ModifiedFormat,
+            state_key: Option<String>,
+            memory_limit_bytes: Option<usize>,
+            // Where sorted runs are spilled; defaults to current's directory
+            scratch_dir: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.key_columns.is_empty() {
+            return Err(Error::InvalidConfig("key_columns must not be empty".to_string()));
+        }
+        let memory_limit = params.memory_limit_bytes.unwrap_or(DEFAULT_MEMORY_LIMIT);
+        if memory_limit == 0 {
+            return Err(Error::InvalidConfig("memory_limit_bytes must be positive".to_string()));
+        }
+
+        if params.state_key.is_some() && self.state.is_none() {
+            return Err(Error::InvalidConfig("This FileExecutor has no state store configured".to_string()));
+        }
+        // Without a previous dump (first run under a state key) every row is added
+        let previous = match (&params.previous, &params.state_key) {
+            (Some(previous), _) => Some(previous.clone()),
+            (None, Some(key)) => match self.state_mark(key).await? {
+                Some(Value::String(path)) => Some(path),
+                Some(_) => return Err(Error::InvalidConfig(format!("State key {} does not hold a path", key))),
+                None => None,
+            },
+            (None, None) => return Err(Error::InvalidConfig(
+                "Provide previous, or a state_key recording the last run".to_string()
+            )),
+        };
+
+        let current_path = self.resolve_path(&params.current)?;
+        let previous_path = previous.as_deref().map(|p| self.resolve_path(p)).transpose()?;
+        let resolve = |p: &Option<String>| p.as_deref().map(|p| self.resolve_path(p)).transpose();
+        let dests = [resolve(&params.dest.added)?, resolve(&params.dest.removed)?, resolve(&params.dest.modified)?];
+        if dests.iter().flatten().any(|d| *d == current_path || Some(d) == previous_path.as_ref()) {
+            return Err(Error::InvalidConfig("dest must not be one of the inputs".to_string()));
+        }
+        let scratch_parent = match &params.scratch_dir {
+            Some(dir) => self.resolve_path(dir)?,
+            None => current_path.parent().map(Path::to_path_buf).unwrap_or_else(|| self.base_path.clone()),
+        };
+
+        let key_columns = params.key_columns;
+        let policy = params.duplicates;
+        let modified_format = params.modified_format;
+        let outputs = dests.clone();
+        let summary = tokio::task::spawn_blocking(move || -> Result<Value> {
+            let columns: Vec<String> = csv::Reader::from_path(&current_path).map_err(invalid_data)?
+                .headers().map_err(invalid_data)?
+                .iter().map(str::to_string).collect();
+            if let Some(missing) = key_columns.iter().find(|k| !columns.contains(k)) {
+                return Err(Error::InvalidConfig(format!("Key column {} is not in {}", missing, current_path.display())));
+            }
+            let keys: Vec<usize> = key_columns.iter()
+                .map(|k| columns.iter().position(|c| c == k).expect("checked above"))
+                .collect();
+            let others: Vec<usize> = (0..columns.len()).filter(|i| !keys.contains(i)).collect();
+
+            let mut current = SortedCsv::open(
+                &current_path, &columns, &key_columns, Scratch::create(&scratch_parent, "delta")?, memory_limit,
+            )?;
+            let mut previous = previous_path.as_ref()
+                .map(|p| SortedCsv::open(p, &columns, &key_columns, Scratch::create(&scratch_parent, "delta")?, memory_limit))
+                .transpose()?;
+
+            let modified_header: Vec<String> = match modified_format {
+                ModifiedFormat::Both => key_columns.iter().cloned()
+                    .chain(others.iter().flat_map(|&i| [format!("old_{}", columns[i]), format!("new_{}", columns[i])]))
+                    .collect(),
+                ModifiedFormat::Changes => key_columns.iter().cloned().chain(["changes".to_string()]).collect(),
+            };
+            let mut out = Outputs {
+                added: writer(&outputs[0], &columns)?,
+                removed: writer(&outputs[1], &columns)?,
+                modified: writer(&outputs[2], &modified_header)?,
+            };
+
+            let (mut added, mut removed, mut modified, mut unchanged, mut duplicate_keys) = (0u64, 0u64, 0u64, 0u64, 0u64);
+            let mut apply_policy = |key: &str, rows: Vec<Vec<String>>, side: &str| -> Result<Vec<Vec<String>>> {
+                if rows.len() < 2 {
+                    return Ok(rows);
+                }
+                duplicate_keys += 1;
+                match policy {
+                    DuplicatePolicy::Error => Err(invalid_data(format!(
+                        "Duplicate key {} in {} ({} rows); set duplicates to last_wins or emit_all",
+                        key, side, rows.len()
+                    ))),
+                    DuplicatePolicy::LastWins => Ok(rows.into_iter().last().into_iter().collect()),
+                    DuplicatePolicy::EmitAll => Ok(rows),
+                }
+            };
+
+            let mut cur = current.next_group()?;
+            let mut prev = match previous.as_mut() {
+                Some(p) => p.next_group()?,
+                None => None,
+            };
+            loop {
+                let order = match (&prev, &cur) {
+                    (None, None) => break,
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (Some((p, _)), Some((c, _))) => p.cmp(c),
+                };
+                let (old_rows, new_rows) = match order {
+                    Ordering::Less => {
+                        let (key, rows) = prev.take().expect("ordered above");
+                        (apply_policy(&key, rows, "previous")?, Vec::new())
+                    }
+                    Ordering::Greater => {
+                        let (key, rows) = cur.take().expect("ordered above");
+                        (Vec::new(), apply_policy(&key, rows, "current")?)
+                    }
+                    Ordering::Equal => {
+                        let (key, old) = prev.take().expect("ordered above");
+                        let (_, new) = cur.take().expect("ordered above");
+                        (apply_policy(&key, old, "previous")?, apply_policy(&key, new, "current")?)
+                    }
+                };
+
+                for i in 0..old_rows.len().max(new_rows.len()) {
+                    match (old_rows.get(i), new_rows.get(i)) {
+                        (Some(old), Some(new)) if old == new => unchanged += 1,
+                        (Some(old), Some(new)) => {
+                            modified += 1;
+                            let mut row: Vec<String> = keys.iter().map(|&k| new[k].clone()).collect();
+                            match modified_format {
+                                ModifiedFormat::Both => {
+                                    for &c in &others {
+                                        row.push(old[c].clone());
+                                        row.push(new[c].clone());
+                                    }
+                                }
+                                ModifiedFormat::Changes => {
+                                    let changes: Map<String, Value> = others.iter()
+                                        .filter(|&&c| old[c] != new[c])
+                                        .map(|&c| (columns[c].clone(), json!({ "old": old[c], "new": new[c] })))
+                                        .collect();
+                                    row.push(Value::Object(changes).to_string());
+                                }
+                            }
+                            write_row(&mut out.modified, &row)?;
+                        }
+                        (Some(old), None) => {
+                            removed += 1;
+                            write_row(&mut out.removed, old)?;
+                        }
+                        (None, Some(new)) => {
+                            added += 1;
+                            write_row(&mut out.added, new)?;
+                        }
+                        (None, None) => unreachable!(),
+                    }
+                }
+
+                if prev.is_none() {
+                    if let Some(p) = previous.as_mut() {
+                        prev = p.next_group()?;
+                    }
+                }
+                if cur.is_none() {
+                    cur = current.next_group()?;
+                }
+            }
+
+            for writer in [out.added, out.removed, out.modified].into_iter().flatten() {
+                writer.into_inner().map_err(|e| invalid_data(e.to_string()))?.sync_all()?;
+            }
+
+            Ok(json!({
+                "added": added,
+                "removed": removed,
+                "modified": modified,
+                "unchanged": unchanged,
+                "duplicate_keys": duplicate_keys,
+                "current_rows": current.rows,
+                "previous_rows": previous.as_ref().map(|p| p.rows),
+                "runs_spilled": current.runs_spilled + previous.as_ref().map_or(0, |p| p.runs_spilled),
+            }))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        for dest in dests.iter().flatten() {
+            self.settle(dest, None).await?;
+        }
+        if let (Some(key), Some(store)) = (&params.state_key, &self.state) {
+            store.set(key, json!(params.current)).await?;
+        }
+
+        let mut output = summary;
+        output["current"] = json!(params.current);
+        output["previous"] = json!(previous);
+        output["dest"] = json!({
+            "added": params.dest.added,
+            "removed": params.dest.removed,
+            "modified": params.dest.modified,
+        });
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
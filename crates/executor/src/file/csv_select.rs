@@ -0,0 +1,118 @@
+use local_automation_common::{Error, Result};
+use serde::Deserialize;
+
+// A column named by header or by zero-based position
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(super) enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+// `{"column": "status", "equals": "active"}`; exactly one predicate
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RowFilter {
+    column: ColumnRef,
+    equals: Option<String>,
+    contains: Option<String>,
+    #[serde(default)]
+    not_empty: bool,
+}
+
+enum Predicate {
+    Equals(String),
+    Contains(String),
+    NotEmpty,
+}
+
+// Projection and filter resolved against a file's header
+pub(super) struct Selection {
+    columns: Option<Vec<usize>>,
+    filter: Option<(usize, Predicate)>,
+}
+
+// `headers` is None for files read with `has_headers: false`, where names
+// are the synthesized `col_<n>`
+fn resolve(column: &ColumnRef, headers: Option<&[String]>) -> Result<usize> {
+    let (index, known) = match (column, headers) {
+        (ColumnRef::Index(i), Some(headers)) => (Some(*i), *i < headers.len()),
+        (ColumnRef::Index(i), None) => (Some(*i), true),
+        (ColumnRef::Name(name), Some(headers)) => {
+            let index = headers.iter().position(|h| h == name);
+            (index, index.is_some())
+        }
+        (ColumnRef::Name(name), None) => {
+            let index = name.strip_prefix("col_").and_then(|n| n.parse().ok());
+            (index, index.is_some())
+        }
+    };
+    match index {
+        Some(index) if known => Ok(index),
+        _ => {
+            let available = match headers {
+                Some(headers) => headers.join(", "),
+                None => "col_0, col_1, ... (file has no header row)".to_string(),
+            };
+            let shown = match column {
+                ColumnRef::Index(i) => i.to_string(),
+                ColumnRef::Name(name) => format!("'{}'", name),
+            };
+            Err(Error::InvalidConfig(format!("Unknown column {}; available columns: {}", shown, available)))
+        }
+    }
+}
+
+impl Selection {
+    pub fn new(columns: Option<&[ColumnRef]>, filter: Option<&RowFilter>, headers: Option<&[String]>) -> Result<Self> {
+        let columns = columns
+            .map(|columns| columns.iter().map(|c| resolve(c, headers)).collect::<Result<Vec<_>>>())
+            .transpose()?;
+        let filter = match filter {
+            Some(filter) => {
+                let predicate = match (&filter.equals, &filter.contains, filter.not_empty) {
+                    (Some(value), None, false) => Predicate::Equals(value.clone()),
+                    (None, Some(value), false) => Predicate::Contains(value.clone()),
+                    (None, None, true) => Predicate::NotEmpty,
+                    _ => return Err(Error::InvalidConfig(
+                        "filter takes exactly one of 'equals', 'contains' or 'not_empty: true'".to_string()
+                    )),
+                };
+                Some((resolve(&filter.column, headers)?, predicate))
+            }
+            None => None,
+        };
+        Ok(Self { columns, filter })
+    }
+
+    // Cells past the end of a short (flexible) row read as empty
+    pub fn matches(&self, row: &csv::StringRecord) -> bool {
+        let Some((index, predicate)) = &self.filter else {
+            return true;
+        };
+        let cell = row.get(*index).unwrap_or("");
+        match predicate {
+            Predicate::Equals(value) => cell == value,
+            Predicate::Contains(value) => cell.contains(value.as_str()),
+            Predicate::NotEmpty => !cell.is_empty(),
+        }
+    }
+
+    pub fn project(&self, row: &csv::StringRecord) -> Vec<String> {
+        match &self.columns {
+            Some(columns) => columns.iter().map(|&i| row.get(i).unwrap_or("").to_string()).collect(),
+            None => row.iter().map(str::to_string).collect(),
+        }
+    }
+
+    pub fn project_headers(&self, headers: Vec<String>) -> Vec<String> {
+        match &self.columns {
+            Some(columns) => columns.iter().map(|&i| headers.get(i).cloned().unwrap_or_default()).collect(),
+            None => headers,
+        }
+    }
+
+    pub fn columns(&self) -> Option<&[usize]> {
+        self.columns.as_deref()
+    }
+}
@@ -0,0 +1,79 @@
+use local_automation_common::{Error, Result};
+use serde::Deserialize;
+
+// Optional CSV layout settings shared by read_csv and write_csv. The defaults
+// are the comma-separated, double-quoted, header-first files we always wrote.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct CsvDialect {
+    #[serde(default = "default_delimiter")]
+    delimiter: char,
+    #[serde(default = "default_quote")]
+    quote: char,
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    // Tolerate rows whose length differs from the header or from each other
+    #[serde(default)]
+    pub flexible: bool,
+}
+
+fn default_delimiter() -> char { ',' }
+fn default_quote() -> char { '"' }
+fn default_has_headers() -> bool { true }
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            quote: default_quote(),
+            has_headers: default_has_headers(),
+            flexible: false,
+        }
+    }
+}
+
+fn single_byte(name: &str, c: char) -> Result<u8> {
+    match u8::try_from(c) {
+        Ok(b) if c.is_ascii() && b != b'\n' && b != b'\r' => Ok(b),
+        _ => Err(Error::InvalidConfig(format!(
+            "{} must be a single ASCII character other than a line break, got {:?}", name, c
+        ))),
+    }
+}
+
+impl CsvDialect {
+    fn bytes(&self) -> Result<(u8, u8)> {
+        let delimiter = single_byte("delimiter", self.delimiter)?;
+        let quote = single_byte("quote", self.quote)?;
+        if delimiter == quote {
+            return Err(Error::InvalidConfig("delimiter and quote must differ".to_string()));
+        }
+        Ok((delimiter, quote))
+    }
+
+    pub fn reader(&self) -> Result<csv::ReaderBuilder> {
+        let (delimiter, quote) = self.bytes()?;
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(delimiter)
+            .quote(quote)
+            .has_headers(self.has_headers)
+            .flexible(self.flexible);
+        Ok(builder)
+    }
+
+    pub fn writer(&self) -> Result<csv::WriterBuilder> {
+        let (delimiter, quote) = self.bytes()?;
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(delimiter)
+            .quote(quote)
+            .has_headers(self.has_headers)
+            .flexible(self.flexible);
+        Ok(builder)
+    }
+}
+
+// Column names reported for files read with `has_headers: false`
+pub(super) fn synthesized_headers(width: usize) -> Vec<String> {
+    (0..width).map(|i| format!("col_{}", i)).collect()
+}
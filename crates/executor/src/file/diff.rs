@@ -0,0 +1,138 @@
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use similar::TextDiff;
+use std::path::Path;
+
+use super::sync::digest;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    // Equal or not, with a digest of each side
+    #[default]
+    Binary,
+    // Unified diff of the text
+    Lines,
+    // JSON Patch (RFC 6902) turning left into right
+    Json,
+}
+
+fn read_text(path: &Path, display: &str) -> Result<String> {
+    String::from_utf8(std::fs::read(path)?).map_err(|_| Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("{} is not valid UTF-8; use mode binary", display),
+    )))
+}
+
+fn read_json(path: &Path, display: &str) -> Result<Value> {
+    serde_json::from_str(&read_text(path, display)?).map_err(|e| Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Invalid JSON in {}: {}", display, e),
+    )))
+}
+
+fn compare(mode: Mode, left: (&Path, &str), right: (&Path, &str), max_lines: usize) -> Result<Value> {
+    match mode {
+        Mode::Binary => {
+            let (left_digest, right_digest) = (hex::encode(digest(left.0)?), hex::encode(digest(right.0)?));
+            Ok(json!({
+                "identical": left_digest == right_digest,
+                "left_sha256": left_digest,
+                "right_sha256": right_digest,
+            }))
+        }
+        Mode::Lines => {
+            let (old, new) = (read_text(left.0, left.1)?, read_text(right.0, right.1)?);
+            let diff = TextDiff::from_lines(&old, &new);
+            let text = diff.unified_diff().header(left.1, right.1).to_string();
+            let total = text.lines().count();
+            let shown: Vec<&str> = text.lines().take(max_lines).collect();
+            let (mut added, mut removed) = (0, 0);
+            for change in diff.iter_all_changes() {
+                match change.tag() {
+                    similar::ChangeTag::Insert => added += 1,
+                    similar::ChangeTag::Delete => removed += 1,
+                    similar::ChangeTag::Equal => {}
+                }
+            }
+            Ok(json!({
+                "identical": old == new,
+                "diff": shown.iter().map(|line| format!("{}\n", line)).collect::<String>(),
+                "lines_added": added,
+                "lines_removed": removed,
+                "truncated": total > max_lines,
+            }))
+        }
+        Mode::Json => {
+            let patch = json_patch::diff(&read_json(left.0, left.1)?, &read_json(right.0, right.1)?);
+            Ok(json!({
+                "identical": patch.0.is_empty(),
+                "diff": patch,
+            }))
+        }
+    }
+}
+
+impl FileExecutor {
+    pub(super) async fn diff(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            left: String,
+            right: String,
+            #[serde(default)]
+            mode: Mode,
+            #[serde(default = "default_max_lines")]
+            max_lines: usize,
+            // Report an absent side instead of failing
+            #[serde(default)]
+            allow_missing: bool,
+        }
+
+        fn default_max_lines() -> usize { 1000 }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let left_path = self.resolve_path(&params.left)?;
+        let right_path = self.resolve_path(&params.right)?;
+        let mut missing = Vec::new();
+        for (side, path, display) in [("left", &left_path, &params.left), ("right", &right_path, &params.right)] {
+            if !tokio::fs::try_exists(path).await? {
+                if !params.allow_missing {
+                    return Err(Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("{} file {} does not exist; pass allow_missing: true to compare anyway", side, display),
+                    )));
+                }
+                missing.push(side);
+            }
+        }
+
+        let mut output = match missing.is_empty() {
+            true => {
+                let (mode, max_lines) = (params.mode, params.max_lines);
+                let (left, right) = (params.left.clone(), params.right.clone());
+                tokio::task::spawn_blocking(move || {
+                    compare(mode, (&left_path, &left), (&right_path, &right), max_lines)
+                })
+                .await
+                .map_err(|e| Error::Io(std::io::Error::other(e)))??
+            }
+            // Two absent files are the same absence
+            false => json!({ "identical": missing.len() == 2, "diff": null }),
+        };
+        output["left"] = json!(params.left);
+        output["right"] = json!(params.right);
+        output["mode"] = json!(params.mode);
+        output["missing"] = json!(missing);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,395 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeSet, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::external_sort::{merge_runs, reduce_runs, RunWriter, Scratch, ENTRY_OVERHEAD};
+use super::profile::ProfileFormat;
+use super::sketch::{hash_value, HyperLogLog};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum DistinctMode {
+    // External sort: sorted runs spilled to scratch, then a k-way merge
+    #[default]
+    Exact,
+    // Exact set up to `sample_size` values, then a bloom filter; may drop a
+    // fraction of values on false positives but never writes a duplicate
+    Approximate,
+}
+
+fn invalid_data(e: impl ToString) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+}
+
+fn column_index(reader: &mut csv::Reader<File>, path: &Path, column: &str) -> Result<usize> {
+    reader.headers().map_err(invalid_data)?
+        .iter()
+        .position(|h| h == column)
+        .ok_or_else(|| Error::InvalidConfig(format!(
+            "Column '{}' not found in {}", column, path.display()
+        )))
+}
+
+// Stream the values of one column (CSV header) or field (NDJSON key or JSON
+// pointer) to `f`. Returns (rows, missing) where missing rows have no value.
+fn scan_values(
+    path: &Path,
+    format: ProfileFormat,
+    column: &str,
+    f: &mut dyn FnMut(&str) -> Result<()>,
+) -> Result<(u64, u64)> {
+    let mut rows = 0u64;
+    let mut missing = 0u64;
+    match format {
+        ProfileFormat::Csv => {
+            let mut reader = csv::Reader::from_path(path).map_err(invalid_data)?;
+            let index = column_index(&mut reader, path, column)?;
+            let mut record = csv::StringRecord::new();
+            while reader.read_record(&mut record).map_err(invalid_data)? {
+                rows += 1;
+                match record.get(index) {
+                    Some(cell) if !cell.is_empty() => f(cell)?,
+                    _ => missing += 1,
+                }
+            }
+        }
+        ProfileFormat::Ndjson => {
+            let reader = BufReader::new(File::open(path)?);
+            for (line_no, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                rows += 1;
+                let object: Value = serde_json::from_str(&line).map_err(|e| {
+                    invalid_data(format!("{} line {}: {}", path.display(), line_no + 1, e))
+                })?;
+                let value = if column.starts_with('/') {
+                    object.pointer(column)
+                } else {
+                    object.as_object().and_then(|o: &Map<String, Value>| o.get(column))
+                };
+                match value {
+                    None | Some(Value::Null) => missing += 1,
+                    Some(Value::String(s)) if s.is_empty() => missing += 1,
+                    Some(Value::String(s)) => f(s)?,
+                    Some(other) => f(&other.to_string())?,
+                }
+            }
+        }
+    }
+    Ok((rows, missing))
+}
+
+// The dest file holds one value per line, so a value spanning lines can't be represented
+fn check_line(value: &str) -> Result<()> {
+    if value.contains(['\n', '\r']) {
+        return Err(invalid_data(format!("Value {:?} contains a line break", value)));
+    }
+    Ok(())
+}
+
+struct Bloom {
+    bits: Vec<u64>,
+    len: u64,
+    hashes: u32,
+}
+
+impl Bloom {
+    // Sized for `expected` items at `fp_rate`, but never above `max_bytes`
+    fn new(expected: u64, fp_rate: f64, max_bytes: usize) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let optimal = (-(expected.max(1) as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let len = optimal.clamp(64, (max_bytes as u64 * 8).max(64));
+        let hashes = ((len as f64 / expected.max(1) as f64) * ln2).round().clamp(1.0, 16.0) as u32;
+        Self { bits: vec![0; len.div_ceil(64) as usize], len, hashes }
+    }
+
+    // Sets the value's bits and reports whether they were all set already
+    fn check_and_insert(&mut self, value: &str) -> bool {
+        let h1 = hash_value(value);
+        let h2 = hash_value(&(value, 0x9e37_79b9_7f4a_7c15u64)) | 1;
+        let mut present = true;
+        for k in 0..self.hashes as u64 {
+            let bit = h1.wrapping_add(k.wrapping_mul(h2)) % self.len;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                present = false;
+                self.bits[word] |= mask;
+            }
+        }
+        present
+    }
+
+    fn false_positive_rate(&self, items: u64) -> f64 {
+        let k = self.hashes as f64;
+        (1.0 - (-k * items as f64 / self.len as f64).exp()).powf(k)
+    }
+}
+
+struct Exact {
+    memory_limit: usize,
+    set: BTreeSet<String>,
+    used: usize,
+    scratch: Scratch,
+    runs: Vec<PathBuf>,
+}
+
+impl Exact {
+    fn insert(&mut self, value: &str) -> Result<()> {
+        if self.set.contains(value) {
+            return Ok(());
+        }
+        self.used += value.len() + ENTRY_OVERHEAD;
+        self.set.insert(value.to_string());
+        if self.used >= self.memory_limit {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        let path = self.scratch.next_run();
+        let mut writer = RunWriter::create(&path)?;
+        for value in &self.set {
+            writer.write(&[value])?;
+        }
+        writer.finish()?;
+        self.runs.push(path);
+        self.set.clear();
+        self.used = 0;
+        Ok(())
+    }
+
+    // Writes the sorted distinct values to `out`, returning how many
+    fn finish(mut self, out: &mut dyn Write) -> Result<(u64, usize)> {
+        let mut written = 0u64;
+        if self.runs.is_empty() {
+            for value in &self.set {
+                writeln!(out, "{}", value)?;
+                written += 1;
+            }
+            return Ok((written, 0));
+        }
+
+        if !self.set.is_empty() {
+            self.spill()?;
+        }
+        let spilled = self.runs.len();
+        let runs = std::mem::take(&mut self.runs);
+        let runs = reduce_runs(&mut self.scratch, runs, 1)?;
+        // Each run is sorted and duplicate-free, so duplicates across runs are adjacent
+        let mut last: Option<String> = None;
+        merge_runs(&runs, 1, &mut |mut record| {
+            let value = record.pop().unwrap_or_default();
+            if last.as_deref() != Some(value.as_str()) {
+                writeln!(out, "{}", value)?;
+                written += 1;
+            }
+            last = Some(value);
+            Ok(())
+        })?;
+        Ok((written, spilled))
+    }
+}
+
+impl FileExecutor {
+    pub(super) async fn distinct(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            sources: Option<Vec<String>>,
+            pattern: Option<String>,
+            column: String,
+            format: Option<ProfileFormat>,
+            dest: String,
+            #[serde(default)]
+            mode: DistinctMode,
+            memory_limit_bytes: Option<usize>,
+            // Where exact mode spills sorted runs; defaults to dest's directory
+            scratch_dir: Option<String>,
+            #[serde(default = "default_expected_items")]
+            expected_items: u64,
+            #[serde(default = "default_false_positive_rate")]
+            false_positive_rate: f64,
+            #[serde(default = "default_sample_size")]
+            sample_size: usize,
+        }
+
+        fn default_expected_items() -> u64 { 10_000_000 }
+        fn default_false_positive_rate() -> f64 { 0.01 }
+        fn default_sample_size() -> usize { 100_000 }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let memory_limit = params.memory_limit_bytes.unwrap_or(DEFAULT_MEMORY_LIMIT);
+        if memory_limit == 0 {
+            return Err(Error::InvalidConfig("memory_limit_bytes must be positive".to_string()));
+        }
+        if !(params.false_positive_rate > 0.0 && params.false_positive_rate < 1.0) {
+            return Err(Error::InvalidConfig(
+                "false_positive_rate must be between 0 and 1".to_string()
+            ));
+        }
+
+        let sources = self.concat_sources(params.sources, params.pattern).await?;
+        let dest_path = self.resolve_path(&params.dest)?;
+        if sources.iter().any(|(_, p)| *p == dest_path) {
+            return Err(Error::InvalidConfig("dest must not be one of the sources".to_string()));
+        }
+        let sources = sources.into_iter()
+            .map(|(name, path)| {
+                let format = params.format
+                    .or_else(|| ProfileFormat::from_extension(&path))
+                    .ok_or_else(|| Error::InvalidConfig(format!(
+                        "Cannot infer format of {}; pass format: csv or ndjson", name
+                    )))?;
+                Ok((name, path, format))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let scratch_parent = match &params.scratch_dir {
+            Some(dir) => self.resolve_path(dir)?,
+            None => dest_path.parent().map(Path::to_path_buf).unwrap_or_else(|| self.base_path.clone()),
+        };
+
+        let mode = params.mode;
+        let column = params.column;
+        let output_path = dest_path.clone();
+        let (summary, counts) = tokio::task::spawn_blocking(move || {
+            // A missing column must not cost an existing dest
+            for (_, path, format) in &sources {
+                if matches!(format, ProfileFormat::Csv) {
+                    column_index(&mut csv::Reader::from_path(path).map_err(invalid_data)?, path, &column)?;
+                }
+            }
+            let mut out = BufWriter::new(File::create(&dest_path)?);
+            let mut counts = Vec::new();
+            let mut rows = 0u64;
+            let mut missing = 0u64;
+
+            let summary = match mode {
+                DistinctMode::Exact => {
+                    let mut exact = Exact {
+                        memory_limit,
+                        set: BTreeSet::new(),
+                        used: 0,
+                        scratch: Scratch::create(&scratch_parent, "distinct")?,
+                        runs: Vec::new(),
+                    };
+                    for (name, path, format) in &sources {
+                        let (r, m) = scan_values(path, *format, &column, &mut |value| {
+                            check_line(value)?;
+                            exact.insert(value)
+                        })?;
+                        rows += r;
+                        missing += m;
+                        counts.push(json!({ "path": name, "rows": r }));
+                    }
+                    let (distinct, runs) = exact.finish(&mut out)?;
+                    json!({ "distinct": distinct, "exact": true, "runs_spilled": runs })
+                }
+                DistinctMode::Approximate => {
+                    // The exact sample and the filter share the memory budget
+                    let sample_budget = memory_limit / 2;
+                    let mut sample: HashSet<String> = HashSet::new();
+                    let mut sample_used = 0usize;
+                    let mut bloom: Option<Bloom> = None;
+                    let mut hll = HyperLogLog::new();
+                    let mut written = 0u64;
+                    let mut bloom_items = 0u64;
+
+                    for (name, path, format) in &sources {
+                        let (r, m) = scan_values(path, *format, &column, &mut |value| {
+                            check_line(value)?;
+                            hll.insert(value);
+                            if sample.contains(value) {
+                                return Ok(());
+                            }
+                            let fresh = match bloom.as_mut() {
+                                None if sample.len() < params.sample_size
+                                    && sample_used + value.len() + ENTRY_OVERHEAD <= sample_budget =>
+                                {
+                                    sample_used += value.len() + ENTRY_OVERHEAD;
+                                    sample.insert(value.to_string());
+                                    true
+                                }
+                                _ => {
+                                    // Sample is full: everything beyond it goes through the
+                                    // filter, seeded with the sampled values
+                                    let bloom = bloom.get_or_insert_with(|| {
+                                        let mut bloom = Bloom::new(
+                                            params.expected_items,
+                                            params.false_positive_rate,
+                                            memory_limit - sample_budget,
+                                        );
+                                        for v in &sample {
+                                            bloom.check_and_insert(v);
+                                        }
+                                        bloom
+                                    });
+                                    let fresh = !bloom.check_and_insert(value);
+                                    if fresh {
+                                        bloom_items += 1;
+                                    }
+                                    fresh
+                                }
+                            };
+                            if fresh {
+                                writeln!(out, "{}", value)?;
+                                written += 1;
+                            }
+                            Ok(())
+                        })?;
+                        rows += r;
+                        missing += m;
+                        counts.push(json!({ "path": name, "rows": r }));
+                    }
+
+                    let filter = bloom.as_ref().map(|b| json!({
+                        "bits": b.len,
+                        "hashes": b.hashes,
+                        "estimated_false_positive_rate":
+                            b.false_positive_rate(bloom_items + sample.len() as u64),
+                    }));
+                    json!({
+                        "distinct": written,
+                        "exact": bloom.is_none(),
+                        "distinct_estimate": hll.estimate().max(written),
+                        "bloom": filter,
+                    })
+                }
+            };
+            out.flush()?;
+
+            let mut summary = summary;
+            summary["rows_scanned"] = json!(rows);
+            summary["missing"] = json!(missing);
+            Ok::<_, Error>((summary, counts))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        self.settle(&output_path, None).await?;
+        let mut output = summary;
+        output["path"] = json!(output_path);
+        output["mode"] = json!(match mode {
+            DistinctMode::Exact => "exact",
+            DistinctMode::Approximate => "approximate",
+        });
+        output["sources"] = json!(counts);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
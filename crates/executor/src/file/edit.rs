@@ -0,0 +1,382 @@
+use local_automation_common::{Error, Result, Task};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::fs;
+
+use super::lock::HeldLock;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+// Under base_path rather than next to the targets, so edited directories stay
+// clean. Lock files are never removed: a waiter may already have one open.
+pub(super) const LOCK_ROOT: &str = ".locks";
+const WORKING_COPY_MARKER: &str = ".edit-";
+
+// An open edit: edits go to `working`, a copy next to the target, and only reach
+// the target on commit. Dropping the session (abort, expiry, executor shutdown)
+// discards the copy and releases the per-path lock.
+pub(super) struct EditSession {
+    path: PathBuf,
+    working: PathBuf,
+    expires_at: Instant,
+    edits: Vec<Value>,
+    _lock: HeldLock,
+}
+
+impl Drop for EditSession {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.working);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Edit {
+    RegexReplace {
+        pattern: String,
+        replacement: String,
+        // 0 replaces every match
+        #[serde(default)]
+        limit: usize,
+    },
+    JsonPatch {
+        patch: json_patch::Patch,
+    },
+    // Insert before 1-based line `at`; one past the last line appends
+    InsertLines {
+        at: usize,
+        lines: Vec<String>,
+    },
+    // 1-based, inclusive
+    DeleteLines {
+        start: usize,
+        end: usize,
+    },
+    IniSet {
+        section: Option<String>,
+        key: String,
+        value: String,
+    },
+}
+
+fn line_ending(text: &str) -> &'static str {
+    if text.contains("\r\n") { "\r\n" } else { "\n" }
+}
+
+fn ini_set(text: &str, section: Option<&str>, key: &str, value: &str) -> (String, &'static str) {
+    let eol = line_ending(text);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let is_header = |l: &str| l.trim().starts_with('[') && l.trim().ends_with(']');
+
+    // Line range of the wanted section's body
+    let start = match section {
+        None => Some(0),
+        Some(name) => lines.iter()
+            .position(|l| is_header(l) && l.trim()[1..l.trim().len() - 1].trim() == name)
+            .map(|i| i + 1),
+    };
+    let Some(start) = start else {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("[{}]", section.unwrap_or_default()));
+        lines.push(format!("{} = {}", key, value));
+        return (lines.join(eol) + eol, "added_section");
+    };
+    let end = lines[start..].iter().position(|l| is_header(l)).map_or(lines.len(), |i| start + i);
+
+    for line in &mut lines[start..end] {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with(';') || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some((lhs, rhs)) = line.split_once('=') {
+            if lhs.trim() == key {
+                let space = if rhs.starts_with(' ') { " " } else { "" };
+                *line = format!("{}={}{}", lhs, space, value);
+                return (lines.join(eol) + eol, "updated");
+            }
+        }
+    }
+
+    // New key goes after the section's last non-blank line
+    let at = lines[start..end].iter().rposition(|l| !l.trim().is_empty()).map_or(start, |i| start + i + 1);
+    lines.insert(at, format!("{} = {}", key, value));
+    (lines.join(eol) + eol, "added")
+}
+
+impl Edit {
+    // Returns the edited text and a summary for the session log
+    fn apply(&self, text: String) -> Result<(String, Value)> {
+        match self {
+            Edit::RegexReplace { pattern, replacement, limit } => {
+                let regex = Regex::new(pattern).map_err(|e| Error::InvalidConfig(e.to_string()))?;
+                let found = regex.find_iter(&text).count();
+                let replaced = if *limit == 0 { found } else { found.min(*limit) };
+                let edited = regex.replacen(&text, *limit, replacement.as_str()).into_owned();
+                Ok((edited, json!({ "replacements": replaced })))
+            }
+            Edit::JsonPatch { patch } => {
+                let mut doc: Value = serde_json::from_str(&text)?;
+                json_patch::patch(&mut doc, patch)
+                    .map_err(|e| Error::InvalidConfig(format!("JSON patch failed: {}", e)))?;
+                Ok((serde_json::to_string_pretty(&doc)? + "\n", json!({ "operations": patch.0.len() })))
+            }
+            Edit::InsertLines { at, lines } => {
+                let eol = line_ending(&text);
+                let mut existing: Vec<&str> = text.split_inclusive('\n').collect();
+                if *at == 0 || *at > existing.len() + 1 {
+                    return Err(Error::InvalidConfig(format!(
+                        "Line {} is outside 1..={}", at, existing.len() + 1
+                    )));
+                }
+                let inserted: Vec<String> = lines.iter().map(|l| format!("{}{}", l, eol)).collect();
+                // Appending after a last line without a newline must not glue them together
+                let mut fixed_last = None;
+                if *at == existing.len() + 1 {
+                    if let Some(last) = existing.last().filter(|l| !l.ends_with('\n')) {
+                        fixed_last = Some(format!("{}{}", last, eol));
+                    }
+                }
+                if let Some(last) = &fixed_last {
+                    *existing.last_mut().unwrap() = last;
+                }
+                existing.splice(at - 1..at - 1, inserted.iter().map(String::as_str));
+                Ok((existing.concat(), json!({ "inserted": lines.len() })))
+            }
+            Edit::DeleteLines { start, end } => {
+                let mut existing: Vec<&str> = text.split_inclusive('\n').collect();
+                if *start == 0 || start > end || *end > existing.len() {
+                    return Err(Error::InvalidConfig(format!(
+                        "Lines {}..={} are outside 1..={}", start, end, existing.len()
+                    )));
+                }
+                existing.drain(start - 1..*end);
+                Ok((existing.concat(), json!({ "deleted": end - start + 1 })))
+            }
+            Edit::IniSet { section, key, value } => {
+                let (edited, action) = ini_set(&text, section.as_deref(), key, value);
+                Ok((edited, json!({ "action": action })))
+            }
+        }
+    }
+}
+
+// `.<name>.edit-<session id>`, as edit_begin names working copies
+pub(super) fn is_working_copy(file_name: &str) -> bool {
+    file_name.starts_with('.')
+        && file_name.rsplit_once(WORKING_COPY_MARKER).is_some_and(|(_, id)| uuid::Uuid::parse_str(id).is_ok())
+}
+
+impl FileExecutor {
+    // Take a session out of the table for exclusive use; expired ones are dropped
+    fn take_session(&self, session_id: &str) -> Result<EditSession> {
+        let session = self.edit_sessions.lock().unwrap().remove(session_id);
+        match session {
+            Some(session) if Instant::now() < session.expires_at => Ok(session),
+            Some(_) => Err(Error::InvalidConfig(format!(
+                "Edit session {} expired and was aborted", session_id
+            ))),
+            None => Err(Error::InvalidConfig(format!("Unknown edit session: {}", session_id))),
+        }
+    }
+
+    pub(super) async fn edit_begin(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            timeout_secs: Option<u64>,
+            // How long to wait for another session on the same path
+            #[serde(default)]
+            wait_ms: u64,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        if !fs::metadata(&full_path).await?.is_file() {
+            return Err(Error::InvalidConfig(format!("{} is not a file", params.path)));
+        }
+
+        let lock_dir = self.base_path.join(LOCK_ROOT);
+        fs::create_dir_all(&lock_dir).await?;
+        let digest = hex::encode(Sha256::digest(full_path.as_os_str().as_encoded_bytes()));
+        let lock_path = lock_dir.join(format!("{}.edit.lock", digest));
+        let (lock, _) = HeldLock::acquire(lock_path, Duration::from_millis(params.wait_ms), None)
+            .await
+            .map_err(|e| match e {
+                Error::Timeout => Error::InvalidConfig(format!(
+                    "Another edit session is open on {}", params.path
+                )),
+                other => other,
+            })?;
+
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let name = full_path.file_name().unwrap_or_default().to_string_lossy();
+        // Same directory as the target so the commit rename stays atomic
+        let working = full_path.with_file_name(format!(".{}{}{}", name, WORKING_COPY_MARKER, session_id));
+        let bytes = fs::copy(&full_path, &working).await?;
+
+        let timeout = Duration::from_secs(params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let session = EditSession {
+            path: full_path.clone(),
+            working,
+            expires_at: Instant::now() + timeout,
+            edits: Vec::new(),
+            _lock: lock,
+        };
+        self.edit_sessions.lock().unwrap().insert(session_id.clone(), session);
+
+        // Auto-abort if nobody commits in time
+        let sessions = Arc::downgrade(&self.edit_sessions);
+        let id = session_id.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if let Some(sessions) = sessions.upgrade() {
+                let expired = sessions.lock().unwrap().remove(&id);
+                drop(expired);
+            }
+        });
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "session_id": session_id,
+                "path": full_path,
+                "bytes": bytes,
+                "timeout_secs": timeout.as_secs(),
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn edit_apply(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            session_id: String,
+            edits: Vec<Value>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let mut session = self.take_session(&params.session_id)?;
+        // Everything is applied in memory first, so a failing edit leaves the
+        // working copy and the session exactly as they were.
+        let result = async {
+            let mut text = fs::read_to_string(&session.working).await?;
+            let mut applied = Vec::new();
+            for raw in &params.edits {
+                let edit: Edit = serde_json::from_value(raw.clone())
+                    .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+                let (edited, summary) = edit.apply(text)?;
+                text = edited;
+                applied.push(json!({ "edit": raw, "result": summary }));
+            }
+            fs::write(&session.working, &text).await?;
+            Ok::<_, Error>(applied)
+        }
+        .await;
+
+        let applied = match result {
+            Ok(applied) => applied,
+            Err(e) => {
+                self.edit_sessions.lock().unwrap().insert(params.session_id, session);
+                return Err(e);
+            }
+        };
+        session.edits.extend(applied.iter().cloned());
+        let total = session.edits.len();
+        self.edit_sessions.lock().unwrap().insert(params.session_id.clone(), session);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "session_id": params.session_id,
+                "applied": applied,
+                "total_edits": total,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn edit_commit(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            session_id: String,
+            #[serde(default)]
+            backup: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let session = self.take_session(&params.session_id)?;
+        let result = async {
+            let backup = if params.backup {
+                let mut backup = session.path.as_os_str().to_os_string();
+                backup.push(".bak");
+                let backup = PathBuf::from(backup);
+                fs::copy(&session.path, &backup).await?;
+                Some(backup)
+            } else {
+                None
+            };
+            fs::rename(&session.working, &session.path).await?;
+            Ok::<_, Error>(backup)
+        }
+        .await;
+
+        // The staged edits survive a failed commit, which can be retried or aborted
+        let backup = match result {
+            Ok(backup) => backup,
+            Err(e) => {
+                self.edit_sessions.lock().unwrap().insert(params.session_id, session);
+                return Err(e);
+            }
+        };
+        self.settle(&session.path, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "session_id": params.session_id,
+                "path": session.path,
+                "committed": true,
+                "backup": backup,
+                "edits": session.edits,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn edit_abort(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            session_id: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let session = self.take_session(&params.session_id)?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "session_id": params.session_id,
+                "path": session.path,
+                "aborted": true,
+                "edits": session.edits,
+            })),
+            error: None,
+        })
+    }
+}
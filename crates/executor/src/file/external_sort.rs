@@ -0,0 +1,226 @@
+use local_automation_common::{Error, Result};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+// Runs merged at once; more than this are merged in several passes
+const MAX_FAN_IN: usize = 64;
+// Rough per-record bookkeeping cost on top of the field bytes
+pub(super) const ENTRY_OVERHEAD: usize = 64;
+
+// Removes the scratch directory however the operation ends
+pub(super) struct Scratch {
+    dir: PathBuf,
+    runs: usize,
+}
+
+impl Scratch {
+    pub(super) fn create(parent: &Path, label: &str) -> Result<Self> {
+        let dir = parent.join(format!(".{}-{}", label, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, runs: 0 })
+    }
+
+    pub(super) fn next_run(&mut self) -> PathBuf {
+        self.runs += 1;
+        self.dir.join(format!("run-{}", self.runs))
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+// A run is a sequence of records with a fixed number of fields. Fields are
+// length-prefixed so they may hold any bytes.
+pub(super) struct RunWriter {
+    writer: BufWriter<File>,
+}
+
+impl RunWriter {
+    pub(super) fn create(path: &Path) -> Result<Self> {
+        Ok(Self { writer: BufWriter::new(File::create(path)?) })
+    }
+
+    pub(super) fn write(&mut self, fields: &[&str]) -> Result<()> {
+        for field in fields {
+            self.writer.write_all(&(field.len() as u32).to_le_bytes())?;
+            self.writer.write_all(field.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn finish(mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub(super) struct RunReader {
+    reader: BufReader<File>,
+    fields: usize,
+}
+
+impl RunReader {
+    fn open(path: &Path, fields: usize) -> Result<Self> {
+        Ok(Self { reader: BufReader::new(File::open(path)?), fields })
+    }
+
+    fn next(&mut self) -> Result<Option<Vec<String>>> {
+        let mut record = Vec::with_capacity(self.fields);
+        for i in 0..self.fields {
+            let mut len = [0u8; 4];
+            match self.reader.read_exact(&mut len) {
+                Ok(()) => {}
+                Err(e) if i == 0 && e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e.into()),
+            }
+            let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+            self.reader.read_exact(&mut bytes)?;
+            let field = String::from_utf8(bytes).map_err(|e| {
+                Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            })?;
+            record.push(field);
+        }
+        Ok(Some(record))
+    }
+}
+
+// K-way merge of sorted runs, emitting every record in sorted order
+pub(super) fn merge_runs(
+    runs: &[PathBuf],
+    fields: usize,
+    emit: &mut dyn FnMut(Vec<String>) -> Result<()>,
+) -> Result<()> {
+    let mut readers = runs.iter().map(|p| RunReader::open(p, fields)).collect::<Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::new();
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some(record) = reader.next()? {
+            heap.push(Reverse((record, i)));
+        }
+    }
+
+    while let Some(Reverse((record, i))) = heap.pop() {
+        if let Some(next) = readers[i].next()? {
+            heap.push(Reverse((next, i)));
+        }
+        emit(record)?;
+    }
+    Ok(())
+}
+
+// Merge groups of runs until at most MAX_FAN_IN remain, so the final merge
+// never holds too many files open
+pub(super) fn reduce_runs(scratch: &mut Scratch, mut runs: Vec<PathBuf>, fields: usize) -> Result<Vec<PathBuf>> {
+    while runs.len() > MAX_FAN_IN {
+        let mut merged = Vec::new();
+        for group in runs.chunks(MAX_FAN_IN) {
+            let path = scratch.next_run();
+            let mut writer = RunWriter::create(&path)?;
+            merge_runs(group, fields, &mut |record| {
+                let fields: Vec<&str> = record.iter().map(String::as_str).collect();
+                writer.write(&fields)
+            })?;
+            writer.finish()?;
+            for run in group {
+                std::fs::remove_file(run)?;
+            }
+            merged.push(path);
+        }
+        runs = merged;
+    }
+    Ok(runs)
+}
+
+// Sorts records (compared field by field) that may not fit in memory
+pub(super) struct ExternalSorter {
+    fields: usize,
+    memory_limit: usize,
+    buffer: Vec<Vec<String>>,
+    used: usize,
+    scratch: Scratch,
+    runs: Vec<PathBuf>,
+}
+
+impl ExternalSorter {
+    pub(super) fn new(scratch: Scratch, fields: usize, memory_limit: usize) -> Self {
+        Self { fields, memory_limit, buffer: Vec::new(), used: 0, scratch, runs: Vec::new() }
+    }
+
+    pub(super) fn insert(&mut self, record: Vec<String>) -> Result<()> {
+        debug_assert_eq!(record.len(), self.fields);
+        self.used += record.iter().map(String::len).sum::<usize>() + ENTRY_OVERHEAD;
+        self.buffer.push(record);
+        if self.used >= self.memory_limit {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        self.buffer.sort_unstable();
+        let path = self.scratch.next_run();
+        let mut writer = RunWriter::create(&path)?;
+        for record in self.buffer.drain(..) {
+            let fields: Vec<&str> = record.iter().map(String::as_str).collect();
+            writer.write(&fields)?;
+        }
+        writer.finish()?;
+        self.runs.push(path);
+        self.used = 0;
+        Ok(())
+    }
+
+    // Sorted stream of everything inserted, plus how many runs were spilled
+    pub(super) fn finish(mut self) -> Result<(SortedRecords, usize)> {
+        if self.runs.is_empty() {
+            self.buffer.sort_unstable();
+            let records = std::mem::take(&mut self.buffer);
+            return Ok((SortedRecords::Memory(records.into_iter()), 0));
+        }
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+        let spilled = self.runs.len();
+        let runs = std::mem::take(&mut self.runs);
+        let runs = reduce_runs(&mut self.scratch, runs, self.fields)?;
+        let mut readers = runs.iter().map(|p| RunReader::open(p, self.fields)).collect::<Result<Vec<_>>>()?;
+        let mut heap = BinaryHeap::new();
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if let Some(record) = reader.next()? {
+                heap.push(Reverse((record, i)));
+            }
+        }
+        Ok((SortedRecords::Merge { readers, heap, _scratch: self.scratch }, spilled))
+    }
+}
+
+pub(super) enum SortedRecords {
+    Memory(std::vec::IntoIter<Vec<String>>),
+    Merge {
+        readers: Vec<RunReader>,
+        heap: BinaryHeap<Reverse<(Vec<String>, usize)>>,
+        _scratch: Scratch,
+    },
+}
+
+impl SortedRecords {
+    pub(super) fn next(&mut self) -> Result<Option<Vec<String>>> {
+        match self {
+            SortedRecords::Memory(records) => Ok(records.next()),
+            SortedRecords::Merge { readers, heap, .. } => {
+                let Some(Reverse((record, i))) = heap.pop() else {
+                    return Ok(None);
+                };
+                if let Some(next) = readers[i].next()? {
+                    heap.push(Reverse((next, i)));
+                }
+                Ok(Some(record))
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use local_automation_common::{Error, Result, Task};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const READ_CHUNK: usize = 64 * 1024;
+// Applies when only max_matches / until_pattern is given, so a quiet file
+// can't hold the task forever
+const DEFAULT_MAX_DURATION_MS: u64 = 60_000;
+// Matches held before stopping early; `resume` picks up from there
+const MAX_BUFFERED_MATCHES: usize = 10_000;
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FollowFrom {
+    Offset(u64),
+    Named(FromNamed),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FromNamed {
+    Start,
+    End,
+}
+
+// Identity of the file behind a path, so rotation (rename + recreate) is noticed
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> u64 {
+    0
+}
+
+async fn open_at(path: &Path, offset: u64) -> Result<(fs::File, u64)> {
+    let mut file = fs::File::open(path).await?;
+    let id = file_id(&file.metadata().await?);
+    file.seek(SeekFrom::Start(offset)).await?;
+    Ok((file, id))
+}
+
+impl FileExecutor {
+    // `tail -f | grep`: collect lines matching `pattern` as they are appended,
+    // until a stop condition is hit. The returned `resume` value can be stored
+    // with set_state and passed back as `from_state` to continue where this run
+    // stopped. Without max_duration_ms it gives up after DEFAULT_MAX_DURATION_MS.
+    pub(super) async fn follow(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            pattern: String,
+            from: Option<FollowFrom>,
+            from_state: Option<String>,
+            max_duration_ms: Option<u64>,
+            max_matches: Option<usize>,
+            until_pattern: Option<String>,
+            #[serde(default = "default_poll_interval_ms")]
+            poll_interval_ms: u64,
+        }
+
+        fn default_poll_interval_ms() -> u64 { 250 }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.max_duration_ms.is_none() && params.max_matches.is_none() && params.until_pattern.is_none() {
+            return Err(Error::InvalidConfig(
+                "follow needs max_duration_ms, max_matches or until_pattern".to_string()
+            ));
+        }
+        let regex = |p: &str| Regex::new(p).map_err(|e| Error::InvalidConfig(e.to_string()));
+        let pattern = regex(&params.pattern)?;
+        let until = params.until_pattern.as_deref().map(regex).transpose()?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let metadata = fs::metadata(&full_path).await?;
+        let size = metadata.len();
+
+        let resume = match &params.from_state {
+            Some(key) => self.state_mark(key).await?,
+            None => None,
+        };
+        let start = match (&resume, &params.from) {
+            (Some(mark), _) => {
+                let (offset, id) = match mark {
+                    Value::Number(n) => (n.as_u64(), None),
+                    other => (other["offset"].as_u64(), other["file_id"].as_u64()),
+                };
+                let offset = offset.ok_or_else(|| Error::InvalidConfig(
+                    "Stored follow state has no offset".to_string()
+                ))?;
+                // Rotated or truncated since the previous run: the new file is unread
+                let rotated = id.is_some_and(|id| id != file_id(&metadata)) || offset > size;
+                if rotated { 0 } else { offset }
+            }
+            (None, Some(FollowFrom::Offset(offset))) => (*offset).min(size),
+            (None, Some(FollowFrom::Named(FromNamed::Start))) => 0,
+            (None, Some(FollowFrom::Named(FromNamed::End))) | (None, None) => size,
+        };
+
+        let max_duration = Duration::from_millis(params.max_duration_ms.unwrap_or(DEFAULT_MAX_DURATION_MS));
+        let deadline = Instant::now().checked_add(max_duration);
+        let poll_interval = Duration::from_millis(params.poll_interval_ms.max(1));
+        let (mut file, mut id) = open_at(&full_path, start).await?;
+        let mut offset = start;
+        // Bytes read past `offset` that don't form a complete line yet
+        let mut pending: Vec<u8> = Vec::new();
+        let mut buf = vec![0u8; READ_CHUNK];
+        let mut matches = Vec::new();
+        let mut rotations = 0u64;
+
+        let stopped = 'follow: loop {
+            loop {
+                let n = file.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                pending.extend_from_slice(&buf[..n]);
+
+                let mut consumed = 0;
+                while let Some(end) = pending[consumed..].iter().position(|&b| b == b'\n') {
+                    let raw = &pending[consumed..consumed + end];
+                    let line = String::from_utf8_lossy(raw.strip_suffix(b"\r").unwrap_or(raw));
+                    let line_offset = offset;
+                    offset += end as u64 + 1;
+                    consumed += end + 1;
+
+                    if pattern.is_match(&line) {
+                        matches.push(json!({ "offset": line_offset, "line": line }));
+                    }
+                    if until.as_ref().is_some_and(|u| u.is_match(&line)) {
+                        break 'follow "until_pattern";
+                    }
+                    if params.max_matches.is_some_and(|max| matches.len() >= max) {
+                        break 'follow "max_matches";
+                    }
+                    if matches.len() >= MAX_BUFFERED_MATCHES {
+                        break 'follow "match_limit";
+                    }
+                }
+                pending.drain(..consumed);
+                if pending.len() > MAX_LINE_BYTES {
+                    return Err(Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Line at offset {} is longer than {} bytes", offset, MAX_LINE_BYTES),
+                    )));
+                }
+            }
+
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                break "max_duration";
+            }
+
+            // The old handle is drained; now check whether the path moved on
+            match fs::metadata(&full_path).await {
+                Ok(current) if file_id(&current) != id => {
+                    (file, id) = open_at(&full_path, 0).await?;
+                    offset = 0;
+                    pending.clear();
+                    rotations += 1;
+                }
+                Ok(current) if current.len() < offset + pending.len() as u64 => {
+                    file.seek(SeekFrom::Start(0)).await?;
+                    offset = 0;
+                    pending.clear();
+                    rotations += 1;
+                }
+                // Missing between rename and recreate; keep polling
+                _ => {}
+            }
+            tokio::time::sleep(poll_interval).await;
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": full_path,
+                "matches": matches,
+                "stopped": stopped,
+                "offset": offset,
+                "rotations": rotations,
+                "resume": { "offset": offset, "file_id": id },
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate};
+use local_automation_common::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+// Optional rendering settings accepted by operations that turn JSON values into text.
+// An explicit `date_format` / `decimal_separator` always wins over the locale preset.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct FormatParams {
+    pub locale: Option<String>,
+    pub date_format: Option<String>,
+    pub decimal_separator: Option<char>,
+}
+
+pub(super) struct ValueFormatter {
+    decimal_separator: char,
+    date_format: Option<String>,
+}
+
+// (decimal separator, date format) per locale tag
+fn locale_preset(locale: &str) -> Option<(char, &'static str)> {
+    let preset = match locale.to_ascii_lowercase().replace('_', "-").as_str() {
+        "en" | "en-us" => ('.', "%m/%d/%Y"),
+        "en-gb" => ('.', "%d/%m/%Y"),
+        "de" | "de-de" | "de-at" => (',', "%d.%m.%Y"),
+        "de-ch" => ('.', "%d.%m.%Y"),
+        "fr" | "fr-fr" => (',', "%d/%m/%Y"),
+        "nl" | "nl-nl" => (',', "%d-%m-%Y"),
+        "es" | "es-es" | "it" | "it-it" => (',', "%d/%m/%Y"),
+        "iso" => ('.', "%Y-%m-%d"),
+        _ => return None,
+    };
+    Some(preset)
+}
+
+impl ValueFormatter {
+    pub fn from_params(params: &FormatParams) -> Result<Self> {
+        let (mut decimal_separator, mut date_format) = ('.', None);
+
+        if let Some(locale) = &params.locale {
+            let (sep, fmt) = locale_preset(locale).ok_or_else(|| Error::InvalidConfig(
+                format!("Unknown locale: {}", locale)
+            ))?;
+            decimal_separator = sep;
+            date_format = Some(fmt.to_string());
+        }
+        if let Some(sep) = params.decimal_separator {
+            decimal_separator = sep;
+        }
+        if let Some(fmt) = &params.date_format {
+            if StrftimeItems::new(fmt).any(|item| matches!(item, Item::Error)) {
+                return Err(Error::InvalidConfig(
+                    format!("Invalid date_format: {}", fmt)
+                ));
+            }
+            date_format = Some(fmt.clone());
+        }
+
+        Ok(Self { decimal_separator, date_format })
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    pub fn date_format(&self) -> Option<&str> {
+        self.date_format.as_deref()
+    }
+
+    pub fn render(&self, value: &Value) -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => self.format_number(n),
+            Value::String(s) => self.format_string(s),
+            other => other.to_string(),
+        }
+    }
+
+    // serde_json prints the shortest representation that round-trips, so swapping
+    // the separator never loses precision.
+    pub fn format_number(&self, n: &serde_json::Number) -> String {
+        let text = n.to_string();
+        if self.decimal_separator == '.' {
+            text
+        } else {
+            text.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+
+    fn format_string(&self, s: &str) -> String {
+        let Some(fmt) = &self.date_format else {
+            return s.to_string();
+        };
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return date.format(fmt).to_string();
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+            return datetime.format(fmt).to_string();
+        }
+        s.to_string()
+    }
+}
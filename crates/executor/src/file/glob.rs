@@ -0,0 +1,119 @@
+use globset::GlobBuilder;
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+pub(super) struct GlobMatch {
+    // Relative to base_path, always '/'-separated
+    pub relative: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+fn is_glob_component(component: &str) -> bool {
+    component.contains(['*', '?', '[', '{'])
+}
+
+pub(super) fn relative_string(base: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(base).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    Some(parts.join("/"))
+}
+
+impl FileExecutor {
+    // Expand `pattern` under base_path, sorted by relative path. Walking starts at the
+    // pattern's literal directory prefix so `reports/2024/*.csv` doesn't scan the world.
+    pub(super) async fn glob_matches(&self, pattern: &str) -> Result<Vec<GlobMatch>> {
+        // Same traversal guard as every other path param
+        self.resolve_path(pattern)?;
+        if Path::new(pattern).is_absolute() {
+            return Err(Error::PermissionDenied(
+                "Absolute glob patterns are not allowed".to_string()
+            ));
+        }
+
+        let matcher = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| Error::InvalidConfig(format!("Invalid glob pattern: {}", e)))?
+            .compile_matcher();
+
+        let prefix: PathBuf = pattern
+            .split('/')
+            .take_while(|c| !is_glob_component(c))
+            .collect();
+        let base = self.base_path.clone();
+        let root = base.join(&prefix);
+        let internal = self.internal_paths();
+
+        tokio::task::spawn_blocking(move || {
+            if !root.exists() {
+                return Ok(Vec::new());
+            }
+            let mut matches = Vec::new();
+            let walker = WalkDir::new(&root)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|e| !internal.contains(e.path()));
+            for entry in walker {
+                let entry = entry.map_err(|e| Error::Io(e.into()))?;
+                let Some(relative) = relative_string(&base, entry.path()) else {
+                    continue;
+                };
+                if relative.is_empty() || !matcher.is_match(&relative) {
+                    continue;
+                }
+                matches.push(GlobMatch {
+                    relative,
+                    path: entry.path().to_path_buf(),
+                    is_dir: entry.file_type().is_dir(),
+                });
+            }
+            matches.sort_by(|a, b| a.relative.cmp(&b.relative));
+            Ok(matches)
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+
+    pub(super) async fn glob(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            pattern: String,
+            max_results: Option<usize>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let mut matches = self.glob_matches(&params.pattern).await?;
+        let total = matches.len();
+        if let Some(max) = params.max_results {
+            matches.truncate(max);
+        }
+        let entries: Vec<serde_json::Value> = matches.iter()
+            .map(|m| serde_json::json!({ "path": m.relative, "is_dir": m.is_dir }))
+            .collect();
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "pattern": params.pattern,
+                "matches": entries,
+                "total": total,
+                "truncated": matches.len() < total,
+            })),
+            error: None,
+        })
+    }
+}
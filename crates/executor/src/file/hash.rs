@@ -0,0 +1,65 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::digest::DynDigest;
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const ALGORITHMS: &[&str] = &["sha256", "sha1", "md5"];
+
+fn hasher(algorithm: &str) -> Result<Box<dyn DynDigest + Send>> {
+    match algorithm {
+        "sha256" => Ok(Box::new(sha2::Sha256::default())),
+        "sha1" => Ok(Box::new(sha1::Sha1::default())),
+        "md5" => Ok(Box::new(md5::Md5::default())),
+        other => Err(Error::InvalidConfig(format!(
+            "Unknown hash algorithm '{}'; supported: {}", other, ALGORITHMS.join(", ")
+        ))),
+    }
+}
+
+impl FileExecutor {
+    pub(super) async fn hash(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default = "default_algorithm")]
+            algorithm: String,
+        }
+
+        fn default_algorithm() -> String { "sha256".to_string() }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let algorithm = params.algorithm.to_ascii_lowercase();
+        let mut hasher = hasher(&algorithm)?;
+        let full_path = self.resolve_path(&params.path)?;
+        let mut file = fs::File::open(&full_path).await?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let mut size = 0u64;
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "algorithm": algorithm,
+                "digest": hex::encode(hasher.finalize()),
+                "size": size,
+            })),
+            error: None,
+        })
+    }
+}
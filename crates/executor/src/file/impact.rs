@@ -0,0 +1,99 @@
+use local_automation_common::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::FileExecutor;
+
+// Refuse destructive operations that would remove more than this unless the
+// task confirms the impact. Unset limits are not checked.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImpactLimits {
+    pub max_files: Option<u64>,
+    pub max_bytes: Option<u64>,
+    // Share of base_path's files or bytes, e.g. 0.4
+    pub max_fraction: Option<f64>,
+}
+
+// What a destructive operation would remove
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub(super) struct Impact {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+// `confirm_impact` on a task: the author has seen a preview this large
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub(super) struct ConfirmImpact {
+    pub max_files: u64,
+    pub max_bytes: u64,
+}
+
+fn measure(paths: &[PathBuf]) -> Result<Impact> {
+    let mut impact = Impact::default();
+    for path in paths {
+        for entry in WalkDir::new(path).follow_links(false) {
+            let entry = entry.map_err(|e| Error::Io(e.into()))?;
+            if !entry.file_type().is_dir() {
+                impact.files += 1;
+                impact.bytes += entry.metadata().map_err(|e| Error::Io(e.into()))?.len();
+            }
+        }
+    }
+    Ok(impact)
+}
+
+impl FileExecutor {
+    pub(super) async fn measure_impact(&self, paths: Vec<PathBuf>) -> Result<Impact> {
+        tokio::task::spawn_blocking(move || measure(&paths))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+
+    // Measure what removing `paths` would cost and refuse if it crosses the
+    // configured limits without a confirmation that covers it
+    pub(super) async fn guard_impact(
+        &self,
+        operation: &str,
+        paths: &[&Path],
+        confirm: Option<ConfirmImpact>,
+    ) -> Result<Impact> {
+        let Some(limits) = self.impact_limits else {
+            return Ok(Impact::default());
+        };
+        let impact = self.measure_impact(paths.iter().map(|p| p.to_path_buf()).collect()).await?;
+
+        if let Some(confirm) = confirm {
+            if impact.files <= confirm.max_files && impact.bytes <= confirm.max_bytes {
+                return Ok(impact);
+            }
+            return Err(Error::PermissionDenied(format!(
+                "{} would remove {} files ({} bytes), more than confirm_impact allows ({} files, {} bytes)",
+                operation, impact.files, impact.bytes, confirm.max_files, confirm.max_bytes
+            )));
+        }
+
+        let mut exceeded = Vec::new();
+        if limits.max_files.is_some_and(|max| impact.files > max) {
+            exceeded.push(format!("max_files {}", limits.max_files.unwrap_or_default()));
+        }
+        if limits.max_bytes.is_some_and(|max| impact.bytes > max) {
+            exceeded.push(format!("max_bytes {}", limits.max_bytes.unwrap_or_default()));
+        }
+        if let Some(fraction) = limits.max_fraction {
+            let total = self.measure_impact(vec![self.base_path.clone()]).await?;
+            let share = |part: u64, whole: u64| if whole == 0 { 0.0 } else { part as f64 / whole as f64 };
+            let worst = share(impact.files, total.files).max(share(impact.bytes, total.bytes));
+            if worst > fraction {
+                exceeded.push(format!("{:.0}% of base_path ({:.0}% allowed)", worst * 100.0, fraction * 100.0));
+            }
+        }
+        if exceeded.is_empty() {
+            return Ok(impact);
+        }
+        Err(Error::PermissionDenied(format!(
+            "{} would remove {} files ({} bytes), exceeding {}; pass confirm_impact: {{\"max_files\": {}, \"max_bytes\": {}}} to proceed",
+            operation, impact.files, impact.bytes, exceeded.join(", "), impact.files, impact.bytes
+        )))
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+use super::edit::{is_working_copy, LOCK_ROOT};
+use super::txn::STAGING_ROOT;
+use super::FileExecutor;
+
+// What the executor keeps for itself under base_path: the quarantine dir,
+// transaction staging, edit locks and edit working copies. Listings and tree
+// walks skip these, so in-flight bookkeeping is never reported or copied.
+#[derive(Debug, Clone)]
+pub(super) struct InternalPaths {
+    roots: Vec<PathBuf>,
+}
+
+impl InternalPaths {
+    pub fn contains(&self, path: &Path) -> bool {
+        self.roots.iter().any(|root| root == path)
+            || path.file_name().is_some_and(|name| is_working_copy(&name.to_string_lossy()))
+    }
+}
+
+impl FileExecutor {
+    pub(super) fn internal_paths(&self) -> InternalPaths {
+        let mut roots = vec![self.base_path.join(STAGING_ROOT), self.base_path.join(LOCK_ROOT)];
+        roots.extend(self.quarantine.clone());
+        InternalPaths { roots }
+    }
+}
@@ -0,0 +1,449 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_PAGE_LIMIT: usize = 1000;
+
+// Minimal incremental JSON tokenizer. It never holds more than one array element
+// in memory; skipped values are only checked for balanced brackets and valid
+// scalars, captured ones are fully validated by serde_json.
+struct Scanner<R> {
+    reader: R,
+    offset: u64,
+}
+
+impl<R: BufRead> Scanner<R> {
+    fn new(reader: R) -> Self {
+        Self { reader, offset: 0 }
+    }
+
+    fn error(&self, offset: u64, msg: impl std::fmt::Display) -> Error {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid JSON at byte offset {}: {}", offset, msg),
+        ))
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>> {
+        let byte = self.peek()?;
+        if byte.is_some() {
+            self.reader.consume(1);
+            self.offset += 1;
+        }
+        Ok(byte)
+    }
+
+    fn skip_ws(&mut self) -> Result<()> {
+        while let Some(b) = self.peek()? {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, want: u8) -> Result<()> {
+        self.skip_ws()?;
+        let at = self.offset;
+        match self.bump()? {
+            Some(b) if b == want => Ok(()),
+            Some(b) => Err(self.error(at, format!("expected '{}', found '{}'", want as char, b as char))),
+            None => Err(self.error(at, format!("unexpected end of input, expected '{}'", want as char))),
+        }
+    }
+
+    fn string(&mut self, mut out: Option<&mut Vec<u8>>) -> Result<()> {
+        let start = self.offset;
+        self.bump()?;
+        if let Some(out) = out.as_deref_mut() {
+            out.push(b'"');
+        }
+        let mut escaped = false;
+        loop {
+            let Some(b) = self.bump()? else {
+                return Err(self.error(start, "unterminated string"));
+            };
+            if let Some(out) = out.as_deref_mut() {
+                out.push(b);
+            }
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    fn scalar(&mut self, out: Option<&mut Vec<u8>>) -> Result<()> {
+        let start = self.offset;
+        let mut raw = Vec::new();
+        while let Some(b) = self.peek()? {
+            if b.is_ascii_whitespace() || matches!(b, b',' | b']' | b'}' | b':') {
+                break;
+            }
+            raw.push(b);
+            self.bump()?;
+        }
+        if raw.is_empty() {
+            return match self.peek()? {
+                Some(b) => Err(self.error(start, format!("unexpected '{}'", b as char))),
+                None => Err(self.error(start, "unexpected end of input")),
+            };
+        }
+        if let Err(e) = serde_json::from_slice::<Value>(&raw) {
+            return Err(self.error(start, e));
+        }
+        if let Some(out) = out {
+            out.extend_from_slice(&raw);
+        }
+        Ok(())
+    }
+
+    // Consume exactly one JSON value, appending its raw bytes to `out` if given
+    fn value(&mut self, mut out: Option<&mut Vec<u8>>) -> Result<()> {
+        self.skip_ws()?;
+        let start = self.offset;
+        match self.peek()? {
+            Some(b'"') => self.string(out),
+            Some(b'{') | Some(b'[') => {
+                let mut closers = Vec::new();
+                loop {
+                    let at = self.offset;
+                    match self.peek()? {
+                        Some(b'"') => {
+                            self.string(out.as_deref_mut())?;
+                            continue;
+                        }
+                        Some(b) => {
+                            self.bump()?;
+                            if let Some(out) = out.as_deref_mut() {
+                                out.push(b);
+                            }
+                            match b {
+                                b'{' => closers.push(b'}'),
+                                b'[' => closers.push(b']'),
+                                b'}' | b']' => {
+                                    if closers.pop() != Some(b) {
+                                        return Err(self.error(at, format!("unexpected '{}'", b as char)));
+                                    }
+                                    if closers.is_empty() {
+                                        return Ok(());
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        None => return Err(self.error(start, "unterminated container")),
+                    }
+                }
+            }
+            _ => self.scalar(out),
+        }
+    }
+
+    // Position the scanner at the start of the value addressed by `token`
+    fn descend(&mut self, token: &str, pointer: &str) -> Result<()> {
+        let not_found = || Error::InvalidConfig(format!("JSON pointer not found: {}", pointer));
+        self.skip_ws()?;
+        let at = self.offset;
+        match self.peek()? {
+            Some(b'{') => {
+                self.bump()?;
+                loop {
+                    self.skip_ws()?;
+                    if self.peek()? == Some(b'}') {
+                        return Err(not_found());
+                    }
+                    let key_at = self.offset;
+                    let mut raw = Vec::new();
+                    if self.peek()? != Some(b'"') {
+                        return Err(self.error(key_at, "expected object key"));
+                    }
+                    self.string(Some(&mut raw))?;
+                    let key: String = serde_json::from_slice(&raw).map_err(|e| self.error(key_at, e))?;
+                    self.expect(b':')?;
+                    if key == token {
+                        return Ok(());
+                    }
+                    self.value(None)?;
+                    self.skip_ws()?;
+                    let sep_at = self.offset;
+                    match self.bump()? {
+                        Some(b',') => continue,
+                        Some(b'}') => return Err(not_found()),
+                        _ => return Err(self.error(sep_at, "expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'[') => {
+                let index: usize = token.parse().map_err(|_| not_found())?;
+                self.bump()?;
+                for _ in 0..index {
+                    self.skip_ws()?;
+                    if self.peek()? == Some(b']') {
+                        return Err(not_found());
+                    }
+                    self.value(None)?;
+                    self.skip_ws()?;
+                    if self.peek()? == Some(b']') {
+                        return Err(not_found());
+                    }
+                    self.expect(b',')?;
+                }
+                self.skip_ws()?;
+                if self.peek()? == Some(b']') {
+                    return Err(not_found());
+                }
+                Ok(())
+            }
+            Some(_) => Err(not_found()),
+            None => Err(self.error(at, "unexpected end of input")),
+        }
+    }
+
+    fn seek_pointer(&mut self, pointer: &str) -> Result<()> {
+        if pointer.is_empty() {
+            return Ok(());
+        }
+        if !pointer.starts_with('/') {
+            return Err(Error::InvalidConfig(format!("Invalid JSON pointer: {}", pointer)));
+        }
+        for token in pointer[1..].split('/') {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            self.descend(&token, pointer)?;
+        }
+        Ok(())
+    }
+
+    fn open_array(&mut self) -> Result<()> {
+        self.skip_ws()?;
+        let at = self.offset;
+        match self.peek()? {
+            Some(b'[') => {
+                self.bump()?;
+                Ok(())
+            }
+            _ => Err(Error::InvalidConfig(format!("Value at byte offset {} is not an array", at))),
+        }
+    }
+
+    // Walk the array elements, handing each index to `visit` which decides whether
+    // the element is captured. Returning false from `visit` stops the scan early.
+    fn each_element(
+        &mut self,
+        mut visit: impl FnMut(usize, Option<Vec<u8>>, u64) -> Result<bool>,
+        mut capture: impl FnMut(usize) -> bool,
+    ) -> Result<bool> {
+        self.skip_ws()?;
+        if self.peek()? == Some(b']') {
+            self.bump()?;
+            return Ok(true);
+        }
+        let mut index = 0;
+        loop {
+            self.skip_ws()?;
+            let start = self.offset;
+            let raw = if capture(index) {
+                let mut raw = Vec::new();
+                self.value(Some(&mut raw))?;
+                Some(raw)
+            } else {
+                self.value(None)?;
+                None
+            };
+            if !visit(index, raw, start)? {
+                return Ok(false);
+            }
+            index += 1;
+
+            self.skip_ws()?;
+            let at = self.offset;
+            match self.bump()? {
+                Some(b',') => continue,
+                Some(b']') => return Ok(true),
+                Some(b) => return Err(self.error(at, format!("expected ',' or ']', found '{}'", b as char))),
+                None => return Err(self.error(at, "unexpected end of input inside array")),
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.skip_ws()?;
+        if self.peek()?.is_some() {
+            return Err(self.error(self.offset, "trailing content after document"));
+        }
+        Ok(())
+    }
+}
+
+fn open_scanner(path: &PathBuf) -> Result<Scanner<BufReader<std::fs::File>>> {
+    let file = std::fs::File::open(path)?;
+    Ok(Scanner::new(BufReader::with_capacity(64 * 1024, file)))
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+}
+
+impl FileExecutor {
+    pub(super) async fn read_json_stream(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            pointer: String,
+            #[serde(default)]
+            offset: usize,
+            limit: Option<usize>,
+            dest: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let dest_path = params.dest.as_deref().map(|d| self.resolve_path(d)).transpose()?;
+        let offset = params.offset;
+        let pointer = params.pointer;
+
+        if let Some(dest_path) = dest_path {
+            let limit = params.limit.unwrap_or(usize::MAX);
+            let output_path = dest_path.clone();
+            let written = blocking(move || {
+                let mut scanner = open_scanner(&full_path)?;
+                scanner.seek_pointer(&pointer)?;
+                scanner.open_array()?;
+
+                let mut out = BufWriter::new(std::fs::File::create(&dest_path)?);
+                let mut written = 0usize;
+                let end = offset.saturating_add(limit);
+                let completed = scanner.each_element(
+                    |index, raw, start| {
+                        if let Some(raw) = raw {
+                            let value: Value = serde_json::from_slice(&raw).map_err(|e| {
+                                invalid_element(start, e)
+                            })?;
+                            serde_json::to_writer(&mut out, &value)?;
+                            out.write_all(b"\n")?;
+                            written += 1;
+                        }
+                        Ok(index + 1 < end)
+                    },
+                    |index| index >= offset && index < end,
+                )?;
+                if completed && pointer.is_empty() {
+                    scanner.finish()?;
+                }
+                out.flush()?;
+                Ok(written)
+            })
+            .await?;
+            self.settle(&output_path, None).await?;
+
+            return Ok(ExecutionResult {
+                success: true,
+                output: Some(serde_json::json!({
+                    "dest": output_path,
+                    "offset": offset,
+                    "written": written,
+                })),
+                error: None,
+            });
+        }
+
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let (items, has_more) = blocking(move || {
+            let mut scanner = open_scanner(&full_path)?;
+            scanner.seek_pointer(&pointer)?;
+            scanner.open_array()?;
+
+            let mut items = Vec::new();
+            let end = offset.saturating_add(limit);
+            // Scan one element past the page so we know whether more exist
+            let completed = scanner.each_element(
+                |index, raw, start| {
+                    if index >= end {
+                        return Ok(false);
+                    }
+                    if let Some(raw) = raw {
+                        let value: Value = serde_json::from_slice(&raw).map_err(|e| {
+                            invalid_element(start, e)
+                        })?;
+                        items.push(value);
+                    }
+                    Ok(true)
+                },
+                |index| index >= offset && index < end,
+            )?;
+            if completed && pointer.is_empty() {
+                scanner.finish()?;
+            }
+            Ok((items, !completed))
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "items": items,
+                "offset": offset,
+                "count": items.len(),
+                "has_more": has_more,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn json_array_length(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            pointer: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let pointer = params.pointer;
+        let length = blocking(move || {
+            let mut scanner = open_scanner(&full_path)?;
+            scanner.seek_pointer(&pointer)?;
+            scanner.open_array()?;
+            let mut length = 0usize;
+            scanner.each_element(|_, _, _| { length += 1; Ok(true) }, |_| false)?;
+            if pointer.is_empty() {
+                scanner.finish()?;
+            }
+            Ok(length)
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "length": length })),
+            error: None,
+        })
+    }
+}
+
+fn invalid_element(offset: u64, e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid JSON at byte offset {}: {}", offset, e),
+    ))
+}
@@ -0,0 +1,140 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+#[derive(Deserialize)]
+struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+// One line without its terminator; CRLF and LF both end a line
+fn decode_line(mut bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_suffix(b"\n") {
+        bytes = rest.strip_suffix(b"\r").unwrap_or(rest);
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+// A final line without a trailing newline still counts
+async fn count_lines(path: &Path) -> Result<usize> {
+    let mut file = fs::File::open(path).await?;
+    let mut buf = vec![0u8; CHUNK_SIZE as usize];
+    let (mut lines, mut last) = (0, b'\n');
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        lines += buf[..n].iter().filter(|&&b| b == b'\n').count();
+        last = buf[n - 1];
+    }
+    Ok(lines + usize::from(last != b'\n'))
+}
+
+// Lines `start..=end` (1-based) and the total, in one forward pass
+async fn read_forward(path: &Path, start: usize, end: usize) -> Result<(Vec<String>, usize)> {
+    let mut reader = BufReader::new(fs::File::open(path).await?);
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            break;
+        }
+        number += 1;
+        if (start..=end).contains(&number) {
+            lines.push(decode_line(&line));
+        }
+    }
+    Ok((lines, number))
+}
+
+// The last `n` lines, reading backwards so only they are held in memory
+async fn read_tail(path: &Path, n: usize) -> Result<Vec<String>> {
+    let mut file = fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let mut pos = len;
+    let mut buf: Vec<u8> = Vec::new();
+    // `buf` is always the file's tail. A newline ending the file terminates
+    // the last line rather than starting one.
+    let newlines = |buf: &[u8]| buf.strip_suffix(b"\n").unwrap_or(buf).iter().filter(|&&b| b == b'\n').count();
+    while pos > 0 && newlines(&buf) < n {
+        let size = CHUNK_SIZE.min(pos);
+        pos -= size;
+        let mut chunk = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(pos)).await?;
+        file.read_exact(&mut chunk).await?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+    }
+
+    let mut lines: Vec<String> = buf.split_inclusive(|&b| b == b'\n').map(decode_line).collect();
+    // Unless the read reached the start of the file, the first piece is partial
+    if pos > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(n);
+    Ok(lines.split_off(skip))
+}
+
+impl FileExecutor {
+    pub(super) async fn read_lines(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            head: Option<usize>,
+            tail: Option<usize>,
+            range: Option<LineRange>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let (lines, start, total) = match (params.head, params.tail, params.range) {
+            (Some(head), None, None) => {
+                let (lines, total) = read_forward(&full_path, 1, head).await?;
+                (lines, 1, total)
+            }
+            (None, Some(tail), None) => {
+                let lines = if tail == 0 { Vec::new() } else { read_tail(&full_path, tail).await? };
+                let total = count_lines(&full_path).await?;
+                (lines, total - total.min(tail) + 1, total)
+            }
+            (None, None, Some(range)) => {
+                if range.start == 0 || range.start > range.end {
+                    return Err(Error::InvalidConfig(format!(
+                        "range must satisfy 1 <= start <= end, got {}..{}", range.start, range.end
+                    )));
+                }
+                let (lines, total) = read_forward(&full_path, range.start, range.end).await?;
+                (lines, range.start, total)
+            }
+            _ => return Err(Error::InvalidConfig(
+                "read_lines takes exactly one of 'head', 'tail' or 'range'".to_string()
+            )),
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "lines": lines,
+                "start": start,
+                "total_lines": total,
+            })),
+            error: None,
+        })
+    }
+}
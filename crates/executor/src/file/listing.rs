@@ -0,0 +1,84 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use walkdir::WalkDir;
+
+use super::glob::relative_string;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_name().to_string_lossy().starts_with('.')
+}
+
+impl FileExecutor {
+    // Like list_dir, but with per-entry metadata and optional recursion.
+    // Symlinks are reported, never followed.
+    pub(super) async fn list_detailed(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            recursive: bool,
+            #[serde(default)]
+            include_hidden: bool,
+            // Levels below `path`; 1 is the directory itself
+            max_depth: Option<usize>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.max_depth == Some(0) {
+            return Err(Error::InvalidConfig("max_depth must be at least 1".to_string()));
+        }
+        let root = self.resolve_path(&params.path)?;
+        if !tokio::fs::metadata(&root).await?.is_dir() {
+            return Err(Error::InvalidConfig(format!("{} is not a directory", params.path)));
+        }
+        let max_depth = match (params.recursive, params.max_depth) {
+            (false, _) => 1,
+            (true, depth) => depth.unwrap_or(usize::MAX),
+        };
+        let include_hidden = params.include_hidden;
+        let internal = self.internal_paths();
+        let base = self.base_path.clone();
+
+        let entries = tokio::task::spawn_blocking(move || -> Result<Vec<serde_json::Value>> {
+            let walker = WalkDir::new(&root)
+                .follow_links(false)
+                .min_depth(1)
+                .max_depth(max_depth)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|e| {
+                    (include_hidden || !is_hidden(e)) && !internal.contains(e.path())
+                });
+            let mut entries = Vec::new();
+            for entry in walker {
+                let entry = entry.map_err(|e| Error::Io(e.into()))?;
+                let metadata = entry.metadata().map_err(|e| Error::Io(e.into()))?;
+                let modified: DateTime<Utc> = metadata.modified()?.into();
+                entries.push(json!({
+                    "name": entry.file_name().to_string_lossy(),
+                    "relative_path": relative_string(&root, entry.path()),
+                    "path": relative_string(&base, entry.path()),
+                    "is_dir": metadata.is_dir(),
+                    "is_symlink": entry.path_is_symlink(),
+                    "size": metadata.len(),
+                    "modified": modified.to_rfc3339_opts(SecondsFormat::Millis, true),
+                }));
+            }
+            Ok(entries)
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({ "path": params.path, "entries": entries })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+// Written next to the lock file so humans (and other tools) can see who holds it.
+#[derive(Serialize, Deserialize)]
+struct LockHolder {
+    lock_id: String,
+    pid: u32,
+    acquired_at: DateTime<Utc>,
+}
+
+// An OS-level lock (flock / LockFileEx) owned by one FileExecutor instance.
+// Dropping it unlocks the file, so locks never outlive the executor.
+pub(super) struct HeldLock {
+    lock_id: String,
+    acquired_at: DateTime<Utc>,
+    file: File,
+    path: PathBuf,
+    sidecar: PathBuf,
+}
+
+impl Drop for HeldLock {
+    fn drop(&mut self) {
+        // Only remove the sidecar if it is still ours; a stale-lock breaker may
+        // have replaced it in the meantime.
+        if read_holder_sync(&self.sidecar).is_some_and(|h| h.lock_id == self.lock_id) {
+            let _ = std::fs::remove_file(&self.sidecar);
+        }
+        let _ = self.file.unlock();
+    }
+}
+
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".holder.json");
+    PathBuf::from(name)
+}
+
+fn read_holder_sync(sidecar: &Path) -> Option<LockHolder> {
+    let content = std::fs::read(sidecar).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+// True when `file` is still the inode that `path` points to, i.e. nobody broke
+// the lock and recreated the file while we were waiting on the old one.
+#[cfg(unix)]
+fn still_linked(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn still_linked(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+// None (never stale) without a readable sidecar: the holder may be a program
+// that doesn't write one, or may not have written it yet
+fn lock_age(sidecar: &Path) -> Option<Duration> {
+    let holder = read_holder_sync(sidecar)?;
+    (Utc::now() - holder.acquired_at).to_std().ok()
+}
+
+// Readers never see a half-written sidecar
+async fn write_holder(sidecar: &Path, holder: &LockHolder) -> Result<()> {
+    let mut temp = sidecar.as_os_str().to_os_string();
+    temp.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+    let temp = PathBuf::from(temp);
+    fs::write(&temp, serde_json::to_vec_pretty(holder)?).await?;
+    if let Err(e) = fs::rename(&temp, sidecar).await {
+        let _ = fs::remove_file(&temp).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+async fn open_lock_file(path: &Path) -> Result<File> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .await?;
+    Ok(file.into_std().await)
+}
+
+impl HeldLock {
+    // Take the OS lock on `path`, waiting up to `timeout` and breaking locks older
+    // than `break_if_stale`. Also reports whether a stale lock was broken.
+    pub(super) async fn acquire(
+        path: PathBuf,
+        timeout: Duration,
+        break_if_stale: Option<Duration>,
+    ) -> Result<(Self, bool)> {
+        let sidecar = sidecar_path(&path);
+        let deadline = Instant::now() + timeout;
+        let mut broke_stale = false;
+
+        let file = loop {
+            let file = open_lock_file(&path).await?;
+            match file.try_lock() {
+                Ok(()) if still_linked(&file, &path) => break file,
+                Ok(()) => continue,
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+
+            if let Some(max_age) = break_if_stale {
+                let stale = lock_age(&sidecar).is_some_and(|age| age >= max_age);
+                if stale {
+                    // The holder keeps its lock on the unlinked inode; everyone
+                    // else coordinates on the fresh file from now on.
+                    match fs::remove_file(&path).await {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                    let _ = fs::remove_file(&sidecar).await;
+                    broke_stale = true;
+                    continue;
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+
+        let holder = LockHolder {
+            lock_id: uuid::Uuid::new_v4().to_string(),
+            pid: std::process::id(),
+            acquired_at: Utc::now(),
+        };
+        write_holder(&sidecar, &holder).await?;
+
+        let held = HeldLock {
+            lock_id: holder.lock_id,
+            acquired_at: holder.acquired_at,
+            file,
+            path,
+            sidecar,
+        };
+        Ok((held, broke_stale))
+    }
+}
+
+impl FileExecutor {
+    pub(super) async fn acquire_lock(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            timeout_ms: u64,
+            break_if_stale_secs: Option<u64>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let (held, broke_stale) = HeldLock::acquire(
+            full_path.clone(),
+            Duration::from_millis(params.timeout_ms),
+            params.break_if_stale_secs.map(Duration::from_secs),
+        )
+        .await?;
+
+        let output = serde_json::json!({
+            "lock_id": held.lock_id,
+            "path": full_path,
+            "pid": std::process::id(),
+            "acquired_at": held.acquired_at,
+            "broke_stale": broke_stale,
+        });
+        self.locks.lock().unwrap().insert(held.lock_id.clone(), held);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    pub(super) async fn release_lock(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            lock_id: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let held = self.locks.lock().unwrap().remove(&params.lock_id)
+            .ok_or_else(|| Error::InvalidConfig(
+                format!("Unknown lock id: {}", params.lock_id)
+            ))?;
+        let path = held.path.clone();
+        drop(held);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "lock_id": params.lock_id,
+                "path": path,
+                "released": true,
+            })),
+            error: None,
+        })
+    }
+}
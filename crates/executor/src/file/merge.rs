@@ -0,0 +1,129 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// A side of the merge: a JSON file, or `{ "data": ... }` given inline
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Path(String),
+    Inline { data: Value },
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ArrayStrategy {
+    #[default]
+    Replace,
+    Concat,
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// RFC 6901 escaping for the key paths in warnings
+fn pointer(parent: &str, key: &str) -> String {
+    format!("{}/{}", parent, key.replace('~', "~0").replace('/', "~1"))
+}
+
+// Objects merge key by key with the overlay winning. A key missing from the
+// overlay keeps the base value while an explicit null replaces it.
+fn merge(base: Value, overlay: Value, path: &str, arrays: ArrayStrategy, warnings: &mut Vec<Value>) -> Value {
+    match (base, overlay) {
+        (Value::Object(mut base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                let merged = match base.remove(&key) {
+                    Some(existing) => merge(existing, value, &pointer(path, &key), arrays, warnings),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (Value::Array(mut base), Value::Array(overlay)) => match arrays {
+            ArrayStrategy::Replace => Value::Array(overlay),
+            ArrayStrategy::Concat => {
+                base.extend(overlay);
+                Value::Array(base)
+            }
+        },
+        (base, overlay) => {
+            let structural = |v: &Value| v.is_object() || v.is_array();
+            if !overlay.is_null() && kind(&base) != kind(&overlay) && (structural(&base) || structural(&overlay)) {
+                warnings.push(json!({
+                    "path": path,
+                    "base": kind(&base),
+                    "overlay": kind(&overlay),
+                }));
+            }
+            overlay
+        }
+    }
+}
+
+impl FileExecutor {
+    async fn merge_source(&self, source: Source) -> Result<Value> {
+        match source {
+            Source::Inline { data } => Ok(data),
+            Source::Path(path) => {
+                let content = fs::read_to_string(self.resolve_path(&path)?).await?;
+                serde_json::from_str(&content).map_err(|e| Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid JSON in {}: {}", path, e),
+                )))
+            }
+        }
+    }
+
+    // Layer `overlay` on top of `base`; the result goes to `dest` when given,
+    // otherwise it is returned as `data`
+    pub(super) async fn merge_json(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            base: Source,
+            overlay: Source,
+            #[serde(default)]
+            array_strategy: ArrayStrategy,
+            dest: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let base = self.merge_source(params.base).await?;
+        let overlay = self.merge_source(params.overlay).await?;
+        let mut warnings = Vec::new();
+        let merged = merge(base, overlay, "", params.array_strategy, &mut warnings);
+
+        let mut output = json!({ "warnings": warnings });
+        match &params.dest {
+            Some(dest) => {
+                let full_path = self.resolve_path(dest)?;
+                let json_string = serde_json::to_string_pretty(&merged)?;
+                fs::write(&full_path, json_string.as_bytes()).await?;
+                self.settle(&full_path, Some(json_string.len() as u64)).await?;
+                output["dest"] = json!(dest);
+                output["bytes"] = json!(json_string.len());
+            }
+            None => output["data"] = merged,
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+struct Page {
+    items: Vec<Value>,
+    has_more: bool,
+    // (line number, parse error) for lines skipped with `skip_invalid`
+    invalid: Vec<(usize, String)>,
+}
+
+impl FileExecutor {
+    // One JSON document per line, read a line at a time. Blank lines are ignored;
+    // `offset`/`limit` count documents, line numbers in errors are 1-based.
+    pub(super) async fn read_ndjson(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            offset: usize,
+            limit: Option<usize>,
+            #[serde(default)]
+            skip_invalid: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let (display, offset, limit, skip_invalid) = (params.path.clone(), params.offset, params.limit, params.skip_invalid);
+        let page = tokio::task::spawn_blocking(move || -> Result<Page> {
+            let reader = BufReader::new(std::fs::File::open(&full_path)?);
+            let mut page = Page { items: Vec::new(), has_more: false, invalid: Vec::new() };
+            let mut seen = 0usize;
+            for (i, line) in reader.lines().enumerate() {
+                let line = line.map_err(|e| Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Cannot read {} at line {}: {}", display, i + 1, e),
+                )))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let value: Value = match serde_json::from_str(&line) {
+                    Ok(value) => value,
+                    Err(e) if skip_invalid => {
+                        page.invalid.push((i + 1, e.to_string()));
+                        continue;
+                    }
+                    Err(e) => return Err(Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Invalid JSON in {} at line {}: {}", display, i + 1, e),
+                    ))),
+                };
+                if limit.is_some_and(|l| page.items.len() >= l) {
+                    page.has_more = true;
+                    break;
+                }
+                if seen >= offset {
+                    page.items.push(value);
+                }
+                seen += 1;
+            }
+            Ok(page)
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        let mut output = json!({
+            "items": page.items,
+            "offset": offset,
+            "count": page.items.len(),
+            "has_more": page.has_more,
+        });
+        if skip_invalid {
+            output["warnings"] = page.invalid.iter()
+                .map(|(line, error)| json!({ "line": line, "error": error }))
+                .collect();
+        }
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    pub(super) async fn write_ndjson(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            data: Vec<Value>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let lines = params.data.len();
+        let path = full_path.clone();
+        let len = tokio::task::spawn_blocking(move || -> Result<u64> {
+            let mut out = BufWriter::new(std::fs::File::create(&path)?);
+            for value in &params.data {
+                serde_json::to_writer(&mut out, value)?;
+                out.write_all(b"\n")?;
+            }
+            let file = out.into_inner().map_err(|e| Error::Io(e.into_error()))?;
+            Ok(file.metadata()?.len())
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&full_path, Some(len)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "lines": lines,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use globset::Glob;
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::cmp::Ordering;
+use tokio::fs;
+
+use super::{mtime_mark, FileExecutor};
+use crate::state::compare_marks;
+use crate::traits::ExecutionResult;
+
+impl FileExecutor {
+    // Adopt a directory that already holds processed files: advance the
+    // `newer_than_state` mark past them without running anything, so the next
+    // incremental `list` only returns files that arrive (or change) afterwards.
+    pub(super) async fn import_existing(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            state_key: String,
+            // Glob on file names; others are neither counted nor considered
+            filter: Option<String>,
+            // Files modified after this stay "new"
+            mark_before: Option<DateTime<Utc>>,
+            #[serde(default)]
+            dry_run: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let store = self.state.clone().ok_or_else(|| Error::InvalidConfig(
+            "This FileExecutor has no state store configured".to_string()
+        ))?;
+        let filter = match &params.filter {
+            Some(pattern) => Some(
+                Glob::new(pattern)
+                    .map_err(|e| Error::InvalidConfig(format!("Invalid filter: {}", e)))?
+                    .compile_matcher(),
+            ),
+            None => None,
+        };
+
+        let full_path = self.resolve_path(&params.path)?;
+        let mut entries = fs::read_dir(&full_path).await?;
+        let (mut registered, mut left_new, mut bytes) = (0u64, 0u64, 0u64);
+        let mut mark: Option<serde_json::Value> = None;
+        while let Some(entry) = entries.next_entry().await? {
+            if self.internal_paths().contains(&entry.path()) {
+                continue;
+            }
+            if filter.as_ref().is_some_and(|f| !f.is_match(entry.file_name())) {
+                continue;
+            }
+            let metadata = entry.metadata().await?;
+            let modified: DateTime<Utc> = metadata.modified()?.into();
+            if params.mark_before.is_some_and(|cutoff| modified > cutoff) {
+                left_new += 1;
+                continue;
+            }
+            let modified = mtime_mark(&metadata)?;
+            if mark.as_ref().is_none_or(|m| compare_marks(&modified, m) == Some(Ordering::Greater)) {
+                mark = Some(modified);
+            }
+            registered += 1;
+            bytes += metadata.len();
+        }
+
+        let previous = store.get(&params.state_key).await?;
+        let mut updated = false;
+        if let (false, Some(mark)) = (params.dry_run, mark.clone()) {
+            // Never move an existing mark backwards
+            updated = store.transact(&params.state_key, move |current| match current {
+                Some(current) if compare_marks(&mark, current) != Some(Ordering::Greater) => {
+                    Ok((Some(current.clone()), false))
+                }
+                _ => Ok((Some(mark), true)),
+            })
+            .await?;
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "state_key": params.state_key,
+                "registered": registered,
+                "registered_bytes": bytes,
+                "left_new": left_new,
+                "mark": mark,
+                "previous": previous,
+                "updated": updated,
+                "dry_run": params.dry_run,
+            })),
+            error: None,
+        })
+    }
+}
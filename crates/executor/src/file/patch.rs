@@ -0,0 +1,75 @@
+use json_patch::{Patch, PatchErrorKind};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::fs;
+
+use super::atomic::AtomicWrite;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+impl FileExecutor {
+    // Edit a JSON file in place with an RFC 6902 patch, or an RFC 7386 merge
+    // patch with `merge: true`. A failing `test` op fails the task without
+    // touching the file.
+    pub(super) async fn apply_json_patch(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            patch: Value,
+            #[serde(default)]
+            merge: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let content = fs::read_to_string(&full_path).await?;
+        let mut doc: Value = serde_json::from_str(&content).map_err(|e| Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid JSON in {}: {}", params.path, e),
+        )))?;
+
+        let operations = match params.merge {
+            true => {
+                json_patch::merge(&mut doc, &params.patch);
+                None
+            }
+            false => {
+                let patch: Patch = serde_json::from_value(params.patch)
+                    .map_err(|e| Error::InvalidConfig(format!("Invalid JSON patch: {}", e)))?;
+                if let Err(e) = json_patch::patch(&mut doc, &patch) {
+                    if !matches!(e.kind, PatchErrorKind::TestFailed) {
+                        return Err(Error::InvalidConfig(format!("JSON patch failed: {}", e)));
+                    }
+                    return Ok(ExecutionResult {
+                        success: false,
+                        output: Some(json!({
+                            "path": params.path,
+                            "failed_operation": e.operation,
+                            "failed_path": e.path.to_string(),
+                        })),
+                        error: Some(format!("JSON patch test failed at operation {} ({})", e.operation, e.path)),
+                    });
+                }
+                Some(patch.0.len())
+            }
+        };
+
+        let json_string = serde_json::to_string_pretty(&doc)?;
+        let bytes = AtomicWrite::begin(&full_path).await?.finish(json_string.as_bytes()).await?;
+        self.settle(&full_path, Some(bytes)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "merge": params.merge,
+                "operations": operations,
+                "bytes": bytes,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,197 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+
+use super::dialect::{synthesized_headers, CsvDialect};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// Read granularity while looking for enough CSV rows
+const CSV_CHUNK: usize = 8 * 1024;
+
+// How much a preview may return. `preview: true` on a task uses the defaults.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub(super) struct PreviewBudget {
+    bytes: usize,
+    rows: usize,
+    entries: usize,
+}
+
+impl Default for PreviewBudget {
+    fn default() -> Self {
+        Self { bytes: 2048, rows: 20, entries: 100 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PreviewParam {
+    Enabled(bool),
+    Budget(PreviewBudget),
+}
+
+pub(super) fn preview_budget(params: &Value) -> Result<Option<PreviewBudget>> {
+    let Some(value) = params.get("preview") else {
+        return Ok(None);
+    };
+    match serde_json::from_value(value.clone()).map_err(|e| Error::InvalidConfig(format!("Invalid preview: {}", e)))? {
+        PreviewParam::Enabled(true) => Ok(Some(PreviewBudget::default())),
+        PreviewParam::Enabled(false) => Ok(None),
+        PreviewParam::Budget(budget) => Ok(Some(budget)),
+    }
+}
+
+// Longest prefix of `bytes` that is valid UTF-8 when the cut may split a character
+fn utf8_prefix(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(e) if e.error_len().is_none() => String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned(),
+        Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+#[derive(Deserialize)]
+struct ReadParams {
+    path: String,
+    #[serde(default)]
+    decompress: bool,
+    codec: Option<String>,
+    #[serde(flatten)]
+    dialect: CsvDialect,
+}
+
+impl FileExecutor {
+    // A bounded sample of what a read-class operation would return, plus what
+    // was left out. Never mutates: operations without a sample mode are refused
+    // unless they have a dry run.
+    pub(super) async fn preview(&self, task: &Task, budget: PreviewBudget) -> Result<ExecutionResult> {
+        let output = match task.operation.as_str() {
+            "read" => self.preview_read(task, budget).await?,
+            "read_csv" => self.preview_csv(task, budget).await?,
+            "list_dir" => self.preview_listing(self.list_dir(task).await?, "files", budget)?,
+            "glob" => self.preview_listing(self.glob(task).await?, "matches", budget)?,
+            "list_detailed" => self.preview_listing(self.list_detailed(task).await?, "entries", budget)?,
+            "delete" => {
+                let mut params = task.params.clone();
+                params["dry_run"] = json!(true);
+                let dry_run = Task { params, ..task.clone() };
+                return self.delete_file(&dry_run).await;
+            }
+            other => return Err(Error::InvalidConfig(format!(
+                "Operation '{}' does not support preview", other
+            ))),
+        };
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    async fn preview_read(&self, task: &Task, budget: PreviewBudget) -> Result<Value> {
+        let params: ReadParams = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let full_path = self.resolve_path(&params.path)?;
+        let total_bytes = fs::metadata(&full_path).await?.len();
+
+        let (reader, codec) = self.open_reader(&full_path, params.decompress, params.codec.as_deref()).await?;
+        let mut sample = Vec::new();
+        reader.take(budget.bytes as u64 + 1).read_to_end(&mut sample).await?;
+        let truncated = sample.len() > budget.bytes;
+        sample.truncate(budget.bytes);
+        let content = utf8_prefix(&sample);
+
+        Ok(json!({
+            "content": content,
+            "preview": {
+                "truncated": truncated,
+                "returned_bytes": content.len(),
+                "total_bytes": total_bytes,
+                "codec": codec,
+            },
+        }))
+    }
+
+    async fn preview_csv(&self, task: &Task, budget: PreviewBudget) -> Result<Value> {
+        let params: ReadParams = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let full_path = self.resolve_path(&params.path)?;
+        let total_bytes = fs::metadata(&full_path).await?.len();
+
+        // Read until the header, `rows` rows and one more line are buffered
+        let (mut reader, codec) = self.open_reader(&full_path, params.decompress, params.codec.as_deref()).await?;
+        let mut buf = Vec::new();
+        let mut chunk = vec![0u8; CSV_CHUNK];
+        let mut eof = false;
+        while buf.iter().filter(|&&b| b == b'\n').count() < budget.rows + 2 {
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                eof = true;
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+
+        let invalid = |e: csv::Error| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()));
+        let mut csv_reader = params.dialect.reader()?.from_reader(buf.as_slice());
+        let mut headers: Vec<String> = match params.dialect.has_headers {
+            true => csv_reader.headers().map_err(invalid)?.iter().map(str::to_string).collect(),
+            false => Vec::new(),
+        };
+        let mut rows = Vec::new();
+        let mut records = csv_reader.records();
+        for record in records.by_ref().take(budget.rows) {
+            rows.push(record.map_err(invalid)?.iter().map(str::to_string).collect::<Vec<_>>());
+        }
+        let consumed = records.reader().position().byte();
+        let truncated = !eof || records.next().is_some();
+        if !params.dialect.has_headers {
+            headers = synthesized_headers(rows.iter().map(Vec::len).max().unwrap_or(0));
+        }
+
+        // Extrapolated from the bytes the sample took; unknown for compressed input
+        let estimated_rows = match (truncated, codec) {
+            (false, _) => Some(rows.len() as u64),
+            (true, None) if consumed > 0 => {
+                let header_bytes = match params.dialect.has_headers {
+                    true => buf.iter().position(|&b| b == b'\n').map_or(0, |i| i as u64 + 1),
+                    false => 0,
+                };
+                let per_row = (consumed - header_bytes).max(1) as f64 / rows.len().max(1) as f64;
+                Some(((total_bytes - header_bytes) as f64 / per_row).round() as u64)
+            }
+            _ => None,
+        };
+
+        Ok(json!({
+            "headers": headers,
+            "rows": rows,
+            "preview": {
+                "truncated": truncated,
+                "returned_rows": rows.len(),
+                "estimated_rows": estimated_rows,
+                "total_bytes": total_bytes,
+                "codec": codec,
+            },
+        }))
+    }
+
+    fn preview_listing(&self, result: ExecutionResult, field: &str, budget: PreviewBudget) -> Result<Value> {
+        let mut output = result.output.unwrap_or_default();
+        let entries = output.get_mut(field).and_then(Value::as_array_mut).ok_or_else(|| {
+            Error::InvalidConfig(format!("Listing has no '{}' to preview", field))
+        })?;
+        let total = entries.len();
+        entries.truncate(budget.entries);
+        let returned = entries.len();
+        output["preview"] = json!({
+            "truncated": returned < total,
+            "returned_entries": returned,
+            "total_entries": total,
+        });
+        Ok(output)
+    }
+}
@@ -0,0 +1,350 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+use std::path::Path;
+
+use super::sketch::{HyperLogLog, TopK};
+use super::typed_csv::{ColumnSchema, ColumnType};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_SAMPLE_ROWS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ProfileFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ProfileFormat {
+    pub(super) fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+struct ColumnProfile {
+    name: String,
+    count: u64,
+    nulls: u64,
+    types: BTreeMap<&'static str, u64>,
+    distinct: HyperLogLog,
+    top: TopK,
+    examples: Vec<String>,
+    numeric: Option<(f64, f64)>,
+    dates: Option<((NaiveDateTime, String), (NaiveDateTime, String))>,
+}
+
+fn parse_date(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+    DateTime::parse_from_rfc3339(s).ok().map(|d| d.naive_utc())
+}
+
+// Classify a raw CSV cell the same way a human skimming the file would
+fn classify_text(s: &str) -> &'static str {
+    if s.parse::<i64>().is_ok() {
+        "integer"
+    } else if s.parse::<f64>().is_ok() {
+        "float"
+    } else if s.eq_ignore_ascii_case("true") || s.eq_ignore_ascii_case("false") {
+        "boolean"
+    } else if parse_date(s).is_some() {
+        "date"
+    } else {
+        "string"
+    }
+}
+
+impl ColumnProfile {
+    fn new(name: String, top_n: usize) -> Self {
+        Self {
+            name,
+            count: 0,
+            nulls: 0,
+            types: BTreeMap::new(),
+            distinct: HyperLogLog::new(),
+            top: TopK::new(top_n * 10),
+            examples: Vec::new(),
+            numeric: None,
+            dates: None,
+        }
+    }
+
+    fn observe_missing(&mut self) {
+        self.count += 1;
+        self.nulls += 1;
+        *self.types.entry("null").or_insert(0) += 1;
+    }
+
+    fn observe_text(&mut self, text: &str, kind: &'static str, max_examples: usize) {
+        self.count += 1;
+        *self.types.entry(kind).or_insert(0) += 1;
+        self.distinct.insert(text);
+        self.top.insert(text);
+
+        if self.examples.len() < max_examples && !self.examples.iter().any(|e| e == text) {
+            self.examples.push(text.to_string());
+        }
+
+        match kind {
+            "integer" | "float" => {
+                if let Ok(n) = text.parse::<f64>() {
+                    self.numeric = Some(match self.numeric {
+                        Some((min, max)) => (min.min(n), max.max(n)),
+                        None => (n, n),
+                    });
+                }
+            }
+            "date" => {
+                if let Some(d) = parse_date(text) {
+                    let current = (d, text.to_string());
+                    self.dates = Some(match self.dates.take() {
+                        Some((min, max)) => (
+                            if d < min.0 { current.clone() } else { min },
+                            if d > max.0 { current } else { max },
+                        ),
+                        None => (current.clone(), current),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn observe_cell(&mut self, cell: &str, max_examples: usize) {
+        if cell.is_empty() {
+            self.observe_missing();
+        } else {
+            self.observe_text(cell, classify_text(cell), max_examples);
+        }
+    }
+
+    fn observe_json(&mut self, value: &Value, max_examples: usize) {
+        let kind = match value {
+            Value::Null => return self.observe_missing(),
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            Value::Number(_) => "float",
+            Value::String(s) if s.is_empty() => return self.observe_missing(),
+            Value::String(s) if parse_date(s).is_some() => "date",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        self.observe_text(&text, kind, max_examples);
+    }
+
+    // The narrowest read_csv_typed column that accepts every observed value
+    fn suggested_schema(&self) -> ColumnSchema {
+        let seen: Vec<&str> = self.types.keys().copied().filter(|k| *k != "null").collect();
+        let kind = match seen.as_slice() {
+            ["integer"] => ColumnType::Int,
+            kinds if !kinds.is_empty() && kinds.iter().all(|k| *k == "integer" || *k == "float") => ColumnType::Float,
+            ["boolean"] => ColumnType::Bool,
+            ["date"] => ColumnType::Date,
+            _ => ColumnType::String,
+        };
+        ColumnSchema {
+            name: self.name.clone(),
+            kind,
+            format: None,
+            nullable: self.nulls > 0,
+            default: None,
+        }
+    }
+
+    fn report(&self, top_n: usize) -> Value {
+        let null_rate = if self.count == 0 { 0.0 } else { self.nulls as f64 / self.count as f64 };
+        json!({
+            "name": self.name,
+            "count": self.count,
+            "null_count": self.nulls,
+            "null_rate": null_rate,
+            "types": self.types,
+            "distinct_estimate": self.distinct.estimate(),
+            "numeric": self.numeric.map(|(min, max)| json!({ "min": min, "max": max })),
+            "date": self.dates.as_ref().map(|(min, max)| json!({ "min": min.1, "max": max.1 })),
+            "top_values": self.top.top(top_n).into_iter()
+                .map(|(value, count)| json!({ "value": value, "count": count }))
+                .collect::<Vec<_>>(),
+            "examples": self.examples,
+        })
+    }
+}
+
+struct Profiler {
+    columns: Vec<ColumnProfile>,
+    index: HashMap<String, usize>,
+    top_n: usize,
+    examples: usize,
+}
+
+impl Profiler {
+    fn column(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i;
+        }
+        self.columns.push(ColumnProfile::new(name.to_string(), self.top_n));
+        self.index.insert(name.to_string(), self.columns.len() - 1);
+        self.columns.len() - 1
+    }
+}
+
+fn invalid_data(e: impl ToString) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+}
+
+fn profile_csv(path: &Path, profiler: &mut Profiler, sample_rows: usize) -> Result<(u64, bool)> {
+    let mut reader = csv::Reader::from_path(path).map_err(invalid_data)?;
+    let headers = reader.headers().map_err(invalid_data)?.clone();
+    for header in headers.iter() {
+        profiler.column(header);
+    }
+
+    let mut rows = 0u64;
+    for record in reader.records() {
+        if rows as usize >= sample_rows {
+            return Ok((rows, true));
+        }
+        let record = record.map_err(invalid_data)?;
+        for (i, cell) in record.iter().enumerate().take(headers.len()) {
+            let examples = profiler.examples;
+            profiler.columns[i].observe_cell(cell, examples);
+        }
+        // Short rows leave the trailing columns missing
+        for i in record.len()..headers.len() {
+            profiler.columns[i].observe_missing();
+        }
+        rows += 1;
+    }
+    Ok((rows, false))
+}
+
+fn profile_ndjson(path: &Path, profiler: &mut Profiler, sample_rows: usize) -> Result<(u64, bool)> {
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+
+    let mut rows = 0u64;
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if rows as usize >= sample_rows {
+            return Ok((rows, true));
+        }
+        let object: Map<String, Value> = serde_json::from_str(&line).map_err(|e| {
+            invalid_data(format!("line {}: {}", line_no + 1, e))
+        })?;
+
+        for (key, value) in &object {
+            let i = profiler.column(key);
+            let examples = profiler.examples;
+            profiler.columns[i].observe_json(value, examples);
+        }
+        // Fields seen in earlier records but absent here count as missing
+        for column in profiler.columns.iter_mut() {
+            if !object.contains_key(&column.name) {
+                column.observe_missing();
+            }
+        }
+        // Newly discovered fields were missing from every earlier record
+        for column in profiler.columns.iter_mut() {
+            let backfill = rows + 1 - column.count;
+            column.count += backfill;
+            column.nulls += backfill;
+            if backfill > 0 {
+                *column.types.entry("null").or_insert(0) += backfill;
+            }
+        }
+        rows += 1;
+    }
+    Ok((rows, false))
+}
+
+impl FileExecutor {
+    pub(super) async fn profile(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            format: Option<ProfileFormat>,
+            sample_rows: Option<usize>,
+            #[serde(default = "default_top_n")]
+            top_n: usize,
+            #[serde(default = "default_examples")]
+            examples: usize,
+        }
+
+        fn default_top_n() -> usize { 10 }
+        fn default_examples() -> usize { 3 }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let format = params.format
+            .or_else(|| ProfileFormat::from_extension(&full_path))
+            .ok_or_else(|| Error::InvalidConfig(
+                "Cannot infer format from extension; pass format: csv or ndjson".to_string()
+            ))?;
+        let sample_rows = params.sample_rows.unwrap_or(DEFAULT_SAMPLE_ROWS);
+
+        let mut profiler = Profiler {
+            columns: Vec::new(),
+            index: HashMap::new(),
+            top_n: params.top_n,
+            examples: params.examples,
+        };
+
+        let path = full_path.clone();
+        let (profiler, rows, truncated) = tokio::task::spawn_blocking(move || {
+            let (rows, truncated) = match format {
+                ProfileFormat::Csv => profile_csv(&path, &mut profiler, sample_rows)?,
+                ProfileFormat::Ndjson => profile_ndjson(&path, &mut profiler, sample_rows)?,
+            };
+            Ok::<_, Error>((profiler, rows, truncated))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        let columns: Vec<Value> = profiler.columns.iter()
+            .map(|c| c.report(params.top_n))
+            .collect();
+        // Ready to pin as read_csv_typed's schema_path
+        let schema: Vec<ColumnSchema> = profiler.columns.iter()
+            .map(ColumnProfile::suggested_schema)
+            .collect();
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": full_path,
+                "format": format.name(),
+                "rows_profiled": rows,
+                "truncated": truncated,
+                "columns": columns,
+                "schema": schema,
+            })),
+            error: None,
+        })
+    }
+}
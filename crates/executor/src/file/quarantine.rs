@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use super::glob::relative_string;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const SIDECAR_SUFFIX: &str = ".quarantine.json";
+
+// Written next to each quarantined file so triage knows where it came from and why
+#[derive(Serialize, Deserialize)]
+struct QuarantineRecord {
+    original_path: String,
+    task_id: String,
+    error: Option<String>,
+    quarantined_at: DateTime<Utc>,
+}
+
+fn sidecar_for(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+// `name`, or `stem-<timestamp>[-n].ext` if that is taken
+async fn free_name(dir: &Path, name: &str, now: DateTime<Utc>) -> Result<PathBuf> {
+    let candidate = dir.join(name);
+    if !fs::try_exists(&candidate).await? {
+        return Ok(candidate);
+    }
+    let (stem, ext) = match name.rfind('.') {
+        Some(i) if i > 0 => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    };
+    let stamp = now.format("%Y%m%dT%H%M%S%.3f");
+    let mut n = 1;
+    loop {
+        let suffix = if n == 1 { String::new() } else { format!("-{}", n) };
+        let candidate = dir.join(format!("{}-{}{}{}", stem, stamp, suffix, ext));
+        if !fs::try_exists(&candidate).await? {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+impl FileExecutor {
+    fn quarantine_dir(&self) -> Result<&Path> {
+        self.quarantine.as_deref().ok_or_else(|| Error::InvalidConfig(
+            "This FileExecutor has no quarantine_dir configured".to_string()
+        ))
+    }
+
+    pub(super) async fn quarantine(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            error: Option<String>,
+            // Id of the task that failed on this file; defaults to this task
+            task_id: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let dir = self.quarantine_dir()?.to_path_buf();
+        let source = self.resolve_path(&params.path)?;
+        if source.starts_with(&dir) {
+            return Err(Error::InvalidConfig(format!("{} is already quarantined", params.path)));
+        }
+        if !fs::metadata(&source).await?.is_file() {
+            return Err(Error::InvalidConfig(format!("{} is not a file", params.path)));
+        }
+
+        fs::create_dir_all(&dir).await?;
+        let now = Utc::now();
+        let name = source.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let target = free_name(&dir, &name, now).await?;
+        let record = QuarantineRecord {
+            original_path: relative_string(&self.base_path, &source).unwrap_or(params.path),
+            task_id: params.task_id.unwrap_or_else(|| task.id.to_string()),
+            error: params.error,
+            quarantined_at: now,
+        };
+
+        fs::rename(&source, &target).await?;
+        let sidecar = sidecar_for(&target);
+        fs::write(&sidecar, serde_json::to_vec_pretty(&record)?).await?;
+        self.settle(&target, None).await?;
+        self.settle_removed(&source).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": target,
+                "original_path": record.original_path,
+                "sidecar": sidecar,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn list_quarantine(&self, _task: &Task) -> Result<ExecutionResult> {
+        let dir = self.quarantine_dir()?;
+        let mut items = Vec::new();
+        if fs::try_exists(dir).await? {
+            let mut entries = fs::read_dir(dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.ends_with(SIDECAR_SUFFIX) || !entry.file_type().await?.is_file() {
+                    continue;
+                }
+                let record: Option<QuarantineRecord> = match fs::read(sidecar_for(&entry.path())).await {
+                    Ok(content) => serde_json::from_slice(&content).ok(),
+                    Err(_) => None,
+                };
+                items.push(json!({
+                    "file": name,
+                    "size": entry.metadata().await?.len(),
+                    "original_path": record.as_ref().map(|r| &r.original_path),
+                    "task_id": record.as_ref().map(|r| &r.task_id),
+                    "error": record.as_ref().and_then(|r| r.error.as_ref()),
+                    "quarantined_at": record.as_ref().map(|r| r.quarantined_at),
+                }));
+            }
+        }
+        items.sort_by(|a, b| a["file"].as_str().cmp(&b["file"].as_str()));
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({ "items": items })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn requeue_from_quarantine(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            file: Option<String>,
+            #[serde(default)]
+            all: bool,
+            // Only with a single file; defaults to where it was quarantined from
+            dest: Option<String>,
+            #[serde(default)]
+            overwrite: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.dest.is_some() && params.all {
+            return Err(Error::InvalidConfig("dest can only be used with a single file".to_string()));
+        }
+        let dir = self.quarantine_dir()?.to_path_buf();
+        let files: Vec<String> = match (&params.file, params.all) {
+            (Some(file), false) => vec![file.clone()],
+            (None, true) => {
+                let listed = self.list_quarantine(task).await?.output.unwrap_or_default();
+                listed["items"].as_array().into_iter().flatten()
+                    .filter_map(|i| i["file"].as_str().map(str::to_string))
+                    .collect()
+            }
+            _ => return Err(Error::InvalidConfig("Provide exactly one of 'file' or 'all'".to_string())),
+        };
+
+        let mut requeued = Vec::new();
+        for file in files {
+            if file.contains(['/', '\\']) || file.contains("..") {
+                return Err(Error::PermissionDenied(format!("Invalid quarantine entry: {}", file)));
+            }
+            let source = dir.join(&file);
+            let sidecar = sidecar_for(&source);
+            let target = match &params.dest {
+                Some(dest) => self.resolve_path(dest)?,
+                None => {
+                    let record: QuarantineRecord = serde_json::from_slice(&fs::read(&sidecar).await?)?;
+                    self.resolve_path(&record.original_path)?
+                }
+            };
+            if !params.overwrite && fs::try_exists(&target).await? {
+                return Err(Error::InvalidConfig(format!(
+                    "{} already exists; pass overwrite: true", target.display()
+                )));
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::rename(&source, &target).await?;
+            let _ = fs::remove_file(&sidecar).await;
+            self.settle(&target, None).await?;
+            self.settle_removed(&source).await?;
+            requeued.push(json!({ "file": file, "path": target }));
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({ "requeued": requeued })),
+            error: None,
+        })
+    }
+}
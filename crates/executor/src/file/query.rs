@@ -0,0 +1,130 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// Either a JSON Pointer (`/items/0/id`) or a dotted path (`items.0.id`,
+// `items[0].id`), with an optional fallback for when it doesn't resolve
+#[derive(Deserialize, Default)]
+struct Query {
+    pointer: Option<String>,
+    select: Option<String>,
+    default: Option<Value>,
+}
+
+// In `selections` a bare string is a pointer when it starts with '/', otherwise
+// a dotted path
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Selection {
+    Expr(String),
+    Query(Query),
+}
+
+impl Query {
+    fn segments(&self) -> Result<(String, Vec<String>)> {
+        match (&self.pointer, &self.select) {
+            (Some(pointer), None) => Ok((pointer.clone(), pointer_segments(pointer)?)),
+            (None, Some(select)) => Ok((select.clone(), select_segments(select))),
+            _ => Err(Error::InvalidConfig("Give exactly one of pointer or select".to_string())),
+        }
+    }
+}
+
+fn pointer_segments(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/').ok_or_else(|| Error::InvalidConfig(format!(
+        "JSON pointer '{}' must start with '/'", pointer
+    )))?;
+    Ok(rest.split('/').map(|s| s.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+fn select_segments(select: &str) -> Vec<String> {
+    select.replace('[', ".").replace(']', "")
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Names the first segment that doesn't resolve
+fn lookup<'a>(doc: &'a Value, segments: &[String], expr: &str) -> std::result::Result<&'a Value, String> {
+    let mut current = doc;
+    for segment in segments {
+        let next = match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        current = next.ok_or_else(|| format!("'{}' does not resolve at segment '{}'", expr, segment))?;
+    }
+    Ok(current)
+}
+
+fn extract(doc: &Value, query: &Query, display: &str) -> Result<Value> {
+    let (expr, segments) = query.segments()?;
+    match (lookup(doc, &segments, &expr), &query.default) {
+        (Ok(value), _) => Ok(value.clone()),
+        (Err(_), Some(default)) => Ok(default.clone()),
+        (Err(reason), None) => Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} in {}", reason, display),
+        ))),
+    }
+}
+
+impl FileExecutor {
+    // Pluck one value (or several, by name, with `selections`) out of a JSON
+    // file instead of returning the whole document
+    pub(super) async fn query_json(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(flatten)]
+            query: Query,
+            selections: Option<BTreeMap<String, Selection>>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let content = fs::read_to_string(&full_path).await?;
+        let doc: Value = serde_json::from_str(&content).map_err(|e| Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid JSON in {}: {}", params.path, e),
+        )))?;
+
+        let single = params.query.pointer.is_some() || params.query.select.is_some() || params.query.default.is_some();
+        let output = match params.selections {
+            Some(_) if single => return Err(Error::InvalidConfig(
+                "selections cannot be combined with pointer, select or default".to_string()
+            )),
+            Some(selections) => {
+                let mut values = Map::new();
+                for (name, selection) in selections {
+                    let query = match selection {
+                        Selection::Expr(expr) if expr.starts_with('/') => Query { pointer: Some(expr), ..Query::default() },
+                        Selection::Expr(expr) => Query { select: Some(expr), ..Query::default() },
+                        Selection::Query(query) => query,
+                    };
+                    values.insert(name, extract(&doc, &query, &params.path)?);
+                }
+                Value::Object(values)
+            }
+            None => extract(&doc, &params.query, &params.path)?,
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use local_automation_common::{Error, Result, Task};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+impl FileExecutor {
+    pub(super) async fn replace(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            find: String,
+            replace: String,
+            // `find` is a pattern and `replace` may use $1 / ${name}
+            #[serde(default)]
+            regex: bool,
+            // Most replacements to make; all when unset
+            count: Option<usize>,
+            #[serde(default)]
+            allow_no_match: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.find.is_empty() {
+            return Err(Error::InvalidConfig("find must not be empty".to_string()));
+        }
+        let full_path = self.resolve_path(&params.path)?;
+        let text = fs::read_to_string(&full_path).await?;
+
+        // replacen treats 0 as "no limit"
+        let limit = params.count.unwrap_or(0);
+        let (found, edited) = if params.regex {
+            let regex = Regex::new(&params.find).map_err(|e| Error::InvalidConfig(e.to_string()))?;
+            let found = regex.find_iter(&text).count();
+            (found, regex.replacen(&text, limit, params.replace.as_str()).into_owned())
+        } else {
+            let found = text.matches(params.find.as_str()).count();
+            let edited = match params.count {
+                Some(count) => text.replacen(params.find.as_str(), &params.replace, count),
+                None => text.replace(params.find.as_str(), &params.replace),
+            };
+            (found, edited)
+        };
+        let replacements = params.count.map_or(found, |count| found.min(count));
+
+        if replacements == 0 {
+            if !params.allow_no_match {
+                return Err(Error::InvalidConfig(format!(
+                    "No match for '{}' in {}; pass allow_no_match: true to accept", params.find, params.path
+                )));
+            }
+            return Ok(ExecutionResult {
+                success: true,
+                output: Some(json!({ "path": params.path, "replacements": 0, "written": false })),
+                error: None,
+            });
+        }
+
+        // Whole new content next to the target, then one rename over it
+        let mut tmp_path = full_path.as_os_str().to_os_string();
+        tmp_path.push(".tmp");
+        let permissions = fs::metadata(&full_path).await?.permissions();
+        let mut tmp = fs::File::create(&tmp_path).await?;
+        tmp.write_all(edited.as_bytes()).await?;
+        tmp.sync_all().await?;
+        drop(tmp);
+        fs::set_permissions(&tmp_path, permissions).await?;
+        fs::rename(&tmp_path, &full_path).await?;
+        self.settle(&full_path, Some(edited.len() as u64)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "replacements": replacements,
+                "written": true,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use local_automation_common::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::dialect::CsvDialect;
+
+// `<path>.progress.json`: how far a checkpointed CSV write got. `bytes` always
+// ends on a row boundary, so anything past it is a torn or unrecorded tail.
+#[derive(Serialize, Deserialize)]
+struct Progress {
+    fingerprint: String,
+    rows: u64,
+    bytes: u64,
+}
+
+pub(super) struct ResumeOptions {
+    pub checkpoint_rows: usize,
+    // Source index of the first row handed to this call
+    pub rows_offset: u64,
+    // False for intermediate batches of a producer that sends rows in chunks
+    pub finalize: bool,
+    pub expected_total_rows: Option<u64>,
+}
+
+pub(super) struct ResumeOutcome {
+    pub rows_written: u64,
+    pub total_rows: u64,
+    pub resumed_from: Option<u64>,
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+fn progress_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".progress.json");
+    PathBuf::from(name)
+}
+
+fn render_record(record: &[String], dialect: &CsvDialect) -> Result<Vec<u8>> {
+    let mut wtr = dialect.writer()?.from_writer(vec![]);
+    wtr.write_record(record).map_err(|e| invalid_data(e.to_string()))?;
+    wtr.into_inner().map_err(|e| invalid_data(e.to_string()))
+}
+
+fn save_progress(path: &Path, progress: &Progress) -> Result<()> {
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+    let mut tmp = File::create(&tmp_path)?;
+    tmp.write_all(&serde_json::to_vec(progress)?)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// Write `rows` (already rendered) to `path`, checkpointing every few rows so an
+// interrupted export can be continued. A rerun with the same rows produces the
+// same bytes as an uninterrupted run.
+pub(super) fn write_csv_resumable(
+    path: &Path,
+    headers: &[String],
+    rows: &[Vec<String>],
+    dialect: &CsvDialect,
+    options: &ResumeOptions,
+) -> Result<ResumeOutcome> {
+    let sidecar = progress_path(path);
+    let header = match headers.is_empty() {
+        true => Vec::new(),
+        false => render_record(headers, dialect)?,
+    };
+    let fingerprint = hex::encode(Sha256::digest(&header));
+
+    let previous: Option<Progress> = match std::fs::read(&sidecar) {
+        Ok(content) => Some(serde_json::from_slice(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let (mut file, mut progress, resumed_from) = match previous {
+        Some(progress) => {
+            if progress.fingerprint != fingerprint {
+                return Err(Error::InvalidConfig(format!(
+                    "{} holds progress for a different header; delete it to start over",
+                    sidecar.display()
+                )));
+            }
+            let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+            let len = file.metadata()?.len();
+            if len < progress.bytes || progress.bytes < header.len() as u64 {
+                return Err(invalid_data(format!(
+                    "{} is shorter than its recorded progress ({} < {} bytes)",
+                    path.display(), len, progress.bytes
+                )));
+            }
+            // The last recorded row must be complete; nothing recorded yet
+            // (no header, no rows) has no last byte to check
+            if progress.bytes > 0 {
+                let mut last = [0u8; 1];
+                file.seek(SeekFrom::Start(progress.bytes - 1))?;
+                file.read_exact(&mut last)?;
+                if last[0] != b'\n' {
+                    return Err(invalid_data(format!(
+                        "{} does not end a row at recorded offset {}", path.display(), progress.bytes
+                    )));
+                }
+            }
+            file.set_len(progress.bytes)?;
+            file.seek(SeekFrom::End(0))?;
+            let rows = progress.rows;
+            (file, progress, Some(rows))
+        }
+        None => {
+            let mut file = File::create(path)?;
+            file.write_all(&header)?;
+            let progress = Progress { fingerprint, rows: 0, bytes: header.len() as u64 };
+            (file, progress, None)
+        }
+    };
+
+    if options.rows_offset > progress.rows {
+        return Err(Error::InvalidConfig(format!(
+            "Output has {} rows but this batch starts at row {}", progress.rows, options.rows_offset
+        )));
+    }
+    let skip = ((progress.rows - options.rows_offset) as usize).min(rows.len());
+
+    let checkpoint = |file: &mut File, progress: &Progress| -> Result<()> {
+        file.sync_data()?;
+        save_progress(&sidecar, progress)
+    };
+
+    let mut out = std::io::BufWriter::new(&mut file);
+    let mut since_checkpoint = 0usize;
+    for row in &rows[skip..] {
+        let bytes = render_record(row, dialect)?;
+        out.write_all(&bytes)?;
+        progress.rows += 1;
+        progress.bytes += bytes.len() as u64;
+        since_checkpoint += 1;
+        if since_checkpoint >= options.checkpoint_rows.max(1) {
+            out.flush()?;
+            checkpoint(out.get_mut(), &progress)?;
+            since_checkpoint = 0;
+        }
+    }
+    out.flush()?;
+    drop(out);
+
+    if !options.finalize {
+        checkpoint(&mut file, &progress)?;
+    } else {
+        file.sync_data()?;
+        drop(file);
+        let expected = options.expected_total_rows.unwrap_or(progress.rows);
+        let mut reader = dialect.reader()?.from_path(path).map_err(|e| invalid_data(e.to_string()))?;
+        let mut counted = 0u64;
+        for record in reader.records() {
+            record.map_err(|e| invalid_data(e.to_string()))?;
+            counted += 1;
+        }
+        if counted != expected || counted != progress.rows {
+            return Err(invalid_data(format!(
+                "{} has {} rows, expected {}", path.display(), counted, expected
+            )));
+        }
+        match std::fs::remove_file(&sidecar) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(ResumeOutcome {
+        rows_written: (rows.len() - skip) as u64,
+        total_rows: progress.rows,
+        resumed_from,
+    })
+}
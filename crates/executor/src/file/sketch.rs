@@ -0,0 +1,88 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+pub(super) fn hash_value<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// HyperLogLog distinct-count estimator with 2^PRECISION one-byte registers.
+pub(super) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+const PRECISION: u32 = 12;
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self { registers: vec![0; 1 << PRECISION] }
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        let hash = hash_value(value);
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rest = hash << PRECISION;
+        let rank = (rest.leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is far more accurate for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+// Space-Saving heavy hitters: tracks at most `capacity` counters, so memory stays
+// bounded no matter how many distinct values stream past.
+pub(super) struct TopK {
+    capacity: usize,
+    counts: HashMap<String, u64>,
+}
+
+impl TopK {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), counts: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, value: &str) {
+        if let Some(count) = self.counts.get_mut(value) {
+            *count += 1;
+            return;
+        }
+        if self.counts.len() < self.capacity {
+            self.counts.insert(value.to_string(), 1);
+            return;
+        }
+        let (victim, min) = self
+            .counts
+            .iter()
+            .min_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(k, v)| (k.clone(), *v))
+            .expect("capacity is at least one");
+        self.counts.remove(&victim);
+        self.counts.insert(value.to_string(), min + 1);
+    }
+
+    // Highest counts first, ties broken by value for stable output
+    pub fn top(&self, n: usize) -> Vec<(String, u64)> {
+        let mut entries: Vec<(String, u64)> =
+            self.counts.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+}
@@ -0,0 +1,249 @@
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::glob::relative_string;
+use super::internal::InternalPaths;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const NAME_FORMAT: &str = "%Y-%m-%dT%H-%M-%SZ";
+// Half-built and half-deleted snapshots never carry a parseable name, so they
+// are never mistaken for the latest snapshot and get swept on the next run
+const INCOMPLETE_PREFIX: &str = ".incomplete-";
+const DELETING_PREFIX: &str = ".deleting-";
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Compare {
+    // Same size and mtime
+    Metadata,
+    Hash,
+}
+
+#[derive(Default)]
+struct BuildStats {
+    linked: u64,
+    copied: u64,
+    new_bytes: u64,
+    linked_bytes: u64,
+    // Set once a hardlink fails; everything after that is copied
+    fallback: Option<String>,
+}
+
+fn parse_name(name: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(name, NAME_FORMAT).ok().map(|t| t.and_utc())
+}
+
+// Complete snapshots, oldest first
+fn existing_snapshots(dir: &Path) -> Result<Vec<(DateTime<Utc>, String)>> {
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if name.starts_with(INCOMPLETE_PREFIX) || name.starts_with(DELETING_PREFIX) {
+            fs::remove_dir_all(entry.path())?;
+            continue;
+        }
+        if let Some(at) = parse_name(&name) {
+            snapshots.push((at, name));
+        }
+    }
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+fn sha256(path: &Path) -> Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn unchanged(source: &Path, meta: &fs::Metadata, previous: &Path, compare: Compare) -> Result<bool> {
+    let Ok(prev_meta) = fs::symlink_metadata(previous) else {
+        return Ok(false);
+    };
+    if !prev_meta.is_file() || prev_meta.len() != meta.len() {
+        return Ok(false);
+    }
+    Ok(match compare {
+        Compare::Metadata => prev_meta.modified()? == meta.modified()?,
+        Compare::Hash => sha256(source)? == sha256(previous)?,
+    })
+}
+
+fn copy_preserving_mtime(source: &Path, dest: &Path, meta: &fs::Metadata) -> Result<()> {
+    fs::copy(source, dest)?;
+    File::options().write(true).open(dest)?.set_modified(meta.modified()?)?;
+    Ok(())
+}
+
+fn build(source: &Path, previous: Option<&Path>, dest: &Path, compare: Compare, internal: &InternalPaths) -> Result<BuildStats> {
+    let mut stats = BuildStats::default();
+    fs::create_dir(dest)?;
+    for entry in WalkDir::new(source).follow_links(false).min_depth(1).into_iter().filter_entry(|e| !internal.contains(e.path())) {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        let relative = entry.path().strip_prefix(source).expect("walk stays under source");
+        let target = dest.join(relative);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir(&target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+        } else {
+            let meta = entry.metadata().map_err(|e| Error::Io(e.into()))?;
+            let prior = previous.map(|p| p.join(relative));
+            let reusable = match &prior {
+                Some(prior) if stats.fallback.is_none() => unchanged(entry.path(), &meta, prior, compare)?,
+                _ => false,
+            };
+            if reusable {
+                let prior = prior.expect("checked above");
+                match fs::hard_link(&prior, &target) {
+                    Ok(()) => {
+                        stats.linked += 1;
+                        stats.linked_bytes += meta.len();
+                        continue;
+                    }
+                    // No hardlinks on this filesystem (or across devices): copy from here on
+                    Err(e) => stats.fallback = Some(e.to_string()),
+                }
+            }
+            copy_preserving_mtime(entry.path(), &target, &meta)?;
+            stats.copied += 1;
+            stats.new_bytes += meta.len();
+        }
+    }
+    Ok(stats)
+}
+
+// Newest snapshot overall, plus the newest of each of the last `daily` days and
+// the last `weekly` ISO weeks that have one
+fn retained(snapshots: &[(DateTime<Utc>, String)], daily: usize, weekly: usize) -> HashSet<String> {
+    let mut keep = HashSet::new();
+    let (mut days, mut weeks) = (Vec::new(), Vec::new());
+    for (at, name) in snapshots.iter().rev() {
+        if keep.is_empty() {
+            keep.insert(name.clone());
+        }
+        let day = at.date_naive();
+        if !days.contains(&day) && days.len() < daily {
+            days.push(day);
+            keep.insert(name.clone());
+        }
+        let week = (at.iso_week().year(), at.iso_week().week());
+        if !weeks.contains(&week) && weeks.len() < weekly {
+            weeks.push(week);
+            keep.insert(name.clone());
+        }
+    }
+    keep
+}
+
+impl FileExecutor {
+    // Time-machine style: every snapshot is a full tree, but files unchanged since
+    // the latest snapshot are hardlinks to it, so only changes cost space.
+    // Deleting an old snapshot only drops link counts, never data a newer one uses.
+    pub(super) async fn snapshot(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            source: String,
+            snapshots_dir: String,
+            #[serde(default = "default_keep_daily")]
+            keep_daily: usize,
+            #[serde(default = "default_keep_weekly")]
+            keep_weekly: usize,
+            #[serde(default = "default_compare")]
+            compare: Compare,
+            // Names the snapshot; defaults to now
+            at: Option<DateTime<Utc>>,
+        }
+
+        fn default_keep_daily() -> usize { 7 }
+        fn default_keep_weekly() -> usize { 4 }
+        fn default_compare() -> Compare { Compare::Metadata }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let source = self.resolve_path(&params.source)?;
+        let dir = self.resolve_path(&params.snapshots_dir)?;
+        if !tokio::fs::metadata(&source).await?.is_dir() {
+            return Err(Error::InvalidConfig(format!("{} is not a directory", params.source)));
+        }
+        if dir.starts_with(&source) {
+            return Err(Error::InvalidConfig("snapshots_dir must not be inside source".to_string()));
+        }
+        let name = params.at.unwrap_or_else(Utc::now).format(NAME_FORMAT).to_string();
+        let (base, internal) = (self.base_path.clone(), self.internal_paths());
+
+        let (output, snapshot_path) = tokio::task::spawn_blocking(move || -> Result<_> {
+            fs::create_dir_all(&dir)?;
+            let snapshots = existing_snapshots(&dir)?;
+            if snapshots.iter().any(|(_, n)| *n == name) {
+                return Err(Error::InvalidConfig(format!("Snapshot {} already exists", name)));
+            }
+            let previous = snapshots.last().map(|(_, n)| n.clone());
+
+            let building = dir.join(format!("{}{}", INCOMPLETE_PREFIX, name));
+            let stats = build(&source, previous.as_ref().map(|p| dir.join(p)).as_deref(), &building, params.compare, &internal);
+            let stats = match stats {
+                Ok(stats) => stats,
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&building);
+                    return Err(e);
+                }
+            };
+            let snapshot_path: PathBuf = dir.join(&name);
+            fs::rename(&building, &snapshot_path)?;
+
+            // Prune oldest first; a failure leaves a `.deleting-` dir for the next
+            // run rather than a partial snapshot that looks complete
+            let snapshots = existing_snapshots(&dir)?;
+            let keep = retained(&snapshots, params.keep_daily, params.keep_weekly);
+            let (mut pruned, mut prune_errors) = (Vec::new(), Vec::new());
+            for (_, old) in snapshots.iter().filter(|(_, n)| !keep.contains(n)) {
+                let doomed = dir.join(format!("{}{}", DELETING_PREFIX, old));
+                let removed = fs::rename(dir.join(old), &doomed).and_then(|_| fs::remove_dir_all(&doomed));
+                match removed {
+                    Ok(()) => pruned.push(old.clone()),
+                    Err(e) => prune_errors.push(json!({ "snapshot": old, "error": e.to_string() })),
+                }
+            }
+
+            Ok((json!({
+                "snapshot": name,
+                "path": relative_string(&base, &snapshot_path),
+                "previous": previous,
+                "linked": stats.linked,
+                "copied": stats.copied,
+                "new_bytes": stats.new_bytes,
+                "linked_bytes": stats.linked_bytes,
+                "hardlink_fallback": stats.fallback,
+                "pruned": pruned,
+                "prune_errors": prune_errors,
+            }), snapshot_path))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&snapshot_path, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::SystemTime;
+use tokio::fs;
+
+use super::consistency::ExpectRecent;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// Platforms without a given timestamp report null for it
+fn timestamp(time: std::io::Result<SystemTime>) -> Value {
+    match time {
+        Ok(time) => json!(DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Err(_) => Value::Null,
+    }
+}
+
+#[cfg(unix)]
+fn mode(metadata: &std::fs::Metadata) -> Value {
+    use std::os::unix::fs::PermissionsExt;
+    json!(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &std::fs::Metadata) -> Value {
+    Value::Null
+}
+
+impl FileExecutor {
+    // Size, timestamps and type of `path`. Symlinks are reported as such and
+    // described by their target when it exists.
+    pub(super) async fn stat(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default)]
+            allow_missing: bool,
+            expect_recent: Option<ExpectRecent>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        if let Some(barrier) = &params.expect_recent {
+            self.await_recent(&full_path, barrier).await?;
+        }
+        let link_metadata = match fs::symlink_metadata(&full_path).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && params.allow_missing => {
+                return Ok(ExecutionResult {
+                    success: true,
+                    output: Some(json!({ "path": params.path, "exists": false })),
+                    error: None,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let metadata = match is_symlink {
+            true => fs::metadata(&full_path).await.unwrap_or(link_metadata),
+            false => link_metadata,
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "exists": true,
+                "size": metadata.len(),
+                "is_file": metadata.is_file(),
+                "is_dir": metadata.is_dir(),
+                "is_symlink": is_symlink,
+                "created": timestamp(metadata.created()),
+                "modified": timestamp(metadata.modified()),
+                "accessed": timestamp(metadata.accessed()),
+                "mode": mode(&metadata),
+            })),
+            error: None,
+        })
+    }
+}
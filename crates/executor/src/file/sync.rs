@@ -0,0 +1,202 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::glob::relative_string;
+use super::impact::ConfirmImpact;
+use super::internal::InternalPaths;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Compare {
+    // Same size and modification time; copies carry the source mtime over
+    #[default]
+    MtimeSize,
+    // Same size and content digest
+    Hash,
+}
+
+#[derive(Default)]
+struct Plan {
+    copy: Vec<String>,
+    skip: Vec<String>,
+    delete: Vec<String>,
+    // Directories only in `to`, deepest first
+    delete_dirs: Vec<String>,
+    bytes: u64,
+}
+
+fn walk_error(e: walkdir::Error) -> Error {
+    Error::Io(e.into())
+}
+
+pub(super) fn digest(path: &Path) -> Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn unchanged(from: &Path, to: &Path, compare: Compare) -> Result<bool> {
+    let (source, dest) = (std::fs::metadata(from)?, std::fs::symlink_metadata(to)?);
+    if !dest.is_file() || source.len() != dest.len() {
+        return Ok(false);
+    }
+    Ok(match compare {
+        Compare::MtimeSize => source.modified()? == dest.modified()?,
+        Compare::Hash => digest(from)? == digest(to)?,
+    })
+}
+
+// Symlinks are neither followed nor mirrored
+fn plan(from: &Path, to: &Path, compare: Compare, delete_extraneous: bool, internal: &InternalPaths) -> Result<Plan> {
+    let mut plan = Plan::default();
+    let (mut files, mut dirs) = (HashSet::new(), HashSet::new());
+    let walker = WalkDir::new(from).min_depth(1).follow_links(false).sort_by_file_name();
+    for entry in walker.into_iter().filter_entry(|e| !internal.contains(e.path())) {
+        let entry = entry.map_err(walk_error)?;
+        let Some(name) = relative_string(from, entry.path()) else {
+            continue;
+        };
+        if entry.file_type().is_dir() {
+            if to.join(&name).symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+                return Err(Error::InvalidConfig(format!("{} is a directory in from but not in to", name)));
+            }
+            dirs.insert(name);
+        } else if entry.file_type().is_file() {
+            let dest = to.join(&name);
+            if dest.symlink_metadata().is_ok_and(|m| m.is_dir()) {
+                return Err(Error::InvalidConfig(format!("{} is a file in from but a directory in to", name)));
+            }
+            if dest.symlink_metadata().is_ok() && unchanged(entry.path(), &dest, compare)? {
+                plan.skip.push(name.clone());
+            } else {
+                plan.bytes += entry.metadata().map_err(walk_error)?.len();
+                plan.copy.push(name.clone());
+            }
+            files.insert(name);
+        }
+    }
+
+    if delete_extraneous && to.is_dir() {
+        let walker = WalkDir::new(to).min_depth(1).follow_links(false).contents_first(true).sort_by_file_name();
+        for entry in walker.into_iter().filter_entry(|e| !internal.contains(e.path())) {
+            let entry = entry.map_err(walk_error)?;
+            let Some(name) = relative_string(to, entry.path()) else {
+                continue;
+            };
+            if entry.file_type().is_dir() {
+                if !dirs.contains(&name) {
+                    plan.delete_dirs.push(name);
+                }
+            } else if !files.contains(&name) {
+                plan.delete.push(name);
+            }
+        }
+        plan.delete.sort();
+    }
+    Ok(plan)
+}
+
+fn apply(from: &Path, to: &Path, plan: &Plan) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for name in &plan.copy {
+        let (source, dest) = (from.join(name), to.join(name));
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if dest.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+            std::fs::remove_file(&dest)?;
+        }
+        std::fs::copy(&source, &dest)?;
+        let modified = std::fs::metadata(&source)?.modified()?;
+        File::options().write(true).open(&dest)?.set_modified(modified)?;
+    }
+    for name in &plan.delete {
+        std::fs::remove_file(to.join(name))?;
+    }
+    for name in &plan.delete_dirs {
+        std::fs::remove_dir(to.join(name))?;
+    }
+    Ok(())
+}
+
+impl FileExecutor {
+    // Make `to` mirror `from`: new and changed files are copied over, and with
+    // `delete_extraneous` anything only in `to` is removed
+    pub(super) async fn sync_dir(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            from: String,
+            to: String,
+            #[serde(default)]
+            delete_extraneous: bool,
+            #[serde(default)]
+            compare: Compare,
+            #[serde(default)]
+            dry_run: bool,
+            confirm_impact: Option<ConfirmImpact>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let from_path = self.resolve_path(&params.from)?;
+        let to_path = self.resolve_path(&params.to)?;
+        if !tokio::fs::metadata(&from_path).await?.is_dir() {
+            return Err(Error::InvalidConfig(format!("{} is not a directory", params.from)));
+        }
+        if to_path.starts_with(&from_path) || from_path.starts_with(&to_path) {
+            return Err(Error::InvalidConfig("from and to must not contain each other".to_string()));
+        }
+
+        let (from, to) = (from_path.clone(), to_path.clone());
+        let (compare, delete_extraneous) = (params.compare, params.delete_extraneous);
+        let internal = self.internal_paths();
+        let plan = tokio::task::spawn_blocking(move || plan(&from, &to, compare, delete_extraneous, &internal))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        let plan = match params.dry_run {
+            true => plan,
+            false => {
+                let doomed: Vec<PathBuf> = plan.delete.iter().map(|name| to_path.join(name)).collect();
+                let doomed: Vec<&Path> = doomed.iter().map(PathBuf::as_path).collect();
+                self.guard_impact("sync_dir", &doomed, params.confirm_impact).await?;
+
+                let (from, to) = (from_path.clone(), to_path.clone());
+                let plan = tokio::task::spawn_blocking(move || apply(&from, &to, &plan).map(|()| plan))
+                    .await
+                    .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+                self.settle(&to_path, None).await?;
+                plan
+            }
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "from": params.from,
+                "to": params.to,
+                "dry_run": params.dry_run,
+                "copied": plan.copy,
+                "skipped": plan.skip,
+                "deleted": plan.delete,
+                "deleted_dirs": plan.delete_dirs,
+                "totals": {
+                    "copied": plan.copy.len(),
+                    "skipped": plan.skip.len(),
+                    "deleted": plan.delete.len(),
+                    "bytes_copied": plan.bytes,
+                },
+            })),
+            error: None,
+        })
+    }
+}
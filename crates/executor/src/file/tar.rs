@@ -0,0 +1,264 @@
+use ::tar::{Archive, Builder, EntryType};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufWriter};
+use tokio::runtime::Handle;
+use tokio_util::io::SyncIoBridge;
+use walkdir::WalkDir;
+
+use super::compress::resolve_codec;
+use super::glob::relative_string;
+use super::zip::{safe_name, UnsafeEntries};
+use super::internal::InternalPaths;
+use super::FileExecutor;
+use crate::codec::{self, Codec, CodecWriter};
+use crate::traits::ExecutionResult;
+
+struct Totals {
+    entries: Vec<String>,
+    skipped: Vec<String>,
+    uncompressed_bytes: u64,
+}
+
+// `compression` names a codec, or "none" for a plain tar
+fn named_codec(compression: Option<&str>) -> Result<Option<&'static dyn Codec>> {
+    match compression {
+        None => Ok(None),
+        Some(name) if name.eq_ignore_ascii_case("none") => Ok(None),
+        Some(name) => codec::by_name(name).map(Some).ok_or_else(|| Error::InvalidConfig(format!(
+            "Unknown compression '{}' (available: none, {})", name, codec::available().join(", ")
+        ))),
+    }
+}
+
+// Runs on a blocking thread; decoding is bridged back to the runtime
+fn open(path: &Path, codec: Option<&'static dyn Codec>, handle: &Handle) -> Result<Archive<Box<dyn Read>>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = match codec {
+        None => Box::new(BufReader::new(file)),
+        Some(codec) => {
+            let source = tokio::io::BufReader::new(tokio::fs::File::from_std(file));
+            Box::new(SyncIoBridge::new_with_handle(codec.decoder(Box::pin(source)), handle.clone()))
+        }
+    };
+    Ok(Archive::new(reader))
+}
+
+// A directory source contributes its contents relative to itself, a file
+// source just its file name, so extracting reproduces the source layout
+fn create<W: Write>(sources: &[PathBuf], dest: &Path, internal: &InternalPaths, writer: W) -> Result<(W, Vec<String>, u64)> {
+    let mut builder = Builder::new(writer);
+    builder.follow_symlinks(false);
+    let mut entries = Vec::new();
+    let mut uncompressed = 0;
+    for source in sources {
+        let root = match std::fs::symlink_metadata(source)?.is_dir() {
+            true => source.clone(),
+            false => source.parent().map(Path::to_path_buf).unwrap_or_default(),
+        };
+        let walker = WalkDir::new(source).follow_links(false).sort_by_file_name();
+        for entry in walker.into_iter().filter_entry(|e| !internal.contains(e.path())) {
+            let entry = entry.map_err(|e| Error::Io(e.into()))?;
+            if entry.path() == dest {
+                continue;
+            }
+            let Some(name) = relative_string(&root, entry.path()).filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            if entry.file_type().is_dir() {
+                builder.append_dir(&name, entry.path())?;
+            } else {
+                builder.append_path_with_name(entry.path(), &name)?;
+                if entry.file_type().is_file() {
+                    uncompressed += entry.metadata().map_err(|e| Error::Io(e.into()))?.len();
+                }
+                entries.push(name);
+            }
+        }
+    }
+    Ok((builder.into_inner()?, entries, uncompressed))
+}
+
+fn extract(
+    path: &Path,
+    codec: Option<&'static dyn Codec>,
+    handle: &Handle,
+    dest_dir: &Path,
+    on_unsafe: UnsafeEntries,
+    overwrite: bool,
+) -> Result<Totals> {
+    // Links may only point inside the archive's own tree
+    let unsafe_link = |link: Option<&Path>| link.is_some_and(|l| safe_name(&l.to_string_lossy()).is_none());
+
+    // First pass checks every name (and conflict) before anything is written
+    let mut skipped = Vec::new();
+    for entry in open(path, codec, handle)?.entries()? {
+        let entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let is_link = matches!(entry.header().entry_type(), EntryType::Symlink | EntryType::Link);
+        let link = entry.link_name()?;
+        if safe_name(&name).is_none() || (is_link && unsafe_link(link.as_deref())) {
+            match on_unsafe {
+                UnsafeEntries::Fail => return Err(Error::PermissionDenied(format!(
+                    "Archive entry '{}' would extract outside dest_dir", name
+                ))),
+                UnsafeEntries::Skip => skipped.push(name),
+            }
+        } else if !overwrite && !entry.header().entry_type().is_dir() && dest_dir.join(&name).symlink_metadata().is_ok() {
+            return Err(Error::InvalidConfig(format!(
+                "{} already exists; pass overwrite: true to replace it", name
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(dest_dir)?;
+    let mut totals = Totals { entries: Vec::new(), skipped, uncompressed_bytes: 0 };
+    for entry in open(path, codec, handle)?.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if totals.skipped.contains(&name) {
+            continue;
+        }
+        let entry_type = entry.header().entry_type();
+        entry.set_preserve_permissions(true);
+        if overwrite && !entry_type.is_dir() {
+            match std::fs::remove_file(dest_dir.join(&name)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        // unpack_in also refuses to write through links that leave dest_dir
+        if !entry.unpack_in(dest_dir)? {
+            return Err(Error::PermissionDenied(format!(
+                "Archive entry '{}' would extract outside dest_dir", name
+            )));
+        }
+        if !entry_type.is_dir() {
+            if entry_type.is_file() {
+                totals.uncompressed_bytes += entry.size();
+            }
+            totals.entries.push(name);
+        }
+    }
+    Ok(totals)
+}
+
+impl FileExecutor {
+    // Compressed through the shared codecs: `compression` names one, or it is
+    // taken from dest's extension (site.tar.gz), or the tar is left plain
+    pub(super) async fn tar(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            sources: Vec<String>,
+            dest: String,
+            compression: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.sources.is_empty() {
+            return Err(Error::InvalidConfig("sources must not be empty".to_string()));
+        }
+        let sources = params.sources.iter().map(|s| self.resolve_path(s)).collect::<Result<Vec<_>>>()?;
+        let dest = self.resolve_path(&params.dest)?;
+        let codec = match params.compression {
+            Some(_) => named_codec(params.compression.as_deref())?,
+            None => codec::from_extension(&dest),
+        };
+        for source in &sources {
+            tokio::fs::symlink_metadata(source).await?;
+        }
+
+        let out = BufWriter::new(tokio::fs::File::create(&dest).await?);
+        let writer: CodecWriter = match codec {
+            Some(codec) => codec.encoder(Box::pin(out), None),
+            None => Box::pin(out),
+        };
+        let writer = SyncIoBridge::new(writer);
+        let (path, internal) = (dest.clone(), self.internal_paths());
+        let built = tokio::task::spawn_blocking(move || -> Result<_> {
+            let (mut writer, entries, uncompressed) = create(&sources, &path, &internal, writer)?;
+            // Finishes the codec's trailer and flushes the file
+            writer.shutdown()?;
+            Ok((entries, uncompressed))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        let (entries, uncompressed) = match built {
+            Ok(built) => built,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&dest).await;
+                return Err(e);
+            }
+        };
+        let len = tokio::fs::metadata(&dest).await?.len();
+        self.settle(&dest, Some(len)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "dest": dest,
+                "compression": codec.map_or("none", |c| c.name()),
+                "entries": entries,
+                "compressed_bytes": len,
+                "uncompressed_bytes": uncompressed,
+            })),
+            error: None,
+        })
+    }
+
+    // Same entry safety rules as unzip; links must also stay inside dest_dir.
+    // File modes are restored on Unix.
+    pub(super) async fn untar(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            dest_dir: String,
+            // Detected from the content (then the extension) when not given
+            compression: Option<String>,
+            #[serde(default)]
+            on_unsafe: UnsafeEntries,
+            #[serde(default)]
+            overwrite: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let archive = self.resolve_path(&params.path)?;
+        let dest_dir = self.resolve_path(&params.dest_dir)?;
+        let codec = match params.compression.as_deref() {
+            Some(name) if name.eq_ignore_ascii_case("none") => None,
+            name => {
+                let mut source = tokio::io::BufReader::new(tokio::fs::File::open(&archive).await?);
+                let header = source.fill_buf().await?;
+                let header = &header[..header.len().min(codec::SNIFF_LEN)];
+                resolve_codec(name, &archive, header)?
+            }
+        };
+        let (on_unsafe, overwrite) = (params.on_unsafe, params.overwrite);
+        let (handle, source, target) = (Handle::current(), archive.clone(), dest_dir.clone());
+        let totals = tokio::task::spawn_blocking(move || extract(&source, codec, &handle, &target, on_unsafe, overwrite))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&dest_dir, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": archive,
+                "dest_dir": dest_dir,
+                "entries": totals.entries,
+                "skipped": totals.skipped,
+                "uncompressed_bytes": totals.uncompressed_bytes,
+            })),
+            error: None,
+        })
+    }
+}
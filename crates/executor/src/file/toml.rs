@@ -0,0 +1,110 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+// Datetimes become their TOML text, e.g. "1979-05-27T07:32:00Z"
+fn to_json(value: ::toml::Value) -> Result<Value> {
+    Ok(match value {
+        ::toml::Value::String(s) => Value::String(s),
+        ::toml::Value::Integer(i) => i.into(),
+        ::toml::Value::Float(f) => serde_json::Number::from_f64(f).map(Value::Number).ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{} cannot be represented in JSON", f),
+            ))
+        })?,
+        ::toml::Value::Boolean(b) => Value::Bool(b),
+        ::toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        ::toml::Value::Array(items) => Value::Array(items.into_iter().map(to_json).collect::<Result<_>>()?),
+        ::toml::Value::Table(table) => Value::Object(
+            table.into_iter().map(|(k, v)| Ok((k, to_json(v)?))).collect::<Result<Map<_, _>>>()?,
+        ),
+    })
+}
+
+// `at` names the offending value in errors
+fn from_json(value: Value, at: &str) -> Result<::toml::Value> {
+    let unrepresentable = |what: &str| Error::InvalidConfig(format!("{} at {} cannot be represented in TOML", what, at));
+    Ok(match value {
+        Value::Null => return Err(unrepresentable("null")),
+        Value::Bool(b) => ::toml::Value::Boolean(b),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => ::toml::Value::Integer(i),
+            // u64 beyond i64::MAX would silently lose precision as a float
+            _ if n.is_u64() => return Err(unrepresentable("integer above i64::MAX")),
+            (_, Some(f)) => ::toml::Value::Float(f),
+            _ => return Err(unrepresentable("number")),
+        },
+        Value::String(s) => ::toml::Value::String(s),
+        Value::Array(items) => ::toml::Value::Array(
+            items.into_iter().enumerate()
+                .map(|(i, v)| from_json(v, &format!("{}[{}]", at, i)))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => ::toml::Value::Table(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let path = format!("{}.{}", at, k);
+                    Ok((k, from_json(v, &path)?))
+                })
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+impl FileExecutor {
+    pub(super) async fn read_toml(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let content = fs::read_to_string(&full_path).await?;
+        let table: ::toml::Table = content.parse().map_err(|e: ::toml::de::Error| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid TOML in {}: {}", params.path, e),
+            ))
+        })?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(to_json(::toml::Value::Table(table))?),
+            error: None,
+        })
+    }
+
+    pub(super) async fn write_toml(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            data: Value,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if !params.data.is_object() {
+            return Err(Error::InvalidConfig("TOML documents must be a table; data must be an object".to_string()));
+        }
+        let value = from_json(params.data, "data")?;
+        let text = ::toml::to_string_pretty(&value).map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let full_path = self.resolve_path(&params.path)?;
+        fs::write(&full_path, text.as_bytes()).await?;
+        self.settle(&full_path, Some(text.len() as u64)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "path": full_path })),
+            error: None,
+        })
+    }
+}
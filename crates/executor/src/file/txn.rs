@@ -0,0 +1,433 @@
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+// Under base_path so commit renames stay on one filesystem
+pub(super) const STAGING_ROOT: &str = ".txn";
+const JOURNAL: &str = "journal.json";
+
+// Operations that may be tagged with `txn`, and the param naming their target
+const STAGED_OPERATIONS: &[(&str, &str)] = &[
+    ("write", "path"),
+    ("write_json", "path"),
+    ("write_yaml", "path"),
+    ("write_toml", "path"),
+    ("write_csv", "path"),
+    ("write_ndjson", "path"),
+    ("write_bytes", "path"),
+    ("copy", "to"),
+    ("csv_to_json", "to"),
+    ("json_to_csv", "to"),
+];
+
+struct StagedFile {
+    path: PathBuf,
+    depends_on: Vec<PathBuf>,
+}
+
+// An open transaction. Writes land in `staging/files`; dropping it without a
+// commit (rollback, expiry, executor shutdown) discards the staging directory.
+pub(super) struct Transaction {
+    staging: PathBuf,
+    expires_at: Instant,
+    files: Vec<StagedFile>,
+    discard: bool,
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if self.discard {
+            let _ = std::fs::remove_dir_all(&self.staging);
+        }
+    }
+}
+
+// A txn-tagged task rewritten to write into staging; recorded once it succeeds
+pub(super) struct StagedWrite {
+    txn_id: String,
+    file: StagedFile,
+    pub task: Task,
+}
+
+// Written before the first commit rename. A staged file that is gone has
+// been moved into place, which is all recovery needs to know.
+#[derive(Debug, Serialize, Deserialize)]
+struct Journal {
+    txn_id: String,
+    entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    path: PathBuf,
+    // Whether a previous version was saved to `backup` for rollback
+    existed: bool,
+}
+
+fn normalize(path: &str) -> PathBuf {
+    Path::new(path).components().filter(|c| *c != Component::CurDir).collect()
+}
+
+fn staged_path(staging: &Path, path: &Path) -> PathBuf {
+    staging.join("files").join(path)
+}
+
+fn backup_path(staging: &Path, path: &Path) -> PathBuf {
+    staging.join("backup").join(path)
+}
+
+// Dependencies first, otherwise in the order the files were written
+fn commit_order(files: &[StagedFile]) -> Result<Vec<PathBuf>> {
+    let mut order: Vec<PathBuf> = Vec::with_capacity(files.len());
+    let mut remaining: Vec<&StagedFile> = files.iter().collect();
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|file| {
+            file.depends_on.iter().all(|dep| {
+                order.contains(dep) || !remaining.iter().any(|other| &other.path == dep)
+            })
+        });
+        let Some(ready) = ready else {
+            let cycle: Vec<String> = remaining.iter().map(|f| f.path.display().to_string()).collect();
+            return Err(Error::InvalidConfig(format!(
+                "Transaction dependencies form a cycle among: {}", cycle.join(", ")
+            )));
+        };
+        order.push(remaining.remove(ready).path.clone());
+    }
+    Ok(order)
+}
+
+async fn link_or_copy(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    if fs::hard_link(from, to).await.is_err() {
+        fs::copy(from, to).await?;
+    }
+    Ok(())
+}
+
+impl FileExecutor {
+    fn take_transaction(&self, txn_id: &str) -> Result<Transaction> {
+        let txn = self.transactions.lock().unwrap().remove(txn_id);
+        match txn {
+            Some(txn) if Instant::now() < txn.expires_at => Ok(txn),
+            Some(_) => Err(Error::InvalidConfig(format!(
+                "Transaction {} expired and was rolled back", txn_id
+            ))),
+            None => Err(Error::InvalidConfig(format!("Unknown transaction: {}", txn_id))),
+        }
+    }
+
+    pub(super) async fn txn_begin(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            timeout_secs: Option<u64>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let txn_id = uuid::Uuid::new_v4().to_string();
+        let relative = Path::new(STAGING_ROOT).join(&txn_id);
+        let staging = self.base_path.join(&relative);
+        fs::create_dir_all(staging.join("files")).await?;
+
+        let timeout = Duration::from_secs(params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let txn = Transaction {
+            staging,
+            expires_at: Instant::now() + timeout,
+            files: Vec::new(),
+            discard: true,
+        };
+        self.transactions.lock().unwrap().insert(txn_id.clone(), txn);
+
+        // Roll back if nobody commits in time
+        let transactions = Arc::downgrade(&self.transactions);
+        let id = txn_id.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if let Some(transactions) = transactions.upgrade() {
+                let expired = transactions.lock().unwrap().remove(&id);
+                drop(expired);
+            }
+        });
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "txn_id": txn_id,
+                "staging_dir": relative.join("files"),
+                "timeout_secs": timeout.as_secs(),
+            })),
+            error: None,
+        })
+    }
+
+    // For a task carrying `txn`, the same task pointed at the staging area
+    pub(super) async fn txn_prepare(&self, task: &Task) -> Result<Option<StagedWrite>> {
+        #[derive(Deserialize)]
+        struct Params {
+            txn: String,
+            // Paths in the same transaction that must be in place before this one
+            #[serde(default)]
+            txn_depends_on: Vec<String>,
+        }
+
+        if task.params.get("txn").is_none() {
+            return Ok(None);
+        }
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let Some((_, target_param)) = STAGED_OPERATIONS.iter().find(|(op, _)| *op == task.operation) else {
+            return Err(Error::InvalidConfig(format!(
+                "Operation '{}' cannot be part of a transaction", task.operation
+            )));
+        };
+        let target = task.params.get(*target_param).and_then(Value::as_str).ok_or_else(|| {
+            Error::InvalidConfig(format!("Missing '{}'", target_param))
+        })?;
+        self.resolve_path(target)?;
+        // The commit replaces targets wholesale; a backup of the staged copy is meaningless
+        if task.params.get("backup").and_then(Value::as_bool) == Some(true) {
+            return Err(Error::InvalidConfig("backup is not supported inside a transaction".to_string()));
+        }
+        let path = normalize(target);
+        // Staging mirrors the target under the staging dir, which only works
+        // for plain relative paths
+        if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(Error::InvalidConfig(format!(
+                "Transaction targets must be relative paths under the base path, got '{}'", target
+            )));
+        }
+
+        let staging = {
+            let transactions = self.transactions.lock().unwrap();
+            match transactions.get(&params.txn) {
+                Some(txn) if Instant::now() < txn.expires_at => txn.staging.clone(),
+                _ => return Err(Error::InvalidConfig(format!("Unknown transaction: {}", params.txn))),
+            }
+        };
+        let staged = staged_path(&staging, &path);
+        if !staged.starts_with(&staging) {
+            return Err(Error::PermissionDenied(format!("{} would be staged outside the transaction", target)));
+        }
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut params_out = task.params.clone();
+        if let Some(map) = params_out.as_object_mut() {
+            map.remove("txn");
+            map.remove("txn_depends_on");
+            let relative = staged.strip_prefix(&self.base_path).unwrap_or(&staged);
+            map.insert(target_param.to_string(), json!(relative));
+        }
+        Ok(Some(StagedWrite {
+            txn_id: params.txn,
+            file: StagedFile {
+                path,
+                depends_on: params.txn_depends_on.iter().map(|p| normalize(p)).collect(),
+            },
+            task: Task { params: params_out, ..task.clone() },
+        }))
+    }
+
+    pub(super) fn txn_record(&self, staged: StagedWrite, mut result: ExecutionResult) -> Result<ExecutionResult> {
+        let mut transactions = self.transactions.lock().unwrap();
+        let Some(txn) = transactions.get_mut(&staged.txn_id) else {
+            return Err(Error::InvalidConfig(format!(
+                "Transaction {} ended while writing {}", staged.txn_id, staged.file.path.display()
+            )));
+        };
+        if let Some(Value::Object(map)) = &mut result.output {
+            map.insert("txn".to_string(), json!(staged.txn_id));
+            map.insert("target".to_string(), json!(staged.file.path));
+        }
+        // Writing a path again replaces it, keeping its original position
+        match txn.files.iter_mut().find(|f| f.path == staged.file.path) {
+            Some(existing) => existing.depends_on = staged.file.depends_on,
+            None => txn.files.push(staged.file),
+        }
+        Ok(result)
+    }
+
+    pub(super) async fn txn_commit(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            txn_id: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let mut txn = self.take_transaction(&params.txn_id)?;
+        let order = commit_order(&txn.files)?;
+
+        // Keep what is being replaced so an interrupted commit can be undone
+        let mut entries = Vec::with_capacity(order.len());
+        for path in order {
+            let target = self.base_path.join(&path);
+            let existed = fs::metadata(&target).await.is_ok_and(|m| m.is_file());
+            if existed {
+                link_or_copy(&target, &backup_path(&txn.staging, &path)).await?;
+            }
+            entries.push(JournalEntry { path, existed });
+        }
+        let journal = Journal { txn_id: params.txn_id.clone(), entries };
+        let journal_path = txn.staging.join(JOURNAL);
+        let temp = txn.staging.join(format!("{}.tmp", JOURNAL));
+        fs::write(&temp, serde_json::to_vec_pretty(&journal)?).await?;
+        fs::rename(&temp, &journal_path).await?;
+        self.settle(&journal_path, None).await?;
+
+        // From here on the staging area is the recovery record
+        txn.discard = false;
+        for entry in &journal.entries {
+            let target = self.base_path.join(&entry.path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            if let Err(e) = fs::rename(staged_path(&txn.staging, &entry.path), &target).await {
+                return Err(Error::Io(std::io::Error::new(e.kind(), format!(
+                    "Commit of transaction {} stopped at {}: {}; run txn_recover to complete or roll it back",
+                    params.txn_id, entry.path.display(), e
+                ))));
+            }
+            self.settle(&target, None).await?;
+        }
+        txn.discard = true;
+        drop(txn);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "txn_id": params.txn_id,
+                "committed": journal.entries.iter().map(|e| &e.path).collect::<Vec<_>>(),
+                "journal": journal,
+            })),
+            error: None,
+        })
+    }
+
+    pub(super) async fn txn_rollback(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            txn_id: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let txn = self.take_transaction(&params.txn_id)?;
+        let discarded: Vec<PathBuf> = txn.files.iter().map(|f| f.path.clone()).collect();
+        drop(txn);
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "txn_id": params.txn_id,
+                "rolled_back": true,
+                "discarded": discarded,
+            })),
+            error: None,
+        })
+    }
+
+    // Finish commits interrupted by a crash, or undo them with `action:
+    // "rollback"`. Staging left by transactions that never reached commit is
+    // discarded. Meant to run at startup, before new transactions begin.
+    pub(super) async fn txn_recover(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default = "default_action")]
+            action: String,
+        }
+
+        fn default_action() -> String { "complete".to_string() }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let rollback = match params.action.as_str() {
+            "complete" => false,
+            "rollback" => true,
+            other => return Err(Error::InvalidConfig(format!(
+                "Unknown recovery action '{}'; use complete or rollback", other
+            ))),
+        };
+
+        let root = self.base_path.join(STAGING_ROOT);
+        let mut recovered = Vec::new();
+        let mut discarded = 0u64;
+        let mut dirs = match fs::read_dir(&root).await {
+            Ok(dirs) => dirs,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(ExecutionResult {
+                    success: true,
+                    output: Some(json!({ "recovered": recovered, "discarded": discarded })),
+                    error: None,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(dir) = dirs.next_entry().await? {
+            let staging = dir.path();
+            let txn_id = dir.file_name().to_string_lossy().into_owned();
+            if self.transactions.lock().unwrap().contains_key(&txn_id) {
+                continue;
+            }
+            let journal = match fs::read(staging.join(JOURNAL)).await {
+                Ok(bytes) => serde_json::from_slice::<Journal>(&bytes)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    fs::remove_dir_all(&staging).await?;
+                    discarded += 1;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut paths = Vec::new();
+            for entry in &journal.entries {
+                let staged = staged_path(&staging, &entry.path);
+                let target = self.base_path.join(&entry.path);
+                let moved = !fs::try_exists(&staged).await?;
+                match (rollback, moved) {
+                    (false, false) => {
+                        if let Some(parent) = target.parent() {
+                            fs::create_dir_all(parent).await?;
+                        }
+                        fs::rename(&staged, &target).await?;
+                    }
+                    (true, true) if entry.existed => {
+                        fs::rename(backup_path(&staging, &entry.path), &target).await?;
+                    }
+                    (true, true) => {
+                        fs::remove_file(&target).await?;
+                        self.settle_removed(&target).await?;
+                        paths.push(&entry.path);
+                        continue;
+                    }
+                    (_, _) => continue,
+                }
+                self.settle(&target, None).await?;
+                paths.push(&entry.path);
+            }
+            fs::remove_dir_all(&staging).await?;
+            recovered.push(json!({ "txn_id": journal.txn_id, "action": params.action, "paths": paths }));
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({ "recovered": recovered, "discarded": discarded })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,349 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::format::{FormatParams, ValueFormatter};
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+const DEFAULT_MAX_VIOLATIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ColumnType {
+    String,
+    Int,
+    Float,
+    Bool,
+    Date,
+}
+
+// One declared column. `format` is a strftime pattern for dates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ColumnSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ColumnType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+fn default_nullable() -> bool { true }
+
+// What happens to a row with a cell that does not fit its column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OnError {
+    Fail,
+    Skip,
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExtraColumns {
+    Error,
+    Ignore,
+    // Passed through as strings
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MissingColumns {
+    Error,
+    // Every row gets the column's default, or null
+    Null,
+}
+
+// A schema file holds the columns, either bare or as `{"columns": [...]}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SchemaFile {
+    Columns(Vec<ColumnSchema>),
+    Wrapped { columns: Vec<ColumnSchema> },
+}
+
+fn invalid_data(message: impl ToString) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string()))
+}
+
+struct Converter {
+    decimal_separator: char,
+    date_format: Option<String>,
+}
+
+impl Converter {
+    // Drop grouping characters and use '.' as the decimal point
+    fn normalize_number(&self, cell: &str) -> String {
+        let grouping = if self.decimal_separator == ',' { '.' } else { ',' };
+        cell.trim()
+            .chars()
+            .filter(|&c| c != grouping && c != ' ' && c != '\u{a0}' && c != '\u{202f}' && c != '\'')
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect()
+    }
+
+    fn convert(&self, column: &ColumnSchema, cell: &str) -> std::result::Result<Value, String> {
+        match column.kind {
+            ColumnType::String => Ok(Value::String(cell.to_string())),
+            ColumnType::Int => self.normalize_number(cell).parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| "not an integer".to_string()),
+            ColumnType::Float => self.normalize_number(cell).parse::<f64>().ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| "not a finite number".to_string()),
+            ColumnType::Bool => match cell.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+                "false" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+                _ => Err("not a boolean".to_string()),
+            },
+            ColumnType::Date => self.convert_date(column, cell.trim()),
+        }
+    }
+
+    // Dates come out as ISO 8601 whatever the input format
+    fn convert_date(&self, column: &ColumnSchema, cell: &str) -> std::result::Result<Value, String> {
+        let format = column.format.as_deref().or(self.date_format.as_deref());
+        let Some(format) = format else {
+            if let Ok(date) = NaiveDate::parse_from_str(cell, "%Y-%m-%d") {
+                return Ok(json!(date.format("%Y-%m-%d").to_string()));
+            }
+            return DateTime::parse_from_rfc3339(cell)
+                .map(|d| json!(d.to_rfc3339()))
+                .map_err(|_| "not an ISO 8601 date".to_string());
+        };
+        // Date-only parsing accepts and drops time fields, so try with time first
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(cell, format) {
+            return Ok(json!(datetime.format("%Y-%m-%dT%H:%M:%S").to_string()));
+        }
+        NaiveDate::parse_from_str(cell, format)
+            .map(|d| json!(d.format("%Y-%m-%d").to_string()))
+            .map_err(|_| format!("does not match date format {}", format))
+    }
+}
+
+struct TypedOptions {
+    schema: Vec<ColumnSchema>,
+    converter: Converter,
+    on_error: OnError,
+    extra_columns: ExtraColumns,
+    missing_columns: MissingColumns,
+    null_values: Vec<String>,
+    max_violations: usize,
+}
+
+#[derive(Default)]
+struct TypedOutcome {
+    rows: Vec<Value>,
+    rows_read: u64,
+    rows_written: u64,
+    rows_skipped: u64,
+    violations: Vec<Value>,
+    violation_count: u64,
+}
+
+fn parse_typed(path: PathBuf, dest: Option<PathBuf>, options: TypedOptions) -> Result<TypedOutcome> {
+    let mut reader = csv::Reader::from_path(&path).map_err(invalid_data)?;
+    let headers = reader.headers().map_err(invalid_data)?.clone();
+
+    let positions: Vec<Option<usize>> = options.schema.iter()
+        .map(|c| headers.iter().position(|h| h == c.name))
+        .collect();
+    let missing: Vec<&str> = options.schema.iter().zip(&positions)
+        .filter(|(_, p)| p.is_none())
+        .map(|(c, _)| c.name.as_str())
+        .collect();
+    if !missing.is_empty() && options.missing_columns == MissingColumns::Error {
+        return Err(invalid_data(format!("Missing declared columns: {}", missing.join(", "))));
+    }
+    let extra: Vec<(usize, String)> = headers.iter().enumerate()
+        .filter(|(_, h)| !options.schema.iter().any(|c| c.name == *h))
+        .map(|(i, h)| (i, h.to_string()))
+        .collect();
+    if !extra.is_empty() && options.extra_columns == ExtraColumns::Error {
+        let names: Vec<&str> = extra.iter().map(|(_, h)| h.as_str()).collect();
+        return Err(invalid_data(format!("Undeclared columns: {}", names.join(", "))));
+    }
+
+    let mut writer = match &dest {
+        Some(dest) => Some(std::io::BufWriter::new(std::fs::File::create(dest)?)),
+        None => None,
+    };
+    let mut outcome = TypedOutcome::default();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(invalid_data)?;
+        let row_number = index as u64 + 1;
+        outcome.rows_read += 1;
+
+        let mut object = Map::new();
+        let mut skip = false;
+        for (column, position) in options.schema.iter().zip(&positions) {
+            let cell = position.and_then(|i| record.get(i));
+            let converted = match cell {
+                Some(cell) if !options.null_values.iter().any(|n| n == cell) => {
+                    options.converter.convert(column, cell)
+                }
+                _ => match (&column.default, column.nullable) {
+                    (Some(default), _) => Ok(default.clone()),
+                    (None, true) => Ok(Value::Null),
+                    (None, false) => Err("null in a non-nullable column".to_string()),
+                },
+            };
+            let value = match converted {
+                Ok(value) => value,
+                Err(problem) => {
+                    let violation = json!({
+                        "row": row_number,
+                        "column": column.name,
+                        "value": cell,
+                        "error": problem,
+                    });
+                    if options.on_error == OnError::Fail {
+                        return Err(invalid_data(format!(
+                            "Row {} column '{}' ({:?}): {}", row_number, column.name, cell.unwrap_or_default(), problem
+                        )));
+                    }
+                    outcome.violation_count += 1;
+                    if outcome.violations.len() < options.max_violations {
+                        outcome.violations.push(violation);
+                    }
+                    if options.on_error == OnError::Skip {
+                        skip = true;
+                        break;
+                    }
+                    Value::Null
+                }
+            };
+            object.insert(column.name.clone(), value);
+        }
+        if skip {
+            outcome.rows_skipped += 1;
+            continue;
+        }
+        if options.extra_columns == ExtraColumns::Keep {
+            for (i, name) in &extra {
+                object.insert(name.clone(), json!(record.get(*i).unwrap_or_default()));
+            }
+        }
+
+        match &mut writer {
+            Some(writer) => {
+                serde_json::to_writer(&mut *writer, &object)?;
+                writer.write_all(b"\n")?;
+            }
+            None => outcome.rows.push(Value::Object(object)),
+        }
+        outcome.rows_written += 1;
+    }
+    if let Some(mut writer) = writer {
+        writer.flush()?;
+    }
+    Ok(outcome)
+}
+
+impl FileExecutor {
+    // read_csv with declared column types: cells are validated and converted
+    // while streaming, and rows that do not fit are handled per `on_error`
+    pub(super) async fn read_csv_typed(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            schema: Option<Vec<ColumnSchema>>,
+            // JSON file with the columns, e.g. profile's `schema` pinned to disk
+            schema_path: Option<String>,
+            #[serde(default = "default_on_error")]
+            on_error: OnError,
+            #[serde(default = "default_extra_columns")]
+            extra_columns: ExtraColumns,
+            #[serde(default = "default_missing_columns")]
+            missing_columns: MissingColumns,
+            // Cells read as null before conversion
+            #[serde(default = "default_null_values")]
+            null_values: Vec<String>,
+            // Write rows as NDJSON here instead of returning them
+            dest: Option<String>,
+            #[serde(default = "default_max_violations")]
+            max_violations: usize,
+            #[serde(flatten)]
+            format: FormatParams,
+        }
+
+        fn default_on_error() -> OnError { OnError::Fail }
+        fn default_extra_columns() -> ExtraColumns { ExtraColumns::Error }
+        fn default_missing_columns() -> MissingColumns { MissingColumns::Error }
+        fn default_null_values() -> Vec<String> { vec![String::new()] }
+        fn default_max_violations() -> usize { DEFAULT_MAX_VIOLATIONS }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let schema = match (params.schema, &params.schema_path) {
+            (Some(schema), None) => schema,
+            (None, Some(schema_path)) => {
+                let content = tokio::fs::read(self.resolve_path(schema_path)?).await?;
+                match serde_json::from_slice(&content).map_err(|e| Error::InvalidConfig(format!(
+                    "Invalid schema in {}: {}", schema_path, e
+                )))? {
+                    SchemaFile::Columns(columns) | SchemaFile::Wrapped { columns } => columns,
+                }
+            }
+            _ => return Err(Error::InvalidConfig(
+                "Provide exactly one of 'schema' or 'schema_path'".to_string()
+            )),
+        };
+        if schema.is_empty() {
+            return Err(Error::InvalidConfig("schema must declare at least one column".to_string()));
+        }
+        let formatter = ValueFormatter::from_params(&params.format)?;
+        let options = TypedOptions {
+            schema,
+            converter: Converter {
+                decimal_separator: formatter.decimal_separator(),
+                date_format: formatter.date_format().map(str::to_string),
+            },
+            on_error: params.on_error,
+            extra_columns: params.extra_columns,
+            missing_columns: params.missing_columns,
+            null_values: params.null_values,
+            max_violations: params.max_violations,
+        };
+
+        let full_path = self.resolve_path(&params.path)?;
+        let dest = params.dest.as_deref().map(|d| self.resolve_path(d)).transpose()?;
+        let dest_path = dest.clone();
+        let outcome = tokio::task::spawn_blocking(move || parse_typed(full_path, dest_path, options))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        if let Some(dest) = &dest {
+            self.settle(dest, None).await?;
+        }
+
+        let mut output = json!({
+            "path": params.path,
+            "rows_read": outcome.rows_read,
+            "rows_written": outcome.rows_written,
+            "rows_skipped": outcome.rows_skipped,
+            "violation_count": outcome.violation_count,
+            "violations": outcome.violations,
+        });
+        match params.dest {
+            Some(dest) => output["dest"] = json!(dest),
+            None => output["rows"] = Value::Array(outcome.rows),
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
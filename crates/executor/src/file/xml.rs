@@ -0,0 +1,233 @@
+use local_automation_common::{Error, Result, Task};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+#[derive(Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+fn malformed(path: &str, position: u64, message: impl std::fmt::Display) -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Malformed XML in {} at byte {}: {}", path, position, message),
+    ))
+}
+
+fn open_element(start: &BytesStart, path: &str, position: u64) -> Result<Element> {
+    let mut element = Element {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| malformed(path, position, e))?;
+        let value = attribute.unescape_value().map_err(|e| malformed(path, position, e))?;
+        element.attributes.push((String::from_utf8_lossy(attribute.key.as_ref()).into_owned(), value.into_owned()));
+    }
+    Ok(element)
+}
+
+fn parse(content: &str, path: &str) -> Result<Element> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let event = reader.read_event().map_err(|e| malformed(path, reader.error_position(), e))?;
+        let position = reader.buffer_position();
+        let finished = match event {
+            Event::Start(start) => {
+                stack.push(open_element(&start, path, position)?);
+                None
+            }
+            Event::Empty(start) => Some(open_element(&start, path, position)?),
+            Event::End(_) => stack.pop(),
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| malformed(path, position, e))?;
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&text);
+                }
+                None
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&data));
+                }
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+        if let Some(element) = finished {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None if root.is_none() => root = Some(element),
+                None => return Err(malformed(path, position, "more than one root element")),
+            }
+        }
+    }
+    if let Some(open) = stack.last() {
+        return Err(malformed(path, reader.buffer_position(), format!("unclosed element <{}>", open.name)));
+    }
+    root.ok_or_else(|| malformed(path, 0, "no root element"))
+}
+
+// A childless element without attributes is just its text; anything else is
+// an object with attributes under `attrs_key`, text under `text_key` and one
+// key per child name (an array when the name repeats)
+fn to_json(element: &Element, attrs_key: &str, text_key: &str) -> Value {
+    if element.attributes.is_empty() && element.children.is_empty() {
+        return match element.text.is_empty() {
+            true => Value::Null,
+            false => Value::String(element.text.clone()),
+        };
+    }
+    let mut object = Map::new();
+    if !element.attributes.is_empty() {
+        let attributes: Map<String, Value> = element.attributes.iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        object.insert(attrs_key.to_string(), Value::Object(attributes));
+    }
+    if !element.text.is_empty() {
+        object.insert(text_key.to_string(), Value::String(element.text.clone()));
+    }
+    for child in &element.children {
+        let value = to_json(child, attrs_key, text_key);
+        match object.get_mut(&child.name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                object.insert(child.name.clone(), value);
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+struct Step {
+    name: String,
+    // 1-based among the siblings that match `name`
+    index: Option<usize>,
+    descendant: bool,
+}
+
+// xpath-lite: `/a/b`, `//b`, `a/*/c`, `a/b[2]`
+fn parse_select(select: &str) -> Result<Vec<Step>> {
+    let invalid = || Error::InvalidConfig(format!("Unsupported select expression: {}", select));
+    let mut steps = Vec::new();
+    let mut descendant = false;
+    for (i, segment) in select.split('/').enumerate() {
+        // The empty segment before a leading `/`, or between the two slashes of `//`
+        if segment.is_empty() {
+            if i > 0 {
+                descendant = true;
+            }
+            continue;
+        }
+        let (name, index) = match segment.split_once('[') {
+            Some((name, index)) => {
+                let index = index.strip_suffix(']')
+                    .and_then(|i| i.parse::<usize>().ok())
+                    .filter(|i| *i > 0)
+                    .ok_or_else(invalid)?;
+                (name, Some(index))
+            }
+            None => (segment, None),
+        };
+        if name.is_empty() || name.contains(['[', ']', '@']) {
+            return Err(invalid());
+        }
+        steps.push(Step { name: name.to_string(), index, descendant });
+        descendant = false;
+    }
+    if steps.is_empty() || descendant {
+        return Err(invalid());
+    }
+    Ok(steps)
+}
+
+fn descendants<'a>(element: &'a Element, found: &mut Vec<&'a Element>) {
+    for child in &element.children {
+        found.push(child);
+        descendants(child, found);
+    }
+}
+
+// Paths start at the document, so the first step names (or searches for) the root
+fn select<'a>(root: &'a Element, steps: &[Step]) -> Vec<&'a Element> {
+    let mut current: Vec<&Element> = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        let candidates: Vec<Vec<&Element>> = match (i, step.descendant) {
+            (0, false) => vec![vec![root]],
+            (0, true) => {
+                let mut found = vec![root];
+                descendants(root, &mut found);
+                vec![found]
+            }
+            (_, false) => current.iter().map(|e| e.children.iter().collect()).collect(),
+            (_, true) => current.iter().map(|e| {
+                let mut found = Vec::new();
+                descendants(e, &mut found);
+                found
+            }).collect(),
+        };
+        current = candidates.into_iter().flat_map(|group| {
+            let matching = group.into_iter().filter(|e| step.name == "*" || e.name == step.name);
+            match step.index {
+                Some(n) => matching.skip(n - 1).take(1).collect::<Vec<_>>(),
+                None => matching.collect(),
+            }
+        }).collect();
+    }
+    current
+}
+
+impl FileExecutor {
+    pub(super) async fn read_xml(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            #[serde(default = "default_attrs_key")]
+            attrs_key: String,
+            #[serde(default = "default_text_key")]
+            text_key: String,
+            select: Option<String>,
+        }
+
+        fn default_attrs_key() -> String { "@attrs".to_string() }
+        fn default_text_key() -> String { "#text".to_string() }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let steps = params.select.as_deref().map(parse_select).transpose()?;
+        let path = self.resolve_path(&params.path)?;
+        let content = fs::read_to_string(&path).await?;
+        let root = parse(&content, &params.path)?;
+
+        let output = match &steps {
+            Some(steps) => Value::Array(
+                select(&root, steps).into_iter()
+                    .map(|e| to_json(e, &params.attrs_key, &params.text_key))
+                    .collect(),
+            ),
+            None => json!({ root.name.clone(): to_json(&root, &params.attrs_key, &params.text_key) }),
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+}
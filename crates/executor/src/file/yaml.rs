@@ -0,0 +1,120 @@
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::fs;
+
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+fn parse_error(path: &str, e: serde_yaml::Error) -> Error {
+    match e.location() {
+        Some(at) => invalid_data(format!(
+            "Invalid YAML in {} at line {}, column {}: {}", path, at.line(), at.column(), e
+        )),
+        None => invalid_data(format!("Invalid YAML in {}: {}", path, e)),
+    }
+}
+
+// JSON has no non-string keys, tags or non-finite numbers; scalar keys are
+// stringified and tags dropped
+fn to_json(value: serde_yaml::Value) -> Result<Value> {
+    Ok(match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => i.into(),
+            (_, Some(u), _) => u.into(),
+            (_, _, Some(f)) => serde_json::Number::from_f64(f)
+                .map(Value::Number)
+                .ok_or_else(|| invalid_data(format!("{} cannot be represented in JSON", n)))?,
+            _ => return Err(invalid_data(format!("Unsupported YAML number {}", n))),
+        },
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(items.into_iter().map(to_json).collect::<Result<_>>()?)
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (key, value) in mapping {
+                let key = match key {
+                    serde_yaml::Value::String(s) => s,
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    serde_yaml::Value::Null => "null".to_string(),
+                    other => return Err(invalid_data(format!(
+                        "Mapping key {:?} cannot be represented in JSON", other
+                    ))),
+                };
+                map.insert(key, to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => to_json(tagged.value)?,
+    })
+}
+
+impl FileExecutor {
+    pub(super) async fn read_yaml(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            // Return every document as an array; otherwise the file must hold one
+            #[serde(default)]
+            multi_doc: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let content = fs::read_to_string(&full_path).await?;
+        let mut documents = Vec::new();
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let mut value = serde_yaml::Value::deserialize(document).map_err(|e| parse_error(&params.path, e))?;
+            // `<<: *anchor` merge keys
+            value.apply_merge().map_err(|e| parse_error(&params.path, e))?;
+            documents.push(to_json(value)?);
+        }
+
+        let output = match (params.multi_doc, documents.len()) {
+            (true, _) => Value::Array(documents),
+            (false, 0) => Value::Null,
+            (false, 1) => documents.remove(0),
+            (false, n) => return Err(invalid_data(format!(
+                "{} holds {} YAML documents; pass multi_doc: true to read them all", params.path, n
+            ))),
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    pub(super) async fn write_yaml(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            data: Value,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let full_path = self.resolve_path(&params.path)?;
+        let yaml = serde_yaml::to_string(&params.data).map_err(|e| invalid_data(e.to_string()))?;
+        fs::write(&full_path, yaml.as_bytes()).await?;
+        self.settle(&full_path, Some(yaml.len() as u64)).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "path": full_path })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,234 @@
+use ::zip::write::SimpleFileOptions;
+use ::zip::{CompressionMethod, ZipArchive, ZipWriter};
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::json;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::glob::relative_string;
+use super::internal::InternalPaths;
+use super::FileExecutor;
+use crate::traits::ExecutionResult;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum UnsafeEntries {
+    // Refuse the whole archive before extracting anything
+    #[default]
+    Fail,
+    // Extract everything else and report the unsafe names
+    Skip,
+}
+
+struct Totals {
+    entries: Vec<String>,
+    skipped: Vec<String>,
+    compressed_bytes: u64,
+    uncompressed_bytes: u64,
+}
+
+fn zip_error(e: ::zip::result::ZipError) -> Error {
+    match e {
+        ::zip::result::ZipError::Io(e) => Error::Io(e),
+        other => Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, other.to_string())),
+    }
+}
+
+// Entry names are paths relative to base_path, '/'-separated
+fn create(base: &Path, sources: &[PathBuf], dest: &Path, internal: &InternalPaths) -> Result<Vec<String>> {
+    let mut writer = ZipWriter::new(BufWriter::new(File::create(dest)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut entries = Vec::new();
+    for source in sources {
+        let walker = WalkDir::new(source).follow_links(false).sort_by_file_name();
+        for entry in walker.into_iter().filter_entry(|e| !internal.contains(e.path())) {
+            let entry = entry.map_err(|e| Error::Io(e.into()))?;
+            if entry.path() == dest {
+                continue;
+            }
+            let Some(name) = relative_string(base, entry.path()) else {
+                continue;
+            };
+            let metadata = entry.metadata().map_err(|e| Error::Io(e.into()))?;
+            #[cfg(unix)]
+            let options = {
+                use std::os::unix::fs::PermissionsExt;
+                options.unix_permissions(metadata.permissions().mode() & 0o777)
+            };
+            if entry.file_type().is_dir() {
+                writer.add_directory(format!("{}/", name), options).map_err(zip_error)?;
+            } else if entry.file_type().is_file() {
+                writer.start_file(name.as_str(), options).map_err(zip_error)?;
+                std::io::copy(&mut File::open(entry.path())?, &mut writer)?;
+                entries.push(name);
+            }
+        }
+    }
+    writer.finish().map_err(zip_error)?.into_inner().map_err(|e| Error::Io(e.into_error()))?.sync_all()?;
+    Ok(entries)
+}
+
+fn sizes(path: &Path) -> Result<(u64, u64)> {
+    let mut archive = ZipArchive::new(File::open(path)?).map_err(zip_error)?;
+    let (mut compressed, mut uncompressed) = (0, 0);
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i).map_err(zip_error)?;
+        compressed += file.compressed_size();
+        uncompressed += file.size();
+    }
+    Ok((compressed, uncompressed))
+}
+
+// Only plain relative names stay inside dest_dir: no `..`, roots or drive prefixes
+pub(super) fn safe_name(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let safe = !name.contains('\\') && path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    safe.then(|| path.to_path_buf())
+}
+
+// A symlink already under dest_dir would carry the write somewhere else
+fn through_symlink(dest_dir: &Path, relative: &Path) -> bool {
+    relative.ancestors()
+        .skip(1)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .any(|dir| dest_dir.join(dir).symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()))
+}
+
+fn extract(archive_path: &Path, dest_dir: &Path, on_unsafe: UnsafeEntries, overwrite: bool) -> Result<Totals> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+
+    // Check every name (and conflict) before writing anything
+    let mut plan = Vec::new();
+    let mut totals = Totals { entries: Vec::new(), skipped: Vec::new(), compressed_bytes: 0, uncompressed_bytes: 0 };
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i).map_err(zip_error)?;
+        let name = file.name().to_string();
+        match safe_name(&name).filter(|relative| !through_symlink(dest_dir, relative)) {
+            Some(relative) => {
+                let target = dest_dir.join(&relative);
+                if !overwrite && !file.is_dir() && target.symlink_metadata().is_ok() {
+                    return Err(Error::InvalidConfig(format!(
+                        "{} already exists; pass overwrite: true to replace it", name
+                    )));
+                }
+                plan.push((i, target));
+            }
+            None => match on_unsafe {
+                UnsafeEntries::Fail => return Err(Error::PermissionDenied(format!(
+                    "Archive entry '{}' would extract outside dest_dir", name
+                ))),
+                UnsafeEntries::Skip => totals.skipped.push(name),
+            },
+        }
+    }
+
+    std::fs::create_dir_all(dest_dir)?;
+    for (i, target) in plan {
+        let mut file = archive.by_index(i).map_err(zip_error)?;
+        if file.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Replace a symlink rather than writing through it
+        if target.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+            std::fs::remove_file(&target)?;
+        }
+        let mut out = File::create(&target)?;
+        std::io::copy(&mut file, &mut out)?;
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&target, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+        totals.compressed_bytes += file.compressed_size();
+        totals.uncompressed_bytes += file.size();
+        totals.entries.push(file.name().to_string());
+    }
+    Ok(totals)
+}
+
+impl FileExecutor {
+    pub(super) async fn zip(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            sources: Vec<String>,
+            dest: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        if params.sources.is_empty() {
+            return Err(Error::InvalidConfig("sources must not be empty".to_string()));
+        }
+        let sources = params.sources.iter().map(|s| self.resolve_path(s)).collect::<Result<Vec<_>>>()?;
+        let dest = self.resolve_path(&params.dest)?;
+        let (base, internal) = (self.base_path.clone(), self.internal_paths());
+        let path = dest.clone();
+        let (entries, (compressed, uncompressed)) = tokio::task::spawn_blocking(move || -> Result<_> {
+            for source in &sources {
+                std::fs::symlink_metadata(source)?;
+            }
+            let entries = create(&base, &sources, &path, &internal)?;
+            Ok((entries, sizes(&path)?))
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&dest, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "dest": params.dest,
+                "entries": entries,
+                "compressed_bytes": compressed,
+                "uncompressed_bytes": uncompressed,
+            })),
+            error: None,
+        })
+    }
+
+    // Entries with `..`, absolute or drive-prefixed names never leave dest_dir:
+    // they fail the extraction, or with `on_unsafe: skip` are listed and left out
+    pub(super) async fn unzip(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+            dest_dir: String,
+            #[serde(default)]
+            on_unsafe: UnsafeEntries,
+            #[serde(default)]
+            overwrite: bool,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let archive = self.resolve_path(&params.path)?;
+        let dest_dir = self.resolve_path(&params.dest_dir)?;
+        let (on_unsafe, overwrite, target) = (params.on_unsafe, params.overwrite, dest_dir.clone());
+        let totals = tokio::task::spawn_blocking(move || extract(&archive, &target, on_unsafe, overwrite))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        self.settle(&dest_dir, None).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(json!({
+                "path": params.path,
+                "dest_dir": params.dest_dir,
+                "entries": totals.entries,
+                "skipped": totals.skipped,
+                "compressed_bytes": totals.compressed_bytes,
+                "uncompressed_bytes": totals.uncompressed_bytes,
+            })),
+            error: None,
+        })
+    }
+}
@@ -1,6 +1,15 @@
+pub mod cache;
+pub mod codec;
+pub mod deprecation;
 pub mod file;
+pub mod sanitize;
+pub mod sequence;
+pub mod state;
 pub mod traits; 
 
+pub use cache::CacheExecutor;
 pub use file::FileExecutor; 
+pub use sequence::SequenceExecutor;
+pub use state::{StateExecutor, StateStore};
 pub use traits::{Executor, ExecutionResult};
 
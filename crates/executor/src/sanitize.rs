@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Unix,
+    Windows,
+    // Strictest combination, safe to copy anywhere
+    #[default]
+    All,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SanitizeOptions {
+    pub replacement: String,
+    // In bytes, like filesystem limits
+    pub max_length: usize,
+    pub allow_unicode: bool,
+    pub platform: Platform,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            replacement: "_".to_string(),
+            max_length: 255,
+            allow_unicode: true,
+            platform: Platform::All,
+        }
+    }
+}
+
+const WINDOWS_INVALID: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+// Hash suffix appended when a name has to be shortened: '-' plus 8 hex chars
+const HASH_SUFFIX_LEN: usize = 9;
+
+impl SanitizeOptions {
+    fn windows_rules(&self) -> bool {
+        self.platform != Platform::Unix
+    }
+
+    fn is_forbidden(&self, c: char) -> bool {
+        c.is_control()
+            || c == '/'
+            || c == '\\'
+            || (self.windows_rules() && WINDOWS_INVALID.contains(&c))
+            || (!self.allow_unicode && !c.is_ascii())
+    }
+
+    // The replacement must not itself reintroduce something we strip
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.replacement.chars().any(|c| self.is_forbidden(c)) {
+            return Err(format!("replacement {:?} contains forbidden characters", self.replacement));
+        }
+        if self.max_length < HASH_SUFFIX_LEN + 1 {
+            return Err(format!("max_length must be at least {}", HASH_SUFFIX_LEN + 1));
+        }
+        Ok(())
+    }
+
+    fn fallback(&self) -> &str {
+        if self.replacement.is_empty() { "_" } else { &self.replacement }
+    }
+}
+
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(i) if i > 0 && name.len() - i <= 16 => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    }
+}
+
+fn truncate_bytes(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+pub fn sanitize_filename(name: &str, options: &SanitizeOptions) -> String {
+    let normalized: String = name.nfc().collect();
+
+    let mut out = String::with_capacity(normalized.len());
+    for c in normalized.chars() {
+        if options.is_forbidden(c) {
+            out.push_str(&options.replacement);
+        } else {
+            out.push(c);
+        }
+    }
+
+    if options.windows_rules() {
+        // Windows silently drops trailing dots and spaces
+        let trimmed = out.trim_end_matches(['.', ' ']).len();
+        out.truncate(trimmed);
+    }
+    if out.is_empty() || out == "." || out == ".." {
+        out = options.fallback().to_string();
+    }
+
+    if options.windows_rules() {
+        let stem = out.split('.').next().unwrap_or("").trim_end();
+        if WINDOWS_RESERVED.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+            out = format!("{}{}", options.fallback(), out);
+        }
+    }
+
+    if out.len() > options.max_length {
+        // Keep the extension and make the shortened name unique via the original's hash
+        let digest = hex::encode(&Sha256::digest(name.as_bytes())[..4]);
+        let (stem, ext) = split_extension(&out);
+        let ext = if ext.len() + HASH_SUFFIX_LEN < options.max_length { ext } else { "" };
+        let keep = options.max_length.saturating_sub(ext.len() + HASH_SUFFIX_LEN);
+        out = format!("{}-{}{}", truncate_bytes(stem, keep), digest, ext);
+    }
+    out
+}
+
+// Sanitize a batch, disambiguating names that collide after sanitization by adding
+// `-2`, `-3`, ... before the extension, in input order so results are deterministic.
+// Comparison is case-insensitive unless targeting Unix only.
+pub fn sanitize_filenames(names: &[String], options: &SanitizeOptions) -> Vec<(String, bool)> {
+    let key = |s: &str| if options.windows_rules() { s.to_lowercase() } else { s.to_string() };
+    let mut seen = HashSet::new();
+    let mut results = Vec::with_capacity(names.len());
+
+    for name in names {
+        let sanitized = sanitize_filename(name, options);
+        if seen.insert(key(&sanitized)) {
+            results.push((sanitized, false));
+            continue;
+        }
+        let (stem, ext) = split_extension(&sanitized);
+        let mut n = 2;
+        let unique = loop {
+            let suffix = format!("-{}{}", n, ext);
+            let candidate = format!(
+                "{}{}",
+                truncate_bytes(stem, options.max_length.saturating_sub(suffix.len())),
+                suffix
+            );
+            if seen.insert(key(&candidate)) {
+                break candidate;
+            }
+            n += 1;
+        };
+        results.push((unique, true));
+    }
+    results
+}
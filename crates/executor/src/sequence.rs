@@ -0,0 +1,306 @@
+use async_trait::async_trait;
+use local_automation_common::{Error, Result, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::deprecation::{attach_warnings, resolve, DeprecatedParam};
+use crate::state::StateStore;
+use crate::traits::{Executor, ExecutionResult};
+
+// Persisted under `sequence:<name>` in the StateStore. Values are consumed when
+// handed out: a task that takes a number and then fails leaves a gap, and the
+// number is never issued again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sequence {
+    next: i64,
+    step: i64,
+    format: Option<String>,
+}
+
+fn state_key(name: &str) -> String {
+    format!("sequence:{}", name)
+}
+
+// Render `value` into a template like `INV-{:06}`: `{}` is the plain number,
+// `{:N}` pads to width N with spaces and `{:0N}` with zeros.
+pub fn format_sequence(template: &str, value: i64) -> Result<String> {
+    let invalid = || Error::InvalidConfig(format!("Invalid sequence format: {}", template));
+    let start = template.find('{').ok_or_else(invalid)?;
+    let end = start + template[start..].find('}').ok_or_else(invalid)?;
+    let spec = &template[start + 1..end];
+
+    let rendered = match spec.strip_prefix(':') {
+        None if spec.is_empty() => value.to_string(),
+        None => return Err(invalid()),
+        Some(width) => {
+            let zero = width.starts_with('0');
+            let width: usize = width.parse().map_err(|_| invalid())?;
+            if zero {
+                format!("{:0width$}", value, width = width)
+            } else {
+                format!("{:width$}", value, width = width)
+            }
+        }
+    };
+    Ok(format!("{}{}{}", &template[..start], rendered, &template[end + 1..]))
+}
+
+pub struct SequenceExecutor {
+    store: Arc<StateStore>,
+    strict_deprecations: bool,
+}
+
+// next_batch shipped taking `n`; it is `count` now, like take()
+const DEPRECATED_PARAMS: &[DeprecatedParam] = &[
+    DeprecatedParam { operation: "next_batch", param: "n", since: "0.2.0", replacement: Some("count") },
+];
+
+impl SequenceExecutor {
+    pub fn new(store: Arc<StateStore>) -> Self {
+        Self { store, strict_deprecations: false }
+    }
+
+    // Fail tasks that use deprecated params instead of warning
+    pub fn with_strict_deprecations(mut self, strict: bool) -> Self {
+        self.strict_deprecations = strict;
+        self
+    }
+
+    // Atomically hand out `count` values, creating the sequence from the given
+    // defaults on first use.
+    pub async fn take(
+        &self,
+        name: &str,
+        count: usize,
+        defaults: SequenceDefaults,
+    ) -> Result<(Vec<i64>, Option<String>)> {
+        if count == 0 {
+            return Err(Error::InvalidConfig("count must be at least 1".to_string()));
+        }
+        self.store.transact(&state_key(name), move |current| {
+            let mut sequence = match current {
+                Some(value) => serde_json::from_value::<Sequence>(value.clone())?,
+                None => defaults.into_sequence()?,
+            };
+            let mut values = Vec::with_capacity(count);
+            for _ in 0..count {
+                values.push(sequence.next);
+                sequence.next = sequence.next.checked_add(sequence.step).ok_or_else(|| {
+                    Error::InvalidConfig("Sequence overflowed".to_string())
+                })?;
+            }
+            let format = sequence.format.clone();
+            Ok((Some(serde_json::to_value(sequence)?), (values, format)))
+        })
+        .await
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SequenceDefaults {
+    #[serde(default = "default_start")]
+    pub start: i64,
+    #[serde(default = "default_step")]
+    pub step: i64,
+    pub format: Option<String>,
+}
+
+fn default_start() -> i64 { 1 }
+fn default_step() -> i64 { 1 }
+
+impl SequenceDefaults {
+    fn into_sequence(self) -> Result<Sequence> {
+        if self.step == 0 {
+            return Err(Error::InvalidConfig("step must not be 0".to_string()));
+        }
+        if let Some(format) = &self.format {
+            format_sequence(format, self.start)?;
+        }
+        Ok(Sequence { next: self.start, step: self.step, format: self.format })
+    }
+}
+
+fn render(values: &[i64], format: Option<&str>) -> Result<Vec<String>> {
+    values.iter()
+        .map(|&v| match format {
+            Some(format) => format_sequence(format, v),
+            None => Ok(v.to_string()),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Executor for SequenceExecutor {
+    fn name(&self) -> &str {
+        "sequence"
+    }
+
+    fn validate(&self, task: &Task) -> Result<()> {
+        if task.executor != self.name() {
+            return Err(Error::InvalidConfig(
+                format!("Wrong executor: expected 'sequence', got '{}'", task.executor)
+            ));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, task: &Task) -> Result<ExecutionResult> {
+        self.validate(task)?;
+        let (task, warnings) = resolve(task, &[], DEPRECATED_PARAMS, self.strict_deprecations)?;
+        let task = task.as_ref();
+
+        let result = match task.operation.as_str() {
+            "next" => self.next(task).await,
+            "next_batch" => self.next_batch(task).await,
+            "peek" => self.peek(task).await,
+            "set" => self.set(task).await,
+            _ => Err(Error::InvalidConfig(
+                format!("Unknown operation: {}", task.operation)
+            )),
+        };
+        attach_warnings(result?, warnings)
+    }
+}
+
+impl SequenceExecutor {
+    async fn next(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+            #[serde(flatten)]
+            defaults: SequenceDefaults,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let (values, format) = self.take(&params.name, 1, params.defaults).await?;
+        let formatted = render(&values, format.as_deref())?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "name": params.name,
+                "value": values[0],
+                "formatted": formatted[0],
+            })),
+            error: None,
+        })
+    }
+
+    async fn next_batch(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+            count: usize,
+            #[serde(flatten)]
+            defaults: SequenceDefaults,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let (values, format) = self.take(&params.name, params.count, params.defaults).await?;
+        let formatted = render(&values, format.as_deref())?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "name": params.name,
+                "values": values,
+                "formatted": formatted,
+            })),
+            error: None,
+        })
+    }
+
+    async fn peek(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let sequence = match self.store.get(&state_key(&params.name)).await? {
+            Some(value) => Some(serde_json::from_value::<Sequence>(value)?),
+            None => None,
+        };
+        let output = match sequence {
+            Some(sequence) => serde_json::json!({
+                "name": params.name,
+                "exists": true,
+                "next": sequence.next,
+                "step": sequence.step,
+                "format": sequence.format,
+                "formatted": render(&[sequence.next], sequence.format.as_deref())?[0],
+            }),
+            None => serde_json::json!({ "name": params.name, "exists": false }),
+        };
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        })
+    }
+
+    // Administrative: move the counter or change step/format. Moving it backwards
+    // can reissue values, so it is never done implicitly.
+    async fn set(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+            next: Option<i64>,
+            step: Option<i64>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        // `format: null` clears the format, a missing key keeps it
+        let format = match task.params.get("format") {
+            None => None,
+            Some(Value::Null) => Some(None),
+            Some(Value::String(s)) => Some(Some(s.clone())),
+            Some(_) => return Err(Error::InvalidConfig("format must be a string or null".to_string())),
+        };
+        if params.step == Some(0) {
+            return Err(Error::InvalidConfig("step must not be 0".to_string()));
+        }
+        let (next, step) = (params.next, params.step);
+
+        let (previous, current) = self.store.transact(&state_key(&params.name), move |current| {
+            let previous = current.map(|v| serde_json::from_value::<Sequence>(v.clone())).transpose()?;
+            let mut sequence = previous.clone().unwrap_or(Sequence { next: 1, step: 1, format: None });
+            if let Some(next) = next {
+                sequence.next = next;
+            }
+            if let Some(step) = step {
+                sequence.step = step;
+            }
+            if let Some(format) = format {
+                if let Some(format) = &format {
+                    format_sequence(format, sequence.next)?;
+                }
+                sequence.format = format;
+            }
+            Ok((Some(serde_json::to_value(&sequence)?), (previous, sequence)))
+        })
+        .await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "name": params.name,
+                "previous": previous,
+                "next": current.next,
+                "step": current.step,
+                "format": current.format,
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,273 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use local_automation_common::{Error, Result, Task};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::traits::{Executor, ExecutionResult};
+
+// Small durable key/value store kept in a single JSON file. Every change runs as a
+// read-modify-write transaction under an exclusive OS lock on `<path>.lock` and is
+// published with an atomic rename, so concurrent processes never lose updates.
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<Value>> {
+        let path = self.path.clone();
+        let key = key.to_string();
+        blocking(move || Ok(load(&path)?.remove(&key))).await
+    }
+
+    pub async fn set(&self, key: &str, value: Value) -> Result<()> {
+        self.update(key, move |_| Ok(Some(value))).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<bool> {
+        let previous = self.update(key, |_| Ok(None)).await?;
+        Ok(previous.is_some())
+    }
+
+    // Atomically replace the value under `key` with whatever `f` returns (None
+    // removes it). Returns the previous value.
+    pub async fn update<F>(&self, key: &str, f: F) -> Result<Option<Value>>
+    where
+        F: FnOnce(Option<&Value>) -> Result<Option<Value>> + Send + 'static,
+    {
+        self.transact(key, |current| {
+            let previous = current.cloned();
+            Ok((f(current)?, previous))
+        })
+        .await
+    }
+
+    // Like `update`, but `f` also produces an arbitrary result computed inside the
+    // transaction (e.g. the values handed out by a sequence).
+    pub async fn transact<F, T>(&self, key: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(Option<&Value>) -> Result<(Option<Value>, T)> + Send + 'static,
+        T: Send + 'static,
+    {
+        let path = self.path.clone();
+        let key = key.to_string();
+        blocking(move || {
+            let _guard = lock(&path)?;
+            let mut entries = load(&path)?;
+            let (next, result) = f(entries.get(&key))?;
+            match next {
+                Some(value) => {
+                    entries.insert(key, value);
+                }
+                None => {
+                    entries.remove(&key);
+                }
+            }
+            save(&path, &entries)?;
+            Ok(result)
+        })
+        .await
+    }
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+}
+
+fn lock(path: &Path) -> Result<std::fs::File> {
+    let mut lock_path = path.as_os_str().to_os_string();
+    lock_path.push(".lock");
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path)?;
+    file.lock()?;
+    Ok(file)
+}
+
+fn load(path: &Path) -> Result<Map<String, Value>> {
+    match std::fs::read(path) {
+        Ok(content) if content.is_empty() => Ok(Map::new()),
+        Ok(content) => Ok(serde_json::from_slice(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Map::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save(path: &Path, entries: &Map<String, Value>) -> Result<()> {
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+    let mut tmp = std::fs::File::create(&tmp_path)?;
+    tmp.write_all(&serde_json::to_vec_pretty(entries)?)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// Ordering used for high-water marks: numbers numerically, RFC3339 timestamps
+// chronologically, other strings lexicographically.
+pub(crate) fn compare_marks(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => {
+            match (DateTime::parse_from_rfc3339(a), DateTime::parse_from_rfc3339(b)) {
+                (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+                _ => Some(a.cmp(b)),
+            }
+        }
+        _ => None,
+    }
+}
+
+pub struct StateExecutor {
+    store: Arc<StateStore>,
+}
+
+impl StateExecutor {
+    pub fn new(store: Arc<StateStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Executor for StateExecutor {
+    fn name(&self) -> &str {
+        "state"
+    }
+
+    fn validate(&self, task: &Task) -> Result<()> {
+        if task.executor != self.name() {
+            return Err(Error::InvalidConfig(
+                format!("Wrong executor: expected 'state', got '{}'", task.executor)
+            ));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, task: &Task) -> Result<ExecutionResult> {
+        self.validate(task)?;
+
+        match task.operation.as_str() {
+            "get_state" => self.get_state(task).await,
+            "set_state" => self.set_state(task).await,
+            "delete_state" => self.delete_state(task).await,
+            _ => Err(Error::InvalidConfig(
+                format!("Unknown operation: {}", task.operation)
+            )),
+        }
+    }
+}
+
+impl StateExecutor {
+    async fn get_state(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+            default: Option<Value>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let value = self.store.get(&params.key).await?;
+        let found = value.is_some();
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "key": params.key,
+                "found": found,
+                "value": value.or(params.default),
+            })),
+            error: None,
+        })
+    }
+
+    async fn set_state(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+            value: Value,
+            // Only move a high-water mark forward, never back
+            #[serde(default)]
+            only_if_greater: bool,
+            // Compare-and-swap: only write when the current value equals this
+            expect: Option<Value>,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let value = params.value;
+        let only_if_greater = params.only_if_greater;
+        let expect = params.expect;
+        let (previous, current) = self.store.transact(&params.key, move |current| {
+            let previous = current.cloned();
+            let unchanged = Ok((previous.clone(), (previous.clone(), previous.clone())));
+
+            if let Some(expected) = &expect {
+                if current != Some(expected) {
+                    return unchanged;
+                }
+            }
+            if let (true, Some(current)) = (only_if_greater, current) {
+                match compare_marks(&value, current) {
+                    Some(Ordering::Greater) => {}
+                    Some(_) => return unchanged,
+                    None => return Err(Error::InvalidConfig(
+                        "only_if_greater needs two numbers or two strings".to_string()
+                    )),
+                }
+            }
+            Ok((Some(value.clone()), (previous, Some(value))))
+        })
+        .await?;
+        let updated = previous != current;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "key": params.key,
+                "previous": previous,
+                "value": current,
+                "updated": updated,
+            })),
+            error: None,
+        })
+    }
+
+    async fn delete_state(&self, task: &Task) -> Result<ExecutionResult> {
+        #[derive(Deserialize)]
+        struct Params {
+            key: String,
+        }
+
+        let params: Params = serde_json::from_value(task.params.clone())
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
+        let deleted = self.store.delete(&params.key).await?;
+
+        Ok(ExecutionResult {
+            success: true,
+            output: Some(serde_json::json!({ "key": params.key, "deleted": deleted })),
+            error: None,
+        })
+    }
+}
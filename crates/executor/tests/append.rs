@@ -0,0 +1,64 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_append_creates_then_extends() {
+    let (dir, executor) = temp_executor();
+
+    let first = executor
+        .execute(&task("append", json!({ "path": "run.log", "content": "started", "newline": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(first["len"], 8);
+    assert_eq!(first["appended_bytes"], 8);
+
+    let second = executor
+        .execute(&task("append", json!({ "path": "run.log", "content": "done\n", "newline": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(second["len"], 13);
+    assert_eq!(std::fs::read_to_string(dir.path().join("run.log")).unwrap(), "started\ndone\n");
+
+    executor
+        .execute(&task("append", json!({ "path": "run.log", "content": "no newline" })))
+        .await
+        .unwrap();
+    assert!(std::fs::read_to_string(dir.path().join("run.log")).unwrap().ends_with("done\nno newline"));
+}
+
+#[tokio::test]
+async fn test_append_empty_string() {
+    let (dir, executor) = temp_executor();
+
+    let output = executor
+        .execute(&task("append", json!({ "path": "empty.log", "content": "", "newline": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["len"], 0);
+    assert!(dir.path().join("empty.log").exists());
+
+    std::fs::write(dir.path().join("data.txt"), "abc").unwrap();
+    let output = executor
+        .execute(&task("append", json!({ "path": "data.txt", "content": "" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["len"], 3);
+    assert_eq!(output["appended_bytes"], 0);
+
+    let result = executor
+        .execute(&task("append", json!({ "path": "../outside.log", "content": "x" })))
+        .await;
+    assert!(matches!(result, Err(Error::PermissionDenied(_))));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_append_csv_creates_with_headers_then_appends() {
+    let (dir, executor) = temp_executor();
+
+    let output = executor
+        .execute(&task("append_csv", json!({
+            "path": "log.csv",
+            "headers": ["id", "note"],
+            "rows": [[1, "first, with comma"]],
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["headers_written"], true);
+    assert_eq!(output["rows_appended"], 1);
+
+    let output = executor
+        .execute(&task("append_csv", json!({ "path": "log.csv", "rows": [[2, "second"], [3, null]] })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["headers_written"], false);
+    assert_eq!(output["rows_appended"], 2);
+
+    let content = std::fs::read_to_string(dir.path().join("log.csv")).unwrap();
+    assert_eq!(content, "id,note\n1,\"first, with comma\"\n2,second\n3,\n");
+    assert_eq!(output["len"], content.len());
+}
+
+#[tokio::test]
+async fn test_append_csv_repairs_missing_trailing_newline() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "a,b\n1,2").unwrap();
+
+    executor
+        .execute(&task("append_csv", json!({ "path": "data.csv", "rows": [[3, 4]] })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("data.csv")).unwrap(), "a,b\n1,2\n3,4\n");
+}
+
+#[tokio::test]
+async fn test_append_csv_rejects_mismatched_rows_and_missing_headers() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "a,b\n1,2\n").unwrap();
+
+    let err = executor
+        .execute(&task("append_csv", json!({ "path": "data.csv", "rows": [[3, 4], [5]] })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("Row 1 has 1 columns")), "{:?}", err);
+    assert_eq!(std::fs::read_to_string(dir.path().join("data.csv")).unwrap(), "a,b\n1,2\n");
+
+    let err = executor
+        .execute(&task("append_csv", json!({ "path": "data.csv", "headers": ["a", "c"], "rows": [] })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+
+    let err = executor
+        .execute(&task("append_csv", json!({ "path": "new.csv", "rows": [[1]] })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("headers")), "{:?}", err);
+    assert!(!dir.path().join("new.csv").exists());
+}
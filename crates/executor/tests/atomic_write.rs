@@ -0,0 +1,92 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+fn entries(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_atomic_writes_replace_in_place() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt"), "old").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir.path().join("a.txt"), std::fs::Permissions::from_mode(0o640)).unwrap();
+    }
+
+    let output = executor
+        .execute(&task("write", json!({ "path": "a.txt", "content": "new", "atomic": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["atomic"], true);
+    assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "new");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir.path().join("a.txt")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    executor
+        .execute(&task("write_json", json!({ "path": "b.json", "data": { "k": 1 }, "atomic": true })))
+        .await
+        .unwrap();
+    executor
+        .execute(&task("write_csv", json!({ "path": "c.csv", "headers": ["k"], "rows": [[1]], "atomic": true })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("c.csv")).unwrap(), "k\n1\n");
+    assert_eq!(entries(dir.path()), vec!["a.txt", "b.json", "c.csv"]);
+
+    let output = executor
+        .execute(&task("write", json!({ "path": "a.txt", "content": "plain" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["atomic"], false);
+}
+
+#[tokio::test]
+async fn test_atomic_write_csv_failure_leaves_original_untouched() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("out.csv"), "a,b\n1,2\n").unwrap();
+
+    // The ragged second row fails serialization after the temp file exists
+    let err = executor
+        .execute(&task("write_csv", json!({
+            "path": "out.csv",
+            "headers": ["a", "b"],
+            "rows": [[3, 4], [5]],
+            "atomic": true,
+        })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::InvalidData), "{:?}", err);
+    assert_eq!(std::fs::read_to_string(dir.path().join("out.csv")).unwrap(), "a,b\n1,2\n");
+    assert_eq!(entries(dir.path()), vec!["out.csv"]);
+
+    let err = executor
+        .execute(&task("write_csv", json!({
+            "path": "out.csv",
+            "headers": ["a", "b"],
+            "rows": [],
+            "atomic": true,
+            "resume": true,
+        })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
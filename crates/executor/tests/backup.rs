@@ -0,0 +1,157 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+fn read(dir: &std::path::Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(name)).unwrap()
+}
+
+#[tokio::test]
+async fn test_backup_before_overwrite() {
+    let (dir, executor) = temp_executor();
+
+    // Nothing to back up on the first write
+    let output = executor
+        .execute(&task("write", json!({ "path": "a.txt", "content": "one", "backup": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["backup"], Value::Null);
+
+    let output = executor
+        .execute(&task("write", json!({ "path": "a.txt", "content": "two", "backup": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["backup"], json!(dir.path().join("a.txt.bak")));
+    assert_eq!(read(dir.path(), "a.txt.bak"), "one");
+    assert_eq!(read(dir.path(), "a.txt"), "two");
+
+    executor
+        .execute(&task("write_json", json!({ "path": "a.json", "data": [1] })))
+        .await
+        .unwrap();
+    executor
+        .execute(&task("write_json", json!({ "path": "a.json", "data": [2], "backup": true, "atomic": true })))
+        .await
+        .unwrap();
+    let saved: Value = serde_json::from_str(&read(dir.path(), "a.json.bak")).unwrap();
+    assert_eq!(saved, json!([1]));
+
+    executor
+        .execute(&task("write_csv", json!({ "path": "a.csv", "headers": ["x"], "rows": [["1"]] })))
+        .await
+        .unwrap();
+    executor
+        .execute(&task("write_csv", json!({ "path": "a.csv", "headers": ["x"], "rows": [["2"]], "backup": true })))
+        .await
+        .unwrap();
+    assert_eq!(read(dir.path(), "a.csv.bak"), "x\n1\n");
+}
+
+#[tokio::test]
+async fn test_backup_on_copy_move_delete() {
+    let (dir, executor) = temp_executor();
+    for (name, content) in [("src.txt", "src"), ("dst.txt", "dst"), ("gone.txt", "gone")] {
+        std::fs::write(dir.path().join(name), content).unwrap();
+    }
+
+    let output = executor
+        .execute(&task("copy", json!({ "from": "src.txt", "to": "dst.txt", "backup": true, "backup_suffix": ".orig" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["backup"], json!(dir.path().join("dst.txt.orig")));
+    assert_eq!(read(dir.path(), "dst.txt.orig"), "dst");
+
+    std::fs::write(dir.path().join("src.txt"), "moved").unwrap();
+    executor
+        .execute(&task("move", json!({ "from": "src.txt", "to": "dst.txt", "backup": true })))
+        .await
+        .unwrap();
+    assert_eq!(read(dir.path(), "dst.txt.bak"), "src");
+    assert_eq!(read(dir.path(), "dst.txt"), "moved");
+
+    let output = executor
+        .execute(&task("delete", json!({ "path": "gone.txt", "backup": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["backup"], json!(dir.path().join("gone.txt.bak")));
+    assert!(!dir.path().join("gone.txt").exists());
+    assert_eq!(read(dir.path(), "gone.txt.bak"), "gone");
+}
+
+#[tokio::test]
+async fn test_backups_rotate_with_keep() {
+    let (dir, executor) = temp_executor();
+
+    for i in 0..5 {
+        executor
+            .execute(&task("write", json!({ "path": "a.txt", "content": i.to_string(), "backup": true, "keep": 3 })))
+            .await
+            .unwrap();
+    }
+    assert_eq!(read(dir.path(), "a.txt"), "4");
+    assert_eq!(read(dir.path(), "a.txt.bak"), "3");
+    assert_eq!(read(dir.path(), "a.txt.bak.1"), "2");
+    assert_eq!(read(dir.path(), "a.txt.bak.2"), "1");
+    assert!(!dir.path().join("a.txt.bak.3").exists());
+}
+
+#[tokio::test]
+async fn test_timestamped_backups_prune_oldest() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt.unrelated.bak"), "keep me").unwrap();
+
+    let mut backups = Vec::new();
+    for i in 0..4 {
+        let output = executor
+            .execute(&task("write", json!({
+                "path": "a.txt",
+                "content": i.to_string(),
+                "backup": true,
+                "backup_suffix": ".{timestamp}.bak",
+                "keep": 2,
+            })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        backups.push(output["backup"].clone());
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let mut names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n.starts_with("a.txt.2"))
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 2);
+    assert_eq!(json!(dir.path().join(&names[1])), backups[3]);
+    assert_eq!(read(dir.path(), &names[1]), "2");
+    assert_eq!(read(dir.path(), &names[0]), "1");
+    assert!(dir.path().join("a.txt.unrelated.bak").exists());
+}
+
+#[tokio::test]
+async fn test_backup_rejects_bad_settings() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+
+    for params in [
+        json!({ "path": "a.txt", "content": "x", "backup": true, "backup_suffix": "/../../escape" }),
+        json!({ "path": "a.txt", "content": "x", "backup": true, "keep": 0 }),
+    ] {
+        let err = executor.execute(&task("write", params)).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+    }
+    assert_eq!(read(dir.path(), "a.txt"), "one");
+}
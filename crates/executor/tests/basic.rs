@@ -1,13 +1,13 @@
+mod common;
+
+use common::temp_executor;
 use local_automation_common::Task;
-use local_automation_executor::file::FileExecutor;
 use local_automation_executor::Executor;
 use serde_json::json;
-use tempfile::tempdir;
 
 #[tokio::test]
 async fn test_all_file_operations() {
-    let dir = tempdir().unwrap();
-    let executor = FileExecutor::new(dir.path().to_path_buf());
+    let (_dir, executor) = temp_executor();
 
     // 1. Write file
     let write_task = Task::new(
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_binary_roundtrip_and_slices() {
+    let (dir, executor) = temp_executor();
+    // PNG signature followed by bytes that are not valid UTF-8
+    let bytes: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0xff, 0xfe, 0x00, 0x01];
+
+    let written = executor
+        .execute(&task("write_bytes", json!({ "path": "image.png", "content": "iVBORw0KGgr//gAB" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(written["bytes"], 12);
+    assert_eq!(std::fs::read(dir.path().join("image.png")).unwrap(), bytes);
+
+    let read = executor
+        .execute(&task("read_bytes", json!({ "path": "image.png" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(read["content"], "iVBORw0KGgr//gAB");
+    assert_eq!(read["bytes"], 12);
+
+    let slice = executor
+        .execute(&task("read_bytes", json!({ "path": "image.png", "offset": 8, "length": 2 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(slice["content"], "//4=");
+    assert_eq!(slice["size"], 12);
+
+    // Past the end yields what is there
+    let tail = executor
+        .execute(&task("read_bytes", json!({ "path": "image.png", "offset": 10, "length": 100 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(tail["bytes"], 2);
+}
+
+#[tokio::test]
+async fn test_malformed_base64_is_rejected() {
+    let (dir, executor) = temp_executor();
+
+    let result = executor
+        .execute(&task("write_bytes", json!({ "path": "x.bin", "content": "not base64!" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    assert!(!dir.path().join("x.bin").exists());
+}
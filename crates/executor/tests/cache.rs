@@ -0,0 +1,167 @@
+mod common;
+
+use common::executor_task;
+use local_automation_common::Error;
+use local_automation_executor::{CacheExecutor, Executor};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::tempdir;
+
+async fn run(cache: &CacheExecutor, operation: &str, params: Value) -> Value {
+    cache.execute(&executor_task("cache", operation, params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_put_get_and_expiry() {
+    let dir = tempdir().unwrap();
+    let cache = CacheExecutor::new(dir.path().join("cache"));
+
+    let miss = run(&cache, "get", json!({ "key": "rates:EUR" })).await;
+    assert_eq!(miss["hit"], false);
+
+    let put = executor_task("cache", "put", json!({ "key": "rates:EUR", "value": { "USD": 1.08 }, "ttl_ms": 150, "run_id": "run-7" }));
+    cache.execute(&put).await.unwrap();
+    let hit = run(&cache, "get", json!({ "key": "rates:EUR" })).await;
+    assert_eq!(hit["hit"], true);
+    assert_eq!(hit["value"], json!({ "USD": 1.08 }));
+    assert_eq!(hit["provenance"], json!({ "task_id": put.id.to_string(), "run_id": "run-7" }));
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let expired = run(&cache, "get", json!({ "key": "rates:EUR" })).await;
+    assert_eq!(expired["hit"], false);
+    assert_eq!(expired["value"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_lru_eviction_keeps_recently_used() {
+    let dir = tempdir().unwrap();
+    let probe = CacheExecutor::new(dir.path().join("probe"));
+    let size = run(&probe, "put", json!({ "key": "k0", "value": "x".repeat(100) })).await["bytes"].as_u64().unwrap();
+    let cache = CacheExecutor::new(dir.path().join("cache")).with_max_bytes(size * 3 + size / 2);
+
+    for key in ["k1", "k2", "k3"] {
+        run(&cache, "put", json!({ "key": key, "value": "x".repeat(100) })).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    // Touch k1 so k2 becomes the least recently used
+    assert_eq!(run(&cache, "get", json!({ "key": "k1" })).await["hit"], true);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let put = run(&cache, "put", json!({ "key": "k4", "value": "x".repeat(100) })).await;
+    assert_eq!(put["evicted"], json!(["k2"]));
+    for (key, hit) in [("k1", true), ("k2", false), ("k3", true), ("k4", true)] {
+        assert_eq!(run(&cache, "get", json!({ "key": key })).await["hit"], hit, "{}", key);
+    }
+
+    let err = cache
+        .execute(&executor_task("cache", "put", json!({ "key": "big", "value": "x".repeat(1000) })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
+
+#[tokio::test]
+async fn test_invalidate_key_and_prefix() {
+    let dir = tempdir().unwrap();
+    let cache = CacheExecutor::new(dir.path().join("cache"));
+    for key in ["partner:a", "partner:b", "rates:EUR"] {
+        run(&cache, "put", json!({ "key": key, "value": 1 })).await;
+    }
+
+    let output = run(&cache, "invalidate", json!({ "prefix": "partner:" })).await;
+    assert_eq!(output["invalidated"], json!(["partner:a", "partner:b"]));
+    let output = run(&cache, "invalidate", json!({ "key": "rates:EUR" })).await;
+    assert_eq!(output["invalidated"], json!(["rates:EUR"]));
+    let output = run(&cache, "invalidate", json!({ "key": "rates:EUR" })).await;
+    assert_eq!(output["invalidated"], json!([]));
+
+    let err = cache.execute(&executor_task("cache", "invalidate", json!({}))).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
+
+#[tokio::test]
+async fn test_get_or_populate_without_stampede_protection() {
+    let dir = tempdir().unwrap();
+    let cache = CacheExecutor::new(dir.path().join("cache"));
+
+    for _ in 0..2 {
+        let output = run(&cache, "get_or_populate", json!({ "key": "k" })).await;
+        assert_eq!((output["hit"].clone(), output["populate"].clone()), (json!(false), json!(true)));
+    }
+    run(&cache, "put", json!({ "key": "k", "value": 42 })).await;
+    let output = run(&cache, "get_or_populate", json!({ "key": "k" })).await;
+    assert_eq!((output["hit"].clone(), output["value"].clone()), (json!(true), json!(42)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_stampede_protection_lets_one_run_populate() {
+    let dir = tempdir().unwrap();
+    let cache = Arc::new(CacheExecutor::new(dir.path().join("cache")));
+
+    let mut handles = Vec::new();
+    for _ in 0..5 {
+        let cache = cache.clone();
+        handles.push(tokio::spawn(async move {
+            let output = run(&cache, "get_or_populate", json!({ "key": "products", "wait_ms": 2000 })).await;
+            if output["populate"] == true {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                run(&cache, "put", json!({ "key": "products", "value": ["p1", "p2"] })).await;
+            }
+            output
+        }));
+    }
+    let mut populated = 0;
+    for handle in handles {
+        let output = handle.await.unwrap();
+        if output["populate"] == true {
+            populated += 1;
+        } else {
+            assert_eq!(output["value"], json!(["p1", "p2"]));
+        }
+    }
+    assert_eq!(populated, 1);
+
+    // A claim whose run never puts expires, and waiters then populate themselves
+    run(&cache, "invalidate", json!({ "key": "products" })).await;
+    let first = run(&cache, "get_or_populate", json!({ "key": "products", "wait_ms": 0, "lease_ms": 60000 })).await;
+    assert_eq!(first["populate"], true);
+    let second = run(&cache, "get_or_populate", json!({ "key": "products", "wait_ms": 50 })).await;
+    assert_eq!((second["hit"].clone(), second["populate"].clone()), (json!(false), json!(true)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_racing_puts_leave_a_whole_entry() {
+    let dir = tempdir().unwrap();
+    let cache = Arc::new(CacheExecutor::new(dir.path().join("cache")));
+
+    let mut handles = Vec::new();
+    for i in 0..16 {
+        let cache = cache.clone();
+        handles.push(tokio::spawn(async move {
+            run(&cache, "put", json!({ "key": "shared", "value": { "writer": i, "blob": "y".repeat(10_000) } })).await;
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    let output = run(&cache, "get", json!({ "key": "shared" })).await;
+    let writer = output["value"]["writer"].as_u64().unwrap();
+    assert!(writer < 16);
+    assert_eq!(output["value"]["blob"].as_str().unwrap().len(), 10_000);
+}
+
+#[tokio::test]
+async fn test_out_of_range_durations_are_rejected() {
+    let dir = tempdir().unwrap();
+    let cache = CacheExecutor::new(dir.path().join("cache"));
+
+    for ms in [u64::MAX, i64::MAX as u64] {
+        let result = cache.execute(&executor_task("cache", "put", json!({ "key": "k", "value": 1, "ttl_ms": ms }))).await;
+        assert!(matches!(&result, Err(Error::InvalidConfig(m)) if m.contains("ttl_ms")), "{:?}", result);
+        let result = cache.execute(&executor_task("cache", "get_or_populate", json!({ "key": "k", "wait_ms": 10, "lease_ms": ms }))).await;
+        assert!(matches!(&result, Err(Error::InvalidConfig(m)) if m.contains("lease_ms")), "{:?}", result);
+    }
+    let miss = run(&cache, "get", json!({ "key": "k" })).await;
+    assert_eq!(miss["hit"], false);
+}
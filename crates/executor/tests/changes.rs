@@ -0,0 +1,123 @@
+mod common;
+
+use common::task;
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::{Executor, StateStore};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn executor(dir: &Path) -> FileExecutor {
+    FileExecutor::new(dir.to_path_buf())
+        .with_state_store(Arc::new(StateStore::new(dir.join("state.json"))))
+}
+
+async fn changes(executor: &FileExecutor, params: Value) -> Value {
+    let mut params = params;
+    params["path"] = json!("inbox");
+    params["state_key"] = json!("inbox_changes");
+    executor.execute(&task("changes_since", params)).await.unwrap().output.unwrap()
+}
+
+async fn confirm(executor: &FileExecutor, scan: &Value) -> Value {
+    executor
+        .execute(&task("changes_confirm", json!({
+            "state_key": "inbox_changes",
+            "scan_id": scan["scan_id"],
+            "through": scan["through"],
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_changes_are_reported_until_confirmed() {
+    let dir = tempdir().unwrap();
+    let inbox = dir.path().join("inbox");
+    std::fs::create_dir_all(inbox.join("sub")).unwrap();
+    std::fs::write(inbox.join("a.csv"), "1").unwrap();
+    std::fs::write(inbox.join("b.csv"), "1").unwrap();
+    std::fs::write(inbox.join("sub/c.csv"), "1").unwrap();
+    std::fs::write(inbox.join("notes.txt"), "1").unwrap();
+    let executor = executor(dir.path());
+    let filter = json!({ "filter": "**/*.csv" });
+
+    let first = changes(&executor, filter.clone()).await;
+    assert_eq!(first["created"], json!(["a.csv", "b.csv", "sub/c.csv"]));
+    assert_eq!(first["backend"], "index");
+    // Unconfirmed scans are reported again
+    let again = changes(&executor, filter.clone()).await;
+    assert_eq!(again["created"], first["created"]);
+    confirm(&executor, &again).await;
+    assert_eq!(changes(&executor, filter.clone()).await["created"], json!([]));
+
+    std::fs::write(inbox.join("a.csv"), "12").unwrap();
+    std::fs::remove_file(inbox.join("b.csv")).unwrap();
+    std::fs::write(inbox.join("sub/d.csv"), "1").unwrap();
+    std::fs::write(inbox.join("notes.txt"), "changed").unwrap();
+    let scan = changes(&executor, filter.clone()).await;
+    assert_eq!(scan["created"], json!(["sub/d.csv"]));
+    assert_eq!(scan["modified"], json!(["a.csv"]));
+    assert_eq!(scan["deleted"], json!(["b.csv"]));
+    let confirmed = confirm(&executor, &scan).await;
+    assert_eq!(confirmed["applied"], 3);
+
+    // The confirmed index survives a restart
+    let restarted = self::executor(dir.path());
+    let scan = changes(&restarted, filter).await;
+    assert_eq!((scan["created"].clone(), scan["modified"].clone(), scan["deleted"].clone()), (json!([]), json!([]), json!([])));
+
+    let err = restarted
+        .execute(&task("changes_since", json!({ "path": "inbox", "state_key": "inbox_changes" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("filter")));
+}
+
+#[tokio::test]
+async fn test_pages_and_partial_confirmation() {
+    let dir = tempdir().unwrap();
+    let inbox = dir.path().join("inbox");
+    std::fs::create_dir_all(&inbox).unwrap();
+    for name in ["1", "2", "3", "4", "5"] {
+        std::fs::write(inbox.join(format!("{}.txt", name)), name).unwrap();
+    }
+    let executor = executor(dir.path());
+
+    let page = changes(&executor, json!({ "max_changes": 2 })).await;
+    assert_eq!(page["created"], json!(["1.txt", "2.txt"]));
+    assert_eq!(page["truncated"], true);
+    assert_eq!(page["through"], "2.txt");
+
+    // Later pages come from the same scan, even if the tree changes meanwhile
+    std::fs::write(inbox.join("0.txt"), "0").unwrap();
+    let second = changes(&executor, json!({ "max_changes": 2, "cursor": page["next_cursor"] })).await;
+    assert_eq!(second["created"], json!(["3.txt", "4.txt"]));
+    assert_eq!(second["scan_id"], page["scan_id"]);
+    let last = changes(&executor, json!({ "max_changes": 2, "cursor": second["next_cursor"] })).await;
+    assert_eq!(last["created"], json!(["5.txt"]));
+    assert_eq!(last["truncated"], false);
+    assert_eq!(last["next_cursor"], Value::Null);
+
+    // Only the first page was processed
+    let confirmed = confirm(&executor, &page).await;
+    assert_eq!(confirmed["complete"], false);
+    assert_eq!(confirmed["applied"], 2);
+    let rescan = changes(&executor, json!({})).await;
+    assert_eq!(rescan["created"], json!(["0.txt", "3.txt", "4.txt", "5.txt"]));
+
+    // A fresh scan replaces the pending one its cursors pointed into
+    let err = executor
+        .execute(&task("changes_since", json!({
+            "path": "inbox",
+            "state_key": "inbox_changes",
+            "cursor": second["next_cursor"],
+        })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("no longer pending")));
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::codec;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+
+fn sample_text() -> String {
+    (0..2000).map(|i| format!("line {} of the sample export\n", i)).collect()
+}
+
+fn have(binary: &str) -> bool {
+    Command::new(binary).arg("--version").output().is_ok_and(|o| o.status.success())
+}
+
+#[tokio::test]
+async fn test_round_trip_every_codec() {
+    let (dir, executor) = temp_executor();
+    let text = sample_text();
+    std::fs::write(dir.path().join("data.txt"), &text).unwrap();
+
+    for name in codec::available() {
+        let ext = codec::by_name(name).unwrap().extension();
+        let compressed = executor
+            .execute(&task("compress", json!({ "path": "data.txt", "codec": name, "level": 3 })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(compressed["original_bytes"], text.len());
+        assert!(compressed["compressed_bytes"].as_u64().unwrap() < text.len() as u64 / 4, "{}", name);
+        let archive = format!("data.txt.{}", ext);
+        assert!(dir.path().join(&archive).exists());
+
+        // Magic bytes pick the codec even when the name says otherwise
+        let misnamed = format!("{}.bin", name);
+        std::fs::rename(dir.path().join(&archive), dir.path().join(&misnamed)).unwrap();
+        let decompressed = executor
+            .execute(&task("decompress", json!({ "path": misnamed, "dest": format!("{}.txt", name) })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(decompressed["codec"], name);
+        assert_eq!(std::fs::read_to_string(dir.path().join(format!("{}.txt", name))).unwrap(), text);
+    }
+}
+
+#[tokio::test]
+async fn test_dest_defaults_and_delete_source() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("report.csv"), "a,b\n1,2\n").unwrap();
+
+    // Codec taken from dest's extension
+    if codec::by_name("zstd").is_some() {
+        let output = executor
+            .execute(&task("compress", json!({ "path": "report.csv", "dest": "copy.zst" })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(output["codec"], "zstd");
+    }
+    let output = executor
+        .execute(&task("compress", json!({ "path": "report.csv", "delete_source": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["codec"], "gzip");
+    assert!(!dir.path().join("report.csv").exists());
+
+    executor
+        .execute(&task("decompress", json!({ "path": "report.csv.gz", "delete_source": true })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("report.csv")).unwrap(), "a,b\n1,2\n");
+    assert!(!dir.path().join("report.csv.gz").exists());
+}
+
+#[tokio::test]
+async fn test_plain_and_mismatched_input_is_rejected() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("plain.gz"), "not compressed at all").unwrap();
+
+    let err = executor
+        .execute(&task("decompress", json!({ "path": "plain.gz" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, Error::Io(e) if e.kind() == std::io::ErrorKind::InvalidData), "{}", err);
+    assert!(err.to_string().contains("not gzip data"), "{}", err);
+    assert!(!dir.path().join("plain").exists());
+
+    std::fs::write(dir.path().join("plain.txt"), "text").unwrap();
+    let err = executor
+        .execute(&task("decompress", json!({ "path": "plain.txt", "dest": "out.txt" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not a recognized compressed file"), "{}", err);
+
+    let result = executor
+        .execute(&task("compress", json!({ "path": "plain.txt", "codec": "rar" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    let result = executor
+        .execute(&task("compress", json!({ "path": "plain.txt", "level": 42 })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+    // Reading a directory fails mid-copy; the half-written archive goes too
+    std::fs::create_dir(dir.path().join("folder")).unwrap();
+    let result = executor
+        .execute(&task("compress", json!({ "path": "folder", "dest": "folder.gz" })))
+        .await;
+    assert!(matches!(result, Err(Error::Io(_))), "{:?}", result);
+    assert!(!dir.path().join("folder.gz").exists());
+}
+
+#[tokio::test]
+async fn test_read_operations_decompress_transparently() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("report.csv"), "name,qty\nbolt,3\n").unwrap();
+    executor
+        .execute(&task("compress", json!({ "path": "report.csv" })))
+        .await
+        .unwrap();
+
+    let csv = executor
+        .execute(&task("read_csv", json!({ "path": "report.csv.gz", "decompress": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(csv["headers"], json!(["name", "qty"]));
+
+    // Plain files pass through untouched
+    let plain = executor
+        .execute(&task("read", json!({ "path": "report.csv", "decompress": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(plain["content"], "name,qty\nbolt,3\n");
+}
+
+#[tokio::test]
+async fn test_compatible_with_system_binaries() {
+    let (dir, executor) = temp_executor();
+    let text = sample_text();
+    let binaries = [("gzip", "gz"), ("zstd", "zst"), ("xz", "xz")];
+
+    for (binary, ext) in binaries {
+        if codec::by_name(binary).is_none() || !have(binary) {
+            continue;
+        }
+        let input = format!("sys-{}.txt", binary);
+        std::fs::write(dir.path().join(&input), &text).unwrap();
+        let status = Command::new(binary).arg("-q").arg("-k").arg(dir.path().join(&input)).status().unwrap();
+        assert!(status.success());
+
+        // Produced by the system tool, read by us
+        let read = executor
+            .execute(&task("read", json!({ "path": format!("{}.{}", input, ext), "decompress": true })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(read["content"], text);
+
+        // Produced by us, read by the system tool
+        let ours = format!("ours-{}.txt", binary);
+        std::fs::write(dir.path().join(&ours), &text).unwrap();
+        executor
+            .execute(&task("compress", json!({ "path": ours, "codec": binary })))
+            .await
+            .unwrap();
+        let output = Command::new(binary)
+            .arg("-d").arg("-c")
+            .arg(dir.path().join(format!("{}.{}", ours, ext)))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), text);
+    }
+}
+
+#[test]
+fn test_detection_rule_is_shared() {
+    let gzip = codec::by_name("gzip").unwrap();
+    assert_eq!(codec::from_extension(Path::new("a.csv.GZ")).unwrap().name(), "gzip");
+    assert_eq!(codec::sniff(gzip.magic()).unwrap().name(), "gzip");
+    // Explicit name, then magic, then extension
+    let detected = codec::detect(None, Path::new("a.txt"), &[0x1f, 0x8b, 8, 0]).unwrap();
+    assert_eq!(detected.unwrap().name(), "gzip");
+    assert!(codec::detect(None, Path::new("a.txt"), b"hello").unwrap().is_none());
+    assert!(codec::detect(Some("nope"), Path::new("a.gz"), b"").is_err());
+}
+
+#[tokio::test]
+async fn test_gzip_and_gunzip_shorthands() {
+    let (dir, executor) = temp_executor();
+    let text = sample_text();
+    std::fs::write(dir.path().join("report.csv"), &text).unwrap();
+    std::fs::write(dir.path().join("plain.csv.gz"), "not gzip at all").unwrap();
+
+    let result = executor
+        .execute(&task("gzip", json!({ "path": "report.csv", "delete_source": true })))
+        .await
+        .unwrap();
+    let output = result.output.unwrap();
+    assert_eq!(output["codec"], "gzip");
+    assert_eq!(output["original_bytes"], text.len());
+    assert!(output.get("warnings").is_none());
+    assert!(!dir.path().join("report.csv").exists());
+
+    let output = executor
+        .execute(&task("gunzip", json!({ "path": "report.csv.gz" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["dest"], json!(dir.path().join("report.csv")));
+    assert_eq!(std::fs::read_to_string(dir.path().join("report.csv")).unwrap(), text);
+
+    let err = executor
+        .execute(&task("gunzip", json!({ "path": "plain.csv.gz" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::InvalidData), "{:?}", err);
+    assert!(!dir.path().join("plain.csv").exists());
+
+    let err = executor
+        .execute(&task("gzip", json!({ "path": "report.csv", "codec": "zstd" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
@@ -0,0 +1,22 @@
+// Helpers shared by the integration tests; not every test file uses all of them
+#![allow(dead_code)]
+
+use local_automation_common::Task;
+use local_automation_executor::file::FileExecutor;
+use serde_json::Value;
+use tempfile::TempDir;
+
+pub fn task(operation: &str, params: Value) -> Task {
+    executor_task("file", operation, params)
+}
+
+pub fn executor_task(executor: &str, operation: &str, params: Value) -> Task {
+    Task::new(executor.to_string(), operation.to_string(), params)
+}
+
+// A FileExecutor rooted in a fresh temp dir; keep the dir alive for the test
+pub fn temp_executor() -> (TempDir, FileExecutor) {
+    let dir = tempfile::tempdir().unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf());
+    (dir, executor)
+}
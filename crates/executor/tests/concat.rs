@@ -0,0 +1,121 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+fn setup() -> (tempfile::TempDir, FileExecutor) {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("daily")).unwrap();
+    std::fs::write(dir.path().join("daily/2024-06-01.csv"), "id,amount\n1,10\n2,20\n").unwrap();
+    std::fs::write(dir.path().join("daily/2024-06-02.csv"), "id,amount\n3,30\n").unwrap();
+    std::fs::write(dir.path().join("daily/notes.txt"), "ignored").unwrap();
+    (dir, executor)
+}
+
+#[tokio::test]
+async fn test_concat_csv_writes_header_once() {
+    let (dir, executor) = setup();
+    let output = executor
+        .execute(&task(
+            "concat_csv",
+            json!({ "pattern": "daily/*.csv", "dest": "month.csv", "provenance_column": "source" }),
+        ))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("month.csv")).unwrap(),
+        "id,amount,source\n1,10,daily/2024-06-01.csv\n2,20,daily/2024-06-01.csv\n3,30,daily/2024-06-02.csv\n"
+    );
+    assert_eq!(
+        output["sources"],
+        json!([
+            { "path": "daily/2024-06-01.csv", "rows": 2 },
+            { "path": "daily/2024-06-02.csv", "rows": 1 }
+        ])
+    );
+    assert_eq!(output["total_rows"], 3);
+}
+
+#[tokio::test]
+async fn test_concat_csv_strict_mismatch_writes_nothing() {
+    let (dir, executor) = setup();
+    std::fs::write(dir.path().join("daily/2024-06-03.csv"), "amount,id\n40,4\n").unwrap();
+
+    let err = executor
+        .execute(&task("concat_csv", json!({ "pattern": "daily/*.csv", "dest": "month.csv" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+    assert!(err.to_string().contains("daily/2024-06-03.csv"), "{}", err);
+    assert!(!dir.path().join("month.csv").exists());
+}
+
+#[tokio::test]
+async fn test_concat_csv_by_name_fills_missing_columns() {
+    let (dir, executor) = setup();
+    std::fs::write(dir.path().join("extra.csv"), "amount,region,id\n40,eu,4\n").unwrap();
+
+    executor
+        .execute(&task(
+            "concat_csv",
+            json!({
+                "sources": ["daily/2024-06-02.csv", "extra.csv"],
+                "dest": "merged.csv",
+                "header_mode": "by_name",
+                "fill_value": "n/a"
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("merged.csv")).unwrap(),
+        "id,amount,region\n3,30,n/a\n4,40,eu\n"
+    );
+}
+
+#[tokio::test]
+async fn test_concat_plain_with_separator() {
+    let (dir, executor) = setup();
+    std::fs::write(dir.path().join("a.txt"), "first").unwrap();
+    std::fs::write(dir.path().join("b.txt"), "second").unwrap();
+
+    let output = executor
+        .execute(&task(
+            "concat",
+            json!({ "sources": ["b.txt", "a.txt"], "dest": "out.txt", "separator": "\n---\n" }),
+        ))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "second\n---\nfirst"
+    );
+    assert_eq!(output["total_bytes"], 16);
+}
+
+#[tokio::test]
+async fn test_concat_rejects_bad_sources() {
+    let (_dir, executor) = setup();
+    let none = executor
+        .execute(&task("concat", json!({ "pattern": "missing/*.txt", "dest": "out.txt" })))
+        .await;
+    assert!(matches!(none, Err(Error::InvalidConfig(_))));
+
+    let traversal = executor
+        .execute(&task("concat", json!({ "pattern": "../*.txt", "dest": "out.txt" })))
+        .await;
+    assert!(matches!(traversal, Err(Error::PermissionDenied(_))));
+
+    let self_concat = executor
+        .execute(&task("concat", json!({ "sources": ["daily/notes.txt"], "dest": "daily/notes.txt" })))
+        .await;
+    assert!(matches!(self_concat, Err(Error::InvalidConfig(_))));
+}
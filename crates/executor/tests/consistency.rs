@@ -0,0 +1,168 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::{FileExecutor, WriteConsistency};
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_synced_mode_counts_extra_syncs() {
+    let dir = tempdir().unwrap();
+    let relaxed = FileExecutor::new(dir.path().to_path_buf());
+    relaxed
+        .execute(&task("write", json!({ "path": "a.txt", "content": "x" })))
+        .await
+        .unwrap();
+    assert_eq!(relaxed.sync_count(), 0);
+
+    let synced = FileExecutor::new(dir.path().to_path_buf())
+        .with_write_consistency(WriteConsistency::Synced);
+    synced
+        .execute(&task("write", json!({ "path": "b.txt", "content": "hello" })))
+        .await
+        .unwrap();
+    assert_eq!(synced.sync_count(), 2);
+
+    synced
+        .execute(&task("delete", json!({ "path": "b.txt" })))
+        .await
+        .unwrap();
+    assert_eq!(synced.sync_count(), 3);
+}
+
+#[tokio::test]
+async fn test_verified_mode_round_trips_mutations() {
+    let dir = tempdir().unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf())
+        .with_write_consistency(WriteConsistency::Verified);
+
+    let steps = [
+        task("create_dir", json!({ "path": "out" })),
+        task("write", json!({ "path": "out/a.txt", "content": "hello" })),
+        task("write_json", json!({ "path": "out/a.json", "data": { "k": 1 } })),
+        task("write_csv", json!({ "path": "out/a.csv", "headers": ["x"], "rows": [["1"]] })),
+        task("copy", json!({ "from": "out/a.txt", "to": "out/b.txt" })),
+        task("move", json!({ "from": "out/b.txt", "to": "out/c.txt" })),
+    ];
+    for step in &steps {
+        assert!(executor.execute(step).await.unwrap().success, "{}", step.operation);
+    }
+    assert!(!dir.path().join("out/b.txt").exists());
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/c.txt")).unwrap(), "hello");
+}
+
+#[tokio::test]
+async fn test_expect_recent_times_out_on_stale_file() {
+    let (dir, executor) = temp_executor();
+    let path = dir.path().join("old.txt");
+    std::fs::write(&path, "stale").unwrap();
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(old).unwrap();
+
+    let result = executor
+        .execute(&task(
+            "read",
+            json!({ "path": "old.txt", "expect_recent": { "within_ms": 1000, "timeout_ms": 100 } }),
+        ))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+}
+
+#[tokio::test]
+async fn test_expect_recent_waits_for_delayed_write() {
+    let dir = tempdir().unwrap();
+    let executor = Arc::new(FileExecutor::new(dir.path().to_path_buf()));
+
+    // The write becomes visible only after the reader has started polling
+    let writer = executor.clone();
+    let write = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        writer
+            .execute(&task("write", json!({ "path": "late.txt", "content": "fresh" })))
+            .await
+            .unwrap();
+    });
+
+    let exists = executor
+        .execute(&task(
+            "exists",
+            json!({ "path": "late.txt", "expect_recent": { "within_ms": 5000, "timeout_ms": 3000 } }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(exists.output.unwrap()["exists"], true);
+    write.await.unwrap();
+
+    let read = executor
+        .execute(&task(
+            "read",
+            json!({ "path": "other.txt", "expect_recent": { "path": "late.txt", "within_ms": 5000 } }),
+        ))
+        .await;
+    // The barrier passes on late.txt, the read itself then fails on the missing file
+    assert!(matches!(read, Err(Error::Io(_))));
+}
+
+#[tokio::test]
+async fn test_stat_honors_expect_recent() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("old.txt");
+    std::fs::write(&path, "stale").unwrap();
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(old).unwrap();
+    let executor = Arc::new(FileExecutor::new(dir.path().to_path_buf()));
+
+    let result = executor
+        .execute(&task(
+            "stat",
+            json!({ "path": "old.txt", "expect_recent": { "within_ms": 1000, "timeout_ms": 100 } }),
+        ))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+
+    let writer = executor.clone();
+    let write = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        writer
+            .execute(&task("write", json!({ "path": "old.txt", "content": "fresh!" })))
+            .await
+            .unwrap();
+    });
+    let stat = executor
+        .execute(&task(
+            "stat",
+            json!({ "path": "old.txt", "expect_recent": { "within_ms": 5000, "timeout_ms": 3000 } }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(stat.output.unwrap()["size"], 6);
+    write.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_later_writing_operations_settle() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("big.bin"), vec![7u8; 4096]).unwrap();
+    std::fs::write(dir.path().join("items.json"), "[1, 2, 3]").unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf())
+        .with_write_consistency(WriteConsistency::Verified);
+    executor
+        .execute(&task("zip", json!({ "sources": ["items.json"], "dest": "items.zip" })))
+        .await
+        .unwrap();
+
+    let steps = [
+        task("copy_large", json!({ "from": "big.bin", "to": "copy.bin" })),
+        task("read_json_stream", json!({ "path": "items.json", "dest": "items.ndjson" })),
+        task("unzip", json!({ "path": "items.zip", "dest_dir": "unzipped" })),
+    ];
+    for step in &steps {
+        let before = executor.sync_count();
+        assert!(executor.execute(step).await.unwrap().success, "{}", step.operation);
+        assert!(executor.sync_count() > before, "{} did not settle", step.operation);
+    }
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_csv_to_json_keys_rows_by_header() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("people.csv"),
+        "name,city,note\nJosé,São Paulo,\"likes \"\"café\"\", tea\"\n李雷,北京,\n",
+    ).unwrap();
+
+    let output = executor
+        .execute(&task("csv_to_json", json!({ "from": "people.csv", "to": "people.json" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["rows"], 2);
+
+    let written: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join("people.json")).unwrap()).unwrap();
+    assert_eq!(written, json!([
+        { "name": "José", "city": "São Paulo", "note": "likes \"café\", tea" },
+        { "name": "李雷", "city": "北京", "note": "" },
+    ]));
+}
+
+#[tokio::test]
+async fn test_json_to_csv_unions_keys_and_leaves_gaps_empty() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("items.json"), json!([
+        { "name": "Ünïcode, Ltd", "qty": 3 },
+        { "name": "say \"hi\"", "price": 1.5 },
+        { "qty": null, "tags": ["a", "b"] },
+    ]).to_string()).unwrap();
+
+    let output = executor
+        .execute(&task("json_to_csv", json!({ "from": "items.json", "to": "items.csv" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["rows"], 3);
+    assert_eq!(output["columns"], json!(["name", "qty", "price", "tags"]));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("items.csv")).unwrap(),
+        "name,qty,price,tags\n\"Ünïcode, Ltd\",3,,\n\"say \"\"hi\"\"\",,1.5,\n,,,\"[\"\"a\"\",\"\"b\"\"]\"\n"
+    );
+}
+
+#[tokio::test]
+async fn test_json_to_csv_explicit_columns_and_round_trip() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("in.json"), json!([
+        { "b": "2", "a": "1", "dropped": "x" },
+        { "a": "ä" },
+    ]).to_string()).unwrap();
+
+    executor
+        .execute(&task("json_to_csv", json!({ "from": "in.json", "to": "out.csv", "columns": ["b", "a"] })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("out.csv")).unwrap(), "b,a\n2,1\n,ä\n");
+
+    executor
+        .execute(&task("csv_to_json", json!({ "from": "out.csv", "to": "back.json" })))
+        .await
+        .unwrap();
+    let back: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join("back.json")).unwrap()).unwrap();
+    assert_eq!(back, json!([{ "b": "2", "a": "1" }, { "b": "", "a": "ä" }]));
+}
+
+#[tokio::test]
+async fn test_json_to_csv_rejects_non_objects() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("bad.json"), "[{\"a\": 1}, 2]").unwrap();
+    std::fs::write(dir.path().join("obj.json"), "{\"a\": 1}").unwrap();
+
+    for from in ["bad.json", "obj.json"] {
+        let err = executor
+            .execute(&task("json_to_csv", json!({ "from": from, "to": "out.csv" })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::InvalidData), "{:?}", err);
+    }
+    assert!(!dir.path().join("out.csv").exists());
+}
+
+#[tokio::test]
+async fn test_json_to_csv_column_order_is_sorted_per_object() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("in.json"), r#"[{ "zeta": 1, "alpha": 2 }, { "mid": 3, "beta": 4 }]"#).unwrap();
+
+    let output = executor
+        .execute(&task("json_to_csv", json!({ "from": "in.json", "to": "out.csv" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    // The file order of keys is not kept: serde_json sorts each object
+    assert_eq!(output["columns"], json!(["alpha", "zeta", "beta", "mid"]));
+    assert_eq!(output["to"], json!(dir.path().join("out.csv")));
+}
+
+#[tokio::test]
+async fn test_conversions_honor_the_csv_dialect() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("in.tsv"), "'a\tb'\t1\nc\t2\t3\n").unwrap();
+
+    let output = executor
+        .execute(&task("csv_to_json", json!({
+            "from": "in.tsv",
+            "to": "out.json",
+            "delimiter": "\t",
+            "quote": "'",
+            "has_headers": false,
+            "flexible": true,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["rows"], 2);
+    assert_eq!(output["from"], json!(dir.path().join("in.tsv")));
+    let written: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join("out.json")).unwrap()).unwrap();
+    assert_eq!(written, json!([
+        { "col_0": "a\tb", "col_1": "1" },
+        { "col_0": "c", "col_1": "2", "col_2": "3" },
+    ]));
+
+    executor
+        .execute(&task("json_to_csv", json!({
+            "from": "out.json",
+            "to": "back.csv",
+            "columns": ["col_0", "col_1"],
+            "delimiter": ";",
+            "has_headers": false,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("back.csv")).unwrap(), "a\tb;1\nc;2\n");
+
+    let err = executor
+        .execute(&task("csv_to_json", json!({ "from": "in.tsv", "to": "x.json", "delimiter": "é" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+}
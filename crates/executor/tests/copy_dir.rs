@@ -0,0 +1,108 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_copy_dir_copies_tree_and_refuses_overwrite() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir_all(dir.path().join("src/nested/deep")).unwrap();
+    std::fs::create_dir_all(dir.path().join("src/empty")).unwrap();
+    std::fs::write(dir.path().join("src/a.txt"), "hello").unwrap();
+    std::fs::write(dir.path().join("src/nested/b.txt"), "abc").unwrap();
+    std::fs::write(dir.path().join("src/nested/deep/c.txt"), "xy").unwrap();
+
+    let output = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "out/copy" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["files"], 3);
+    assert_eq!(output["bytes"], 10);
+    assert_eq!(output["dirs"], 4);
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/copy/nested/deep/c.txt")).unwrap(), "xy");
+    assert!(dir.path().join("out/copy/empty").is_dir());
+
+    // Nothing is written when any destination file already exists
+    std::fs::write(dir.path().join("src/new.txt"), "new").unwrap();
+    std::fs::write(dir.path().join("src/a.txt"), "changed").unwrap();
+    let err = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "out/copy" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("already exists")));
+    assert!(!dir.path().join("out/copy/new.txt").exists());
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/copy/a.txt")).unwrap(), "hello");
+
+    executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "out/copy", "overwrite": true })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/copy/a.txt")).unwrap(), "changed");
+
+    let err = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "src/inner" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_copy_dir_symlink_modes() {
+    let (dir, executor) = temp_executor();
+    let outside = tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::create_dir_all(dir.path().join("shared")).unwrap();
+    std::fs::write(dir.path().join("shared/s.txt"), "shared").unwrap();
+    std::fs::write(dir.path().join("src/a.txt"), "a").unwrap();
+    std::os::unix::fs::symlink("a.txt", dir.path().join("src/link.txt")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("shared"), dir.path().join("src/shared")).unwrap();
+
+    // Default: links are recreated, not followed
+    let output = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "links" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["files"], 1);
+    assert_eq!(output["symlinks"], 2);
+    let link = std::fs::read_link(dir.path().join("links/link.txt")).unwrap();
+    assert_eq!(link, std::path::PathBuf::from("a.txt"));
+
+    let output = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "skipped", "skip_symlinks": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["skipped_symlinks"], 2);
+    assert!(!dir.path().join("skipped/link.txt").exists());
+
+    let output = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "followed", "follow_symlinks": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["files"], 3);
+    assert_eq!(output["symlinks"], 0);
+    let copied = dir.path().join("followed/shared/s.txt");
+    assert!(!std::fs::symlink_metadata(dir.path().join("followed/shared")).unwrap().is_symlink());
+    assert_eq!(std::fs::read_to_string(copied).unwrap(), "shared");
+
+    // Following never pulls in content from outside the base path
+    std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+    std::os::unix::fs::symlink(outside.path().join("secret.txt"), dir.path().join("src/secret.txt")).unwrap();
+    let err = executor
+        .execute(&task("copy_dir", json!({ "from": "src", "to": "escaped", "follow_symlinks": true })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(_)));
+    assert!(!dir.path().join("escaped").exists());
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::temp_executor;
+use local_automation_common::Task;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+
+fn copy_large(params: serde_json::Value) -> Task {
+    Task::new("file".to_string(), "copy_large".to_string(), params)
+}
+
+fn sample_data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+#[tokio::test]
+async fn test_copy_large_fresh_copy() {
+    let (dir, executor) = temp_executor();
+    let data = sample_data(300_000);
+    std::fs::write(dir.path().join("src.bin"), &data).unwrap();
+
+    let result = executor
+        .execute(&copy_large(json!({ "from": "src.bin", "to": "dst.bin", "chunk_size": 4096 })))
+        .await
+        .unwrap();
+    assert!(result.success);
+    let output = result.output.unwrap();
+    assert_eq!(output["total_bytes"], 300_000);
+    assert_eq!(output["bytes_copied"], 300_000);
+    assert_eq!(output["resumed_from"], 0);
+    assert_eq!(output["verified"], true);
+    assert_eq!(std::fs::read(dir.path().join("dst.bin")).unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_copy_large_resumes_interrupted_copy() {
+    let (dir, executor) = temp_executor();
+    let data = sample_data(300_000);
+    std::fs::write(dir.path().join("src.bin"), &data).unwrap();
+    // Simulate a copy that died at ~90%
+    std::fs::write(dir.path().join("dst.bin"), &data[..270_000]).unwrap();
+
+    let output = executor
+        .execute(&copy_large(json!({ "from": "src.bin", "to": "dst.bin", "chunk_size": 4096 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["resumed_from"], 270_000);
+    assert_eq!(output["bytes_copied"], 30_000);
+    assert_eq!(std::fs::read(dir.path().join("dst.bin")).unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_copy_large_restarts_on_mismatched_prefix() {
+    let (dir, executor) = temp_executor();
+    let data = sample_data(50_000);
+    std::fs::write(dir.path().join("src.bin"), &data).unwrap();
+    let mut corrupted = data[..20_000].to_vec();
+    corrupted[10] ^= 0xff;
+    std::fs::write(dir.path().join("dst.bin"), &corrupted).unwrap();
+
+    let output = executor
+        .execute(&copy_large(json!({ "from": "src.bin", "to": "dst.bin" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["resumed_from"], 0);
+    assert_eq!(output["bytes_copied"], 50_000);
+    assert_eq!(std::fs::read(dir.path().join("dst.bin")).unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_copy_large_replaces_longer_destination() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("src.bin"), b"short").unwrap();
+    std::fs::write(dir.path().join("dst.bin"), b"a much longer stale file").unwrap();
+
+    executor
+        .execute(&copy_large(json!({ "from": "src.bin", "to": "dst.bin" })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(dir.path().join("dst.bin")).unwrap(), b"short");
+}
+
+#[tokio::test]
+async fn test_copy_large_preserves_mtime() {
+    let (dir, executor) = temp_executor();
+    let src = dir.path().join("src.bin");
+    std::fs::write(&src, sample_data(1000)).unwrap();
+    let old = SystemTime::now() - Duration::from_secs(86_400);
+    std::fs::File::options().write(true).open(&src).unwrap().set_modified(old).unwrap();
+
+    executor
+        .execute(&copy_large(json!({ "from": "src.bin", "to": "dst.bin", "preserve_mtime": true })))
+        .await
+        .unwrap();
+    let copied = std::fs::metadata(dir.path().join("dst.bin")).unwrap().modified().unwrap();
+    assert_eq!(copied, std::fs::metadata(&src).unwrap().modified().unwrap());
+}
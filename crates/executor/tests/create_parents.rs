@@ -0,0 +1,70 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_create_parents_for_each_write() {
+    let (dir, executor) = temp_executor();
+
+    let cases = [
+        ("write", json!({ "path": "a/b/out.txt", "content": "hi", "create_parents": true })),
+        ("write", json!({ "path": "c/out.txt", "content": "hi", "atomic": true, "create_parents": true })),
+        ("write_json", json!({ "path": "d/e/out.json", "data": { "k": 1 }, "create_parents": true })),
+        ("write_csv", json!({ "path": "f/out.csv", "headers": ["a"], "rows": [["1"]], "create_parents": true })),
+        ("write_bytes", json!({ "path": "g/h/out.bin", "content": "AAE=", "create_parents": true })),
+    ];
+    for (operation, params) in cases {
+        let path = params["path"].as_str().unwrap().to_string();
+        let output = executor.execute(&task(operation, params)).await.unwrap().output.unwrap();
+        assert_eq!(output["created_parents"], true, "{}", operation);
+        assert!(dir.path().join(&path).is_file(), "{}", path);
+    }
+
+    // Parents already there: nothing to create
+    let output = executor
+        .execute(&task("write", json!({ "path": "a/b/again.txt", "content": "hi", "create_parents": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["created_parents"], false);
+}
+
+#[tokio::test]
+async fn test_missing_parents_fail_by_default() {
+    let (dir, executor) = temp_executor();
+
+    let err = executor
+        .execute(&task("write", json!({ "path": "missing/out.txt", "content": "hi" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::NotFound), "{:?}", err);
+    assert!(!dir.path().join("missing").exists());
+
+    let output = executor
+        .execute(&task("write", json!({ "path": "top.txt", "content": "hi" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["created_parents"], false);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_create_parents_stays_under_base_path() {
+    let (dir, executor) = temp_executor();
+    let outside = tempdir().unwrap();
+    std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+
+    let err = executor
+        .execute(&task("write", json!({ "path": "link/new/out.txt", "content": "hi", "create_parents": true })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(_)), "{:?}", err);
+    assert!(!outside.path().join("new").exists());
+}
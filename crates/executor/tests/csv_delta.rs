@@ -0,0 +1,125 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::{Executor, StateStore};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn rows(path: &std::path::Path) -> Vec<String> {
+    let mut lines: Vec<String> = std::fs::read_to_string(path).unwrap().lines().map(str::to_string).collect();
+    lines[1..].sort();
+    lines
+}
+
+#[tokio::test]
+async fn test_adds_removes_and_modifications() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("yesterday.csv"), "id,region,name,qty\n1,eu,apple,3\n2,eu,pear,5\n3,us,plum,1\n4,us,fig,9\n").unwrap();
+    // Columns in a different order, one row changed, one removed, one added
+    std::fs::write(dir.path().join("today.csv"), "region,id,qty,name\nus,4,9,fig\neu,1,4,apple\nus,5,2,kiwi\neu,2,5,pear\n").unwrap();
+
+    let output = executor
+        .execute(&task("csv_delta", json!({
+            "current": "today.csv",
+            "previous": "yesterday.csv",
+            "key_columns": ["region", "id"],
+            "dest": { "added": "added.csv", "removed": "removed.csv", "modified": "modified.csv" },
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["added"], 1);
+    assert_eq!(output["removed"], 1);
+    assert_eq!(output["modified"], 1);
+    assert_eq!(output["unchanged"], 2);
+
+    assert_eq!(rows(&dir.path().join("added.csv")), ["region,id,qty,name", "us,5,2,kiwi"]);
+    assert_eq!(rows(&dir.path().join("removed.csv")), ["region,id,qty,name", "us,3,1,plum"]);
+    assert_eq!(rows(&dir.path().join("modified.csv")), [
+        "region,id,old_qty,new_qty,old_name,new_name", "eu,1,3,4,apple,apple",
+    ]);
+
+    executor
+        .execute(&task("csv_delta", json!({
+            "current": "today.csv",
+            "previous": "yesterday.csv",
+            "key_columns": ["id"],
+            "modified_format": "changes",
+            "dest": { "modified": "changes.csv" },
+        })))
+        .await
+        .unwrap();
+    let mut reader = csv::Reader::from_path(dir.path().join("changes.csv")).unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[0], "1");
+    let changes: Value = serde_json::from_str(&record[1]).unwrap();
+    assert_eq!(changes, json!({ "qty": { "old": "3", "new": "4" } }));
+}
+
+#[tokio::test]
+async fn test_duplicate_key_policies() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("old.csv"), "id,v\n1,a\n2,b\n").unwrap();
+    std::fs::write(dir.path().join("new.csv"), "id,v\n1,a\n2,x\n2,b\n").unwrap();
+    let params = |policy: &str| json!({
+        "current": "new.csv", "previous": "old.csv", "key_columns": ["id"], "duplicates": policy,
+        "dest": { "added": "added.csv" },
+    });
+
+    let result = executor.execute(&task("csv_delta", params("error"))).await;
+    assert!(matches!(result, Err(Error::Io(_))));
+
+    let last_wins = executor.execute(&task("csv_delta", params("last_wins"))).await.unwrap().output.unwrap();
+    assert_eq!((last_wins["added"].as_u64(), last_wins["modified"].as_u64()), (Some(0), Some(0)));
+    assert_eq!(last_wins["duplicate_keys"], 1);
+
+    // Rows are paired in file order: 2,b vs 2,x is a change and 2,b is extra
+    let emit_all = executor.execute(&task("csv_delta", params("emit_all"))).await.unwrap().output.unwrap();
+    assert_eq!(emit_all["modified"], 1);
+    assert_eq!(emit_all["added"], 1);
+    assert_eq!(rows(&dir.path().join("added.csv")), ["id,v", "2,b"]);
+}
+
+#[tokio::test]
+async fn test_spills_and_chains_runs_through_state() {
+    let dir = tempdir().unwrap();
+    let store = Arc::new(StateStore::new(dir.path().join("state.json")));
+    let executor = FileExecutor::new(dir.path().to_path_buf()).with_state_store(store.clone());
+    let dump = |name: &str, n: usize, skip: usize, bump: usize| {
+        let mut content = String::from("id,value\n");
+        for i in (0..n).rev().filter(|i| i % 10 != skip) {
+            let value = if i % 100 == bump { i + 1 } else { i };
+            content.push_str(&format!("{},{}\n", i, value));
+        }
+        std::fs::write(dir.path().join(name), content).unwrap();
+    };
+    let params = |current: &str| json!({
+        "current": current, "key_columns": ["id"], "state_key": "orders.last_dump",
+        "memory_limit_bytes": 4096, "dest": { "removed": "removed.csv" },
+    });
+
+    dump("day1.csv", 2000, 3, 1000);
+    let first = executor.execute(&task("csv_delta", params("day1.csv"))).await.unwrap().output.unwrap();
+    assert_eq!(first["previous"], Value::Null);
+    assert_eq!(first["added"], 1800);
+
+    dump("day2.csv", 2000, 7, 42);
+    let second = executor.execute(&task("csv_delta", params("day2.csv"))).await.unwrap().output.unwrap();
+    assert_eq!(second["previous"], "day1.csv");
+    assert!(second["runs_spilled"].as_u64().unwrap() > 2);
+    assert_eq!(second["added"], 200);
+    assert_eq!(second["removed"], 200);
+    assert_eq!(second["modified"], 20);
+    assert_eq!(store.get("orders.last_dump").await.unwrap(), Some(json!("day2.csv")));
+    assert_eq!(rows(&dir.path().join("removed.csv")).len(), 201);
+
+    // Scratch runs are cleaned up
+    let leftovers = std::fs::read_dir(dir.path()).unwrap()
+        .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().starts_with(".delta-"))
+        .count();
+    assert_eq!(leftovers, 0);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_read_csv_semicolon_european_export() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("export.csv"),
+        "Datum;Betrag;Notiz\n01.02.2024;12,50;\"Miete; Februar\"\n02.02.2024;3,10;Kaffee\n",
+    ).unwrap();
+
+    let output = executor
+        .execute(&task("read_csv", json!({ "path": "export.csv", "delimiter": ";" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["headers"], json!(["Datum", "Betrag", "Notiz"]));
+    assert_eq!(output["rows"][0], json!(["01.02.2024", "12,50", "Miete; Februar"]));
+    assert_eq!(output["rows"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_read_csv_headerless_tsv_synthesizes_names() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.tsv"), "a\t1\nb\t2\t'x\ty'\n").unwrap();
+
+    let output = executor
+        .execute(&task("read_csv", json!({
+            "path": "data.tsv",
+            "delimiter": "\t",
+            "quote": "'",
+            "has_headers": false,
+            "flexible": true,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["headers"], json!(["col_0", "col_1", "col_2"]));
+    assert_eq!(output["rows"], json!([["a", "1"], ["b", "2", "x\ty"]]));
+
+    // Without `flexible` the ragged row is an error
+    let err = executor
+        .execute(&task("read_csv", json!({ "path": "data.tsv", "delimiter": "\t", "has_headers": false })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::InvalidData));
+}
+
+#[tokio::test]
+async fn test_write_csv_with_dialect_round_trips() {
+    let (dir, executor) = temp_executor();
+
+    executor
+        .execute(&task("write_csv", json!({
+            "path": "out.csv",
+            "headers": ["name", "amount"],
+            "rows": [["Müller; GmbH", 12.5], ["Ørsted", 3]],
+            "delimiter": ";",
+            "locale": "de",
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("out.csv")).unwrap(),
+        "name;amount\n\"Müller; GmbH\";12,5\nØrsted;3\n"
+    );
+
+    let output = executor
+        .execute(&task("read_csv", json!({ "path": "out.csv", "delimiter": ";" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["rows"], json!([["Müller; GmbH", "12,5"], ["Ørsted", "3"]]));
+}
+
+#[tokio::test]
+async fn test_write_csv_headerless_tsv_and_resumable() {
+    let (dir, executor) = temp_executor();
+
+    executor
+        .execute(&task("write_csv", json!({
+            "path": "plain.tsv",
+            "rows": [["a", 1], ["b", 2]],
+            "delimiter": "\t",
+            "has_headers": false,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("plain.tsv")).unwrap(), "a\t1\nb\t2\n");
+
+    let output = executor
+        .execute(&task("write_csv", json!({
+            "path": "resumed.tsv",
+            "rows": [["a", 1], ["b", 2]],
+            "delimiter": "\t",
+            "has_headers": false,
+            "resume": true,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["total_rows"], 2);
+    assert_eq!(std::fs::read_to_string(dir.path().join("resumed.tsv")).unwrap(), "a\t1\nb\t2\n");
+}
+
+#[tokio::test]
+async fn test_csv_dialect_rejects_bad_settings() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.csv"), "a\n1\n").unwrap();
+
+    for params in [
+        json!({ "path": "a.csv", "delimiter": "é" }),
+        json!({ "path": "a.csv", "delimiter": ";;" }),
+        json!({ "path": "a.csv", "delimiter": "'", "quote": "'" }),
+    ] {
+        let err = executor.execute(&task("read_csv", params)).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+    }
+
+    let err = executor
+        .execute(&task("write_csv", json!({ "path": "b.csv", "rows": [[1]] })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("headers")), "{:?}", err);
+}
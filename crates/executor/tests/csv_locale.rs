@@ -0,0 +1,101 @@
+mod common;
+
+use common::temp_executor;
+use local_automation_common::{Error, Task};
+use local_automation_executor::Executor;
+use serde_json::json;
+
+fn write_csv(params: serde_json::Value) -> Task {
+    Task::new("file".to_string(), "write_csv".to_string(), params)
+}
+
+#[tokio::test]
+async fn test_write_csv_eu_and_us_locales() {
+    let (dir, executor) = temp_executor();
+    let rows = json!([["2024-06-01", 1234.5, "Alice", true, null]]);
+
+    executor
+        .execute(&write_csv(json!({
+            "path": "eu.csv",
+            "headers": ["date", "amount", "name", "paid", "note"],
+            "rows": rows,
+            "locale": "de-DE"
+        })))
+        .await
+        .unwrap();
+    let eu = std::fs::read_to_string(dir.path().join("eu.csv")).unwrap();
+    assert_eq!(eu, "date,amount,name,paid,note\n01.06.2024,\"1234,5\",Alice,true,\n");
+
+    executor
+        .execute(&write_csv(json!({
+            "path": "us.csv",
+            "headers": ["date", "amount", "name", "paid", "note"],
+            "rows": rows,
+            "locale": "en-US"
+        })))
+        .await
+        .unwrap();
+    let us = std::fs::read_to_string(dir.path().join("us.csv")).unwrap();
+    assert_eq!(us, "date,amount,name,paid,note\n06/01/2024,1234.5,Alice,true,\n");
+}
+
+#[tokio::test]
+async fn test_explicit_settings_override_locale() {
+    let (dir, executor) = temp_executor();
+
+    executor
+        .execute(&write_csv(json!({
+            "path": "out.csv",
+            "headers": ["at", "value"],
+            "rows": [["2024-06-01T08:30:00Z", 2.5]],
+            "locale": "de-DE",
+            "date_format": "%Y/%m/%d %H:%M",
+            "decimal_separator": "."
+        })))
+        .await
+        .unwrap();
+    let content = std::fs::read_to_string(dir.path().join("out.csv")).unwrap();
+    assert_eq!(content, "at,value\n2024/06/01 08:30,2.5\n");
+}
+
+#[tokio::test]
+async fn test_decimal_comma_keeps_precision() {
+    let (dir, executor) = temp_executor();
+    let values = [0.1 + 0.2, 1e-7, 123_456_789.123_456_79, -0.5];
+
+    executor
+        .execute(&write_csv(json!({
+            "path": "precise.csv",
+            "headers": ["value"],
+            "rows": values.iter().map(|v| vec![json!(v)]).collect::<Vec<_>>(),
+            "decimal_separator": ","
+        })))
+        .await
+        .unwrap();
+
+    let mut reader = csv::Reader::from_path(dir.path().join("precise.csv")).unwrap();
+    let parsed: Vec<f64> = reader
+        .records()
+        .map(|r| r.unwrap()[0].replace(',', ".").parse().unwrap())
+        .collect();
+    assert_eq!(parsed, values);
+}
+
+#[tokio::test]
+async fn test_unknown_locale_and_bad_date_format() {
+    let (_dir, executor) = temp_executor();
+
+    let unknown = executor
+        .execute(&write_csv(json!({
+            "path": "x.csv", "headers": ["a"], "rows": [], "locale": "xx-YY"
+        })))
+        .await;
+    assert!(matches!(unknown, Err(Error::InvalidConfig(_))));
+
+    let bad_format = executor
+        .execute(&write_csv(json!({
+            "path": "x.csv", "headers": ["a"], "rows": [], "date_format": "%Q"
+        })))
+        .await;
+    assert!(matches!(bad_format, Err(Error::InvalidConfig(_))));
+}
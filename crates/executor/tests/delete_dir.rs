@@ -0,0 +1,94 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::{FileExecutor, ImpactLimits};
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_delete_dir_flags() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir_all(dir.path().join("empty")).unwrap();
+    std::fs::create_dir_all(dir.path().join("full/nested")).unwrap();
+    std::fs::write(dir.path().join("full/nested/a.txt"), "a").unwrap();
+    std::fs::write(dir.path().join("file.txt"), "f").unwrap();
+
+    let output = executor
+        .execute(&task("delete_dir", json!({ "path": "empty" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["deleted"], true);
+    assert!(!dir.path().join("empty").exists());
+
+    let err = executor
+        .execute(&task("delete_dir", json!({ "path": "full" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("not empty")), "{:?}", err);
+    assert!(dir.path().join("full/nested/a.txt").exists());
+
+    executor
+        .execute(&task("delete_dir", json!({ "path": "full", "recursive": true })))
+        .await
+        .unwrap();
+    assert!(!dir.path().join("full").exists());
+
+    let err = executor
+        .execute(&task("delete_dir", json!({ "path": "full" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    let output = executor
+        .execute(&task("delete_dir", json!({ "path": "full", "ignore_missing": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["deleted"], false);
+
+    let err = executor
+        .execute(&task("delete_dir", json!({ "path": "file.txt" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+
+    for path in [".", "", "./"] {
+        let err = executor
+            .execute(&task("delete_dir", json!({ "path": path, "recursive": true })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::PermissionDenied(_)), "{:?}", path);
+    }
+    assert!(dir.path().join("file.txt").exists());
+}
+
+#[tokio::test]
+async fn test_recursive_delete_dir_respects_impact_limits() {
+    let dir = tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("logs")).unwrap();
+    for i in 0..5 {
+        std::fs::write(dir.path().join(format!("logs/{}.log", i)), "x").unwrap();
+    }
+    let executor = FileExecutor::new(dir.path().to_path_buf())
+        .with_impact_limits(ImpactLimits { max_files: Some(3), ..Default::default() });
+
+    let err = executor
+        .execute(&task("delete_dir", json!({ "path": "logs", "recursive": true })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(ref m) if m.contains("delete_dir would remove 5 files")));
+
+    executor
+        .execute(&task("delete_dir", json!({
+            "path": "logs",
+            "recursive": true,
+            "confirm_impact": { "max_files": 5, "max_bytes": 5 },
+        })))
+        .await
+        .unwrap();
+    assert!(!dir.path().join("logs").exists());
+}
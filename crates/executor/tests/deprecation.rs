@@ -0,0 +1,128 @@
+mod common;
+
+use common::{executor_task, task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::deprecation::{attach_warnings, resolve, DeprecatedParam, OperationAlias};
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::{ExecutionResult, Executor, SequenceExecutor, StateStore};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_alias_dispatch_warns() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+    let old = executor
+        .execute(&task("list", json!({ "path": "." })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(old["files"], json!(["a.txt"]));
+    let warnings = old["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["kind"], "operation");
+    assert_eq!(warnings[0]["name"], "list");
+    assert_eq!(warnings[0]["replacement"], "list_dir");
+    // Deprecated as of the release after the one that added list_dir
+    assert_eq!(warnings[0]["since"], "0.2.0");
+
+    let new = executor
+        .execute(&task("list_dir", json!({ "path": "." })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(new, json!({ "files": ["a.txt"] }));
+}
+
+const PARAMS: &[DeprecatedParam] = &[
+    DeprecatedParam { operation: "next_batch", param: "n", since: "0.2.0", replacement: Some("count") },
+];
+
+#[tokio::test]
+async fn test_param_rename_is_transformed() {
+    let dir = tempdir().unwrap();
+    let sequence = SequenceExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+
+    let old = sequence
+        .execute(&executor_task("sequence", "next_batch", json!({ "name": "ids", "n": 2 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(old["values"], json!([1, 2]));
+    assert_eq!(old["warnings"][0]["kind"], "param");
+    assert_eq!(old["warnings"][0]["since"], "0.2.0");
+    assert_eq!(old["warnings"][0]["replacement"], "count");
+
+    // An explicit replacement wins over the deprecated spelling
+    let both = sequence
+        .execute(&executor_task("sequence", "next_batch", json!({ "name": "ids", "n": 5, "count": 1 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(both["values"], json!([3]));
+
+    let new = sequence
+        .execute(&executor_task("sequence", "next_batch", json!({ "name": "ids", "count": 1 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert!(new.get("warnings").is_none());
+}
+
+#[test]
+fn test_warnings_are_never_dropped() {
+    let (_, warnings) = resolve(&executor_task("x", "next_batch", json!({ "n": 2 })), &[], PARAMS, false).unwrap();
+    let result = |output: Value| ExecutionResult { success: true, output: Some(output), error: None };
+
+    // Warnings the operation reported come first
+    let merged = attach_warnings(result(json!({ "warnings": [{ "line": 3 }] })), warnings.clone()).unwrap();
+    let merged = merged.output.unwrap();
+    assert_eq!(merged["warnings"][0], json!({ "line": 3 }));
+    assert_eq!(merged["warnings"][1]["name"], "n");
+
+    let wrapped = attach_warnings(result(json!(42)), warnings.clone()).unwrap().output.unwrap();
+    assert_eq!(wrapped["value"], 42);
+    assert_eq!(wrapped["warnings"][0]["name"], "n");
+
+    let wrapped = attach_warnings(result(json!({ "warnings": "mine" })), warnings).unwrap().output.unwrap();
+    assert_eq!(wrapped["value"], json!({ "warnings": "mine" }));
+    assert_eq!(wrapped["warnings"][0]["name"], "n");
+}
+
+#[tokio::test]
+async fn test_strict_mode_rejects_deprecations() {
+    let dir = tempdir().unwrap();
+    let files = FileExecutor::new(dir.path().to_path_buf()).with_strict_deprecations(true);
+    let result = files.execute(&task("list", json!({ "path": "." }))).await;
+    assert!(matches!(result, Err(Error::InvalidConfig(message)) if message.contains("use 'list_dir'")));
+    files.execute(&task("list_dir", json!({ "path": "." }))).await.unwrap();
+
+    let sequence = SequenceExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))))
+        .with_strict_deprecations(true);
+    let result = sequence
+        .execute(&executor_task("sequence", "next_batch", json!({ "name": "ids", "n": 2 })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
+
+#[test]
+fn test_alias_transform_reshapes_params() {
+    fn wrap_path(params: Value) -> local_automation_common::Result<Value> {
+        Ok(json!({ "paths": [params["path"]] }))
+    }
+    let aliases = [OperationAlias { alias: "old", target: "new", deprecated_since: None, transform: Some(wrap_path) }];
+    let original = executor_task("x", "old", json!({ "path": "a.txt" }));
+
+    let (resolved, warnings) = resolve(&original, &aliases, &[], true).unwrap();
+    assert_eq!(resolved.operation, "new");
+    assert_eq!(resolved.params, json!({ "paths": ["a.txt"] }));
+    // Permanent aliases are not deprecations, even in strict mode
+    assert!(warnings.is_empty());
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn diff(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("diff", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_diff_binary() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a"), "same").unwrap();
+    std::fs::write(dir.path().join("b"), "same").unwrap();
+    std::fs::write(dir.path().join("c"), "other").unwrap();
+
+    let output = diff(&executor, json!({ "left": "a", "right": "b" })).await;
+    assert_eq!(output["identical"], true);
+    assert_eq!(output["mode"], "binary");
+    assert_eq!(output["left_sha256"], output["right_sha256"]);
+
+    let output = diff(&executor, json!({ "left": "a", "right": "c" })).await;
+    assert_eq!(output["identical"], false);
+    assert_ne!(output["left_sha256"], output["right_sha256"]);
+}
+
+#[tokio::test]
+async fn test_diff_lines() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("old.conf"), "a\nb\nc\n").unwrap();
+    std::fs::write(dir.path().join("new.conf"), "a\nB\nc\nd\n").unwrap();
+
+    let output = diff(&executor, json!({ "left": "old.conf", "right": "new.conf", "mode": "lines" })).await;
+    assert_eq!(output["identical"], false);
+    assert_eq!(output["lines_added"], 2);
+    assert_eq!(output["lines_removed"], 1);
+    assert_eq!(output["truncated"], false);
+    let text = output["diff"].as_str().unwrap();
+    assert!(text.starts_with("--- old.conf\n+++ new.conf\n"), "{}", text);
+    assert!(text.contains("-b\n+B\n"), "{}", text);
+
+    let output = diff(&executor, json!({ "left": "old.conf", "right": "new.conf", "mode": "lines", "max_lines": 2 })).await;
+    assert_eq!(output["truncated"], true);
+    assert_eq!(output["diff"].as_str().unwrap().lines().count(), 2);
+
+    let output = diff(&executor, json!({ "left": "old.conf", "right": "old.conf", "mode": "lines" })).await;
+    assert_eq!(output["identical"], true);
+    assert_eq!(output["diff"], "");
+}
+
+#[tokio::test]
+async fn test_diff_json() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.json"), r#"{"port": 80, "hosts": ["x"]}"#).unwrap();
+    std::fs::write(dir.path().join("b.json"), "{\n  \"hosts\": [\"x\"],\n  \"port\": 80\n}").unwrap();
+    std::fs::write(dir.path().join("c.json"), r#"{"port": 8080, "hosts": ["x"], "tls": true}"#).unwrap();
+
+    // Formatting and key order don't matter
+    let output = diff(&executor, json!({ "left": "a.json", "right": "b.json", "mode": "json" })).await;
+    assert_eq!(output["identical"], true);
+    assert_eq!(output["diff"], json!([]));
+
+    let output = diff(&executor, json!({ "left": "a.json", "right": "c.json", "mode": "json" })).await;
+    assert_eq!(output["identical"], false);
+    let mut ops = output["diff"].as_array().unwrap().clone();
+    ops.sort_by_key(|op| op["path"].as_str().unwrap().to_string());
+    assert_eq!(ops, vec![
+        json!({ "op": "replace", "path": "/port", "value": 8080 }),
+        json!({ "op": "add", "path": "/tls", "value": true }),
+    ]);
+}
+
+#[tokio::test]
+async fn test_diff_missing_files() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a"), "x").unwrap();
+
+    let err = executor
+        .execute(&task("diff", json!({ "left": "a", "right": "gone" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::NotFound), "{:?}", err);
+
+    let output = diff(&executor, json!({ "left": "a", "right": "gone", "allow_missing": true })).await;
+    assert_eq!(output["identical"], false);
+    assert_eq!(output["missing"], json!(["right"]));
+    assert_eq!(output["diff"], Value::Null);
+
+    let output = diff(&executor, json!({ "left": "none", "right": "gone", "allow_missing": true, "mode": "lines" })).await;
+    assert_eq!(output["identical"], true);
+    assert_eq!(output["missing"], json!(["left", "right"]));
+}
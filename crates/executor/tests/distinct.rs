@@ -0,0 +1,208 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Tracks live and peak heap usage so the memory ceiling can be checked
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(now, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn read_lines(path: &std::path::Path) -> Vec<String> {
+    std::fs::read_to_string(path).unwrap().lines().map(|l| l.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_exact_matches_brute_force_across_files() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("month")).unwrap();
+    let mut expected = BTreeSet::new();
+    for day in 0..5 {
+        let mut csv = String::from("customer_id,amount\n");
+        for i in 0..200 {
+            let id = format!("c{}", (day * 37 + i * 13) % 150);
+            expected.insert(id.clone());
+            csv.push_str(&format!("{},{}\n", id, i));
+        }
+        // A row without an id is reported as missing, not as a value
+        csv.push_str(",5\n");
+        std::fs::write(dir.path().join(format!("month/day{}.csv", day)), csv).unwrap();
+    }
+
+    // A tiny ceiling forces many spilled runs and a multi-pass merge
+    let output = executor
+        .execute(&task("distinct", json!({
+            "pattern": "month/*.csv",
+            "column": "customer_id",
+            "dest": "ids.txt",
+            "memory_limit_bytes": 200,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+
+    let expected: Vec<String> = expected.into_iter().collect();
+    assert_eq!(read_lines(&dir.path().join("ids.txt")), expected);
+    assert_eq!(output["distinct"], expected.len());
+    assert_eq!(output["exact"], true);
+    assert_eq!(output["rows_scanned"], 1005);
+    assert_eq!(output["missing"], 5);
+    assert!(output["runs_spilled"].as_u64().unwrap() > 64);
+    assert_eq!(output["sources"].as_array().unwrap().len(), 5);
+    // Scratch runs are cleaned up
+    let leftovers: Vec<_> = std::fs::read_dir(dir.path()).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|n| n.starts_with(".distinct-"))
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+}
+
+#[tokio::test]
+async fn test_ndjson_field_and_pointer() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("events.ndjson"),
+        "{\"user\":{\"id\":2},\"kind\":\"a\"}\n{\"user\":{\"id\":1},\"kind\":\"b\"}\n\n{\"user\":{\"id\":2}}\n",
+    )
+    .unwrap();
+
+    let output = executor
+        .execute(&task("distinct", json!({
+            "sources": ["events.ndjson"], "column": "/user/id", "dest": "users.txt",
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(read_lines(&dir.path().join("users.txt")), vec!["1", "2"]);
+    assert_eq!(output["missing"], 0);
+
+    let output = executor
+        .execute(&task("distinct", json!({
+            "sources": ["events.ndjson"], "column": "kind", "dest": "kinds.txt",
+            "mode": "approximate",
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(read_lines(&dir.path().join("kinds.txt")), vec!["a", "b"]);
+    assert_eq!(output["missing"], 1);
+    assert_eq!(output["exact"], true);
+}
+
+#[tokio::test]
+async fn test_approximate_never_duplicates() {
+    let (dir, executor) = temp_executor();
+    let mut csv = String::from("id\n");
+    for i in 0..20_000 {
+        csv.push_str(&format!("v{}\n", i % 5_000));
+    }
+    std::fs::write(dir.path().join("data.csv"), csv).unwrap();
+
+    let output = executor
+        .execute(&task("distinct", json!({
+            "sources": ["data.csv"], "column": "id", "dest": "ids.txt",
+            "mode": "approximate", "sample_size": 100, "expected_items": 5_000,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+
+    let lines = read_lines(&dir.path().join("ids.txt"));
+    let unique: BTreeSet<&String> = lines.iter().collect();
+    assert_eq!(unique.len(), lines.len());
+    assert!(lines.len() > 4_800 && lines.len() <= 5_000, "{}", lines.len());
+    assert_eq!(output["exact"], false);
+    assert!(output["bloom"]["bits"].as_u64().unwrap() > 0);
+    let estimate = output["distinct_estimate"].as_u64().unwrap();
+    assert!((4_500..5_500).contains(&estimate), "{}", estimate);
+}
+
+#[tokio::test]
+async fn test_unknown_column_is_rejected() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "a\n1\n").unwrap();
+
+    let result = executor
+        .execute(&task("distinct", json!({ "sources": ["data.csv"], "column": "b", "dest": "out.txt" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    assert!(!dir.path().join("out.txt").exists());
+
+    // Every source is checked before an existing dest is truncated
+    std::fs::write(dir.path().join("more.csv"), "b\n2\n").unwrap();
+    std::fs::write(dir.path().join("out.txt"), "previous\n").unwrap();
+    let result = executor
+        .execute(&task("distinct", json!({ "sources": ["more.csv", "data.csv"], "column": "b", "dest": "out.txt" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    assert_eq!(std::fs::read_to_string(dir.path().join("out.txt")).unwrap(), "previous\n");
+}
+
+#[tokio::test]
+async fn test_large_input_respects_memory_limit() {
+    let (dir, executor) = temp_executor();
+    let path = dir.path().join("large.csv");
+    let distinct = 300_000;
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        writeln!(file, "customer_id,payload").unwrap();
+        for i in 0..(distinct * 2) {
+            // Every id appears twice, far apart
+            writeln!(file, "customer-{:012}-{},{}", (i * 7919) % distinct, "k".repeat(24), i).unwrap();
+        }
+    }
+
+    let limit = 2 * 1024 * 1024;
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let output = executor
+        .execute(&task("distinct", json!({
+            "sources": ["large.csv"], "column": "customer_id", "dest": "ids.txt",
+            "memory_limit_bytes": limit,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    // Holding every id in memory would take well over 20 MB
+    assert!(peak < 3 * limit, "peak {} bytes with a {} byte limit", peak, limit);
+    assert_eq!(output["distinct"], distinct);
+    assert!(output["runs_spilled"].as_u64().unwrap() > 1);
+
+    let lines = read_lines(&dir.path().join("ids.txt"));
+    assert_eq!(lines.len(), distinct);
+    assert!(lines.windows(2).all(|w| w[0] < w[1]));
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::time::Duration;
+use tempfile::tempdir;
+
+async fn begin(executor: &FileExecutor, path: &str) -> String {
+    executor
+        .execute(&task("edit_begin", json!({ "path": path })))
+        .await
+        .unwrap()
+        .output
+        .unwrap()["session_id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+fn working_copies(dir: &std::path::Path) -> Vec<String> {
+    std::fs::read_dir(dir).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|n| n.contains(".edit-"))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_edits_reach_target_only_on_commit() {
+    let (dir, executor) = temp_executor();
+    let original = "[server]\nhost = old\nport=80\n\n[log]\nlevel = info\nline five\nline six\n";
+    std::fs::write(dir.path().join("app.ini"), original).unwrap();
+    let id = begin(&executor, "app.ini").await;
+
+    let applied = executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [
+                { "op": "ini_set", "section": "server", "key": "host", "value": "new" },
+                { "op": "ini_set", "section": "server", "key": "port", "value": "8080" },
+                { "op": "ini_set", "section": "log", "key": "file", "value": "app.log" },
+                { "op": "ini_set", "section": "extra", "key": "on", "value": "yes" },
+            ],
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(applied["applied"][2]["result"]["action"], "added");
+    executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [
+                { "op": "regex_replace", "pattern": "line (\\w+)", "replacement": "row $1", "limit": 1 },
+                { "op": "delete_lines", "start": 8, "end": 8 },
+                { "op": "insert_lines", "at": 1, "lines": ["; generated"] },
+            ],
+        })))
+        .await
+        .unwrap();
+
+    // Target untouched until commit
+    assert_eq!(std::fs::read_to_string(dir.path().join("app.ini")).unwrap(), original);
+
+    let committed = executor
+        .execute(&task("edit_commit", json!({ "session_id": id, "backup": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(committed["edits"].as_array().unwrap().len(), 7);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("app.ini")).unwrap(),
+        "; generated\n[server]\nhost = new\nport=8080\n\n[log]\nlevel = info\nrow five\nfile = app.log\n\n[extra]\non = yes\n"
+    );
+    assert_eq!(std::fs::read_to_string(dir.path().join("app.ini.bak")).unwrap(), original);
+    assert!(working_copies(dir.path()).is_empty());
+
+    // The path is free again
+    begin(&executor, "app.ini").await;
+}
+
+#[tokio::test]
+async fn test_json_patch_and_failed_edit_keep_session_intact() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("config.json"), r#"{"a": 1, "list": [1]}"#).unwrap();
+    let id = begin(&executor, "config.json").await;
+
+    executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [{ "op": "json_patch", "patch": [
+                { "op": "replace", "path": "/a", "value": 2 },
+                { "op": "add", "path": "/list/-", "value": 2 },
+            ]}],
+        })))
+        .await
+        .unwrap();
+
+    // The second edit fails, so the first one in the same batch is not kept either
+    let result = executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [
+                { "op": "json_patch", "patch": [{ "op": "remove", "path": "/a" }] },
+                { "op": "json_patch", "patch": [{ "op": "remove", "path": "/missing" }] },
+            ],
+        })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+    let committed = executor
+        .execute(&task("edit_commit", json!({ "session_id": id })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(committed["edits"].as_array().unwrap().len(), 1);
+    let value: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("config.json")).unwrap()).unwrap();
+    assert_eq!(value, json!({ "a": 2, "list": [1, 2] }));
+}
+
+#[tokio::test]
+async fn test_abort_discards_working_copy() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("notes.txt"), "keep me\n").unwrap();
+    let id = begin(&executor, "notes.txt").await;
+
+    executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [{ "op": "regex_replace", "pattern": "keep", "replacement": "lose" }],
+        })))
+        .await
+        .unwrap();
+    let aborted = executor
+        .execute(&task("edit_abort", json!({ "session_id": id })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(aborted["aborted"], true);
+    assert_eq!(std::fs::read_to_string(dir.path().join("notes.txt")).unwrap(), "keep me\n");
+    assert!(working_copies(dir.path()).is_empty());
+
+    let result = executor
+        .execute(&task("edit_commit", json!({ "session_id": id })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_concurrent_session_is_rejected() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "x\n").unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    let second = FileExecutor::new(dir.path().to_path_buf());
+    let id = begin(&first, "data.txt").await;
+
+    for executor in [&first, &second] {
+        let result = executor
+            .execute(&task("edit_begin", json!({ "path": "data.txt" })))
+            .await;
+        assert!(matches!(&result, Err(Error::InvalidConfig(m)) if m.contains("Another edit session")));
+    }
+
+    first
+        .execute(&task("edit_abort", json!({ "session_id": id })))
+        .await
+        .unwrap();
+    begin(&second, "data.txt").await;
+}
+
+#[tokio::test]
+async fn test_uncommitted_session_times_out() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.txt"), "x\n").unwrap();
+
+    let id = executor
+        .execute(&task("edit_begin", json!({ "path": "data.txt", "timeout_secs": 1 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap()["session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(working_copies(dir.path()).len(), 1);
+
+    tokio::time::sleep(Duration::from_millis(1300)).await;
+    assert!(working_copies(dir.path()).is_empty());
+    let result = executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [{ "op": "delete_lines", "start": 1, "end": 1 }],
+        })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    // The lock went with it
+    begin(&executor, "data.txt").await;
+}
+
+#[tokio::test]
+async fn test_failed_commit_keeps_session() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("conf")).unwrap();
+    std::fs::write(dir.path().join("conf/app.txt"), "old\n").unwrap();
+    let id = begin(&executor, "conf/app.txt").await;
+    executor
+        .execute(&task("edit_apply", json!({
+            "session_id": id,
+            "edits": [{ "op": "regex_replace", "pattern": "old", "replacement": "new" }],
+        })))
+        .await
+        .unwrap();
+
+    // A non-empty directory in the way makes the rename fail
+    std::fs::remove_file(dir.path().join("conf/app.txt")).unwrap();
+    std::fs::create_dir_all(dir.path().join("conf/app.txt/inner")).unwrap();
+    let result = executor.execute(&task("edit_commit", json!({ "session_id": id }))).await;
+    assert!(matches!(result, Err(Error::Io(_))), "{:?}", result);
+    assert_eq!(working_copies(&dir.path().join("conf")).len(), 1);
+
+    std::fs::remove_dir_all(dir.path().join("conf/app.txt")).unwrap();
+    std::fs::write(dir.path().join("conf/app.txt"), "old\n").unwrap();
+    let committed = executor
+        .execute(&task("edit_commit", json!({ "session_id": id })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(committed["edits"].as_array().unwrap().len(), 1);
+    assert_eq!(std::fs::read_to_string(dir.path().join("conf/app.txt")).unwrap(), "new\n");
+
+    // No lock files are left beside the edited file
+    let names: Vec<String> = std::fs::read_dir(dir.path().join("conf")).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, vec!["app.txt"]);
+}
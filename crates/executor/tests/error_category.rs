@@ -0,0 +1,114 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::{Error, ErrorCategory, Task};
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_file_executor_failures_map_to_categories() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("bad.json"), "{ nope").unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+    std::fs::create_dir(dir.path().join("full")).unwrap();
+    std::fs::write(dir.path().join("full/x"), "x").unwrap();
+
+    let cases = [
+        ("read", json!({ "path": "missing.txt" }), ErrorCategory::NotFound),
+        ("read", json!({ "path": "../etc/passwd" }), ErrorCategory::Auth),
+        ("read", json!({}), ErrorCategory::Validation),
+        ("read_json", json!({ "path": "bad.json" }), ErrorCategory::Validation),
+        ("read_xml", json!({ "path": "a.txt" }), ErrorCategory::Validation),
+        ("delete_dir", json!({ "path": "full" }), ErrorCategory::Validation),
+        ("no_such_operation", json!({}), ErrorCategory::Validation),
+    ];
+    for (operation, params, expected) in cases {
+        let err = executor.execute(&task(operation, params.clone())).await.unwrap_err();
+        assert_eq!(err.category(), expected, "{} {} -> {:?}", operation, params, err);
+    }
+
+    let wrong_executor = Task::new("http".to_string(), "read".to_string(), json!({}));
+    assert_eq!(executor.execute(&wrong_executor).await.unwrap_err().category(), ErrorCategory::Validation);
+}
+
+#[test]
+fn test_error_categories_are_a_closed_registry() {
+    for category in ErrorCategory::ALL {
+        assert_eq!(category.as_str().parse::<ErrorCategory>().unwrap(), *category);
+        assert_eq!(serde_json::to_value(category).unwrap(), json!(category.as_str()));
+    }
+    let err = "transient-io".parse::<ErrorCategory>().unwrap_err();
+    assert!(err.to_string().contains("transient_io"), "{}", err);
+
+    let io = |kind| Error::Io(std::io::Error::from(kind));
+    assert_eq!(io(std::io::ErrorKind::ConnectionReset).category(), ErrorCategory::TransientIo);
+    assert_eq!(io(std::io::ErrorKind::TimedOut).category(), ErrorCategory::Timeout);
+    assert_eq!(io(std::io::ErrorKind::StorageFull).category(), ErrorCategory::Budget);
+    assert_eq!(io(std::io::ErrorKind::Other).category(), ErrorCategory::Unknown);
+    assert_eq!(Error::Timeout.category(), ErrorCategory::Timeout);
+    assert_eq!(Error::TaskNotFound("t".into()).category(), ErrorCategory::NotFound);
+}
+
+#[test]
+fn test_custom_executor_mapping_rules() {
+    fn quota(err: &Error) -> Option<ErrorCategory> {
+        match err {
+            Error::InvalidConfig(m) if m.starts_with("quota") => Some(ErrorCategory::Budget),
+            _ => None,
+        }
+    }
+    let rules: &[fn(&Error) -> Option<ErrorCategory>] = &[quota];
+    assert_eq!(Error::InvalidConfig("quota exceeded".into()).category_with(rules), ErrorCategory::Budget);
+    assert_eq!(Error::InvalidConfig("bad param".into()).category_with(rules), ErrorCategory::Validation);
+}
+
+// Collects the messages of warn-level events
+struct Warnings(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+struct Message<'a>(&'a mut String);
+
+impl tracing::field::Visit for Message<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        let _ = write!(self.0, "{}={:?} ", field.name(), value);
+    }
+}
+
+impl tracing::Subscriber for Warnings {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn event(&self, event: &tracing::Event<'_>) {
+        if *event.metadata().level() == tracing::Level::WARN {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.0.lock().unwrap().push(message);
+        }
+    }
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[test]
+fn test_unknown_category_warns() {
+    let warnings = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let io = |kind| Error::Io(std::io::Error::new(kind, "odd failure"));
+    let unmapped: &[fn(&Error) -> Option<ErrorCategory>] = &[|_| Some(ErrorCategory::Unknown)];
+
+    tracing::subscriber::with_default(Warnings(warnings.clone()), || {
+        assert_eq!(io(std::io::ErrorKind::Other).category(), ErrorCategory::Unknown);
+        assert_eq!(io(std::io::ErrorKind::NotFound).category(), ErrorCategory::NotFound);
+        assert_eq!(Error::Timeout.category_with(unmapped), ErrorCategory::Unknown);
+        assert_eq!(Error::Timeout.category_with(&[]), ErrorCategory::Timeout);
+    });
+
+    let warnings = warnings.lock().unwrap();
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("odd failure") && warnings[0].contains("unknown"), "{}", warnings[0]);
+    assert!(warnings[1].contains("Execution timeout"), "{}", warnings[1]);
+}
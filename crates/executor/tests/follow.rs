@@ -0,0 +1,179 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::{Executor, StateStore};
+use serde_json::json;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn append(path: &std::path::Path, text: &str) {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+#[tokio::test]
+async fn test_follow_from_start_until_sentinel() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("app.log"),
+        "INFO start\nERROR disk full\r\nINFO ok\nERROR again\nDONE\nERROR after\n",
+    )
+    .unwrap();
+
+    let output = executor
+        .execute(&task("follow", json!({
+            "path": "app.log", "pattern": "^ERROR", "from": "start", "until_pattern": "^DONE$",
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["stopped"], "until_pattern");
+    assert_eq!(output["matches"], json!([
+        { "offset": 11, "line": "ERROR disk full" },
+        { "offset": 36, "line": "ERROR again" },
+    ]));
+    assert_eq!(output["offset"], 53);
+}
+
+#[tokio::test]
+async fn test_follow_concurrent_appends_and_partial_lines() {
+    let (dir, executor) = temp_executor();
+    let path = dir.path().join("app.log");
+    std::fs::write(&path, "ERROR old\n").unwrap();
+
+    let writer_path = path.clone();
+    let writer = std::thread::spawn(move || {
+        for i in 0..5 {
+            std::thread::sleep(Duration::from_millis(20));
+            // Lines arrive in two writes; the half line must not be reported early
+            append(&writer_path, &format!("ERROR part {}", i));
+            std::thread::sleep(Duration::from_millis(10));
+            append(&writer_path, " done\nINFO noise\n");
+        }
+    });
+
+    let output = executor
+        .execute(&task("follow", json!({
+            "path": "app.log", "pattern": "^ERROR", "max_matches": 5, "max_duration_ms": 5000,
+            "poll_interval_ms": 5,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(output["stopped"], "max_matches");
+    let lines: Vec<&str> = output["matches"].as_array().unwrap().iter()
+        .map(|m| m["line"].as_str().unwrap())
+        .collect();
+    assert_eq!(lines, (0..5).map(|i| format!("ERROR part {} done", i)).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_follow_survives_rotation_and_truncation() {
+    let (dir, executor) = temp_executor();
+    let path = dir.path().join("app.log");
+    std::fs::write(&path, "ERROR before\n").unwrap();
+
+    let writer_path = path.clone();
+    let writer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        append(&writer_path, "ERROR one\n");
+        std::thread::sleep(Duration::from_millis(50));
+        // Rename-and-recreate rotation
+        std::fs::rename(&writer_path, writer_path.with_extension("log.1")).unwrap();
+        append(&writer_path, "ERROR two\n");
+        std::thread::sleep(Duration::from_millis(50));
+        // Copy-truncate rotation
+        std::fs::write(&writer_path, "").unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        append(&writer_path, "ERROR three\n");
+    });
+
+    let output = executor
+        .execute(&task("follow", json!({
+            "path": "app.log", "pattern": "^ERROR", "max_matches": 3, "max_duration_ms": 5000,
+            "poll_interval_ms": 5,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    writer.join().unwrap();
+
+    let lines: Vec<&str> = output["matches"].as_array().unwrap().iter()
+        .map(|m| m["line"].as_str().unwrap())
+        .collect();
+    assert_eq!(lines, vec!["ERROR one", "ERROR two", "ERROR three"]);
+    assert_eq!(output["rotations"], 2);
+    assert_eq!(output["offset"], 12);
+}
+
+#[tokio::test]
+async fn test_follow_resumes_from_state() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    std::fs::write(&path, "ERROR a\nERROR b\n").unwrap();
+    let store = Arc::new(StateStore::new(dir.path().join("state.json")));
+    let executor = FileExecutor::new(dir.path().to_path_buf()).with_state_store(store.clone());
+
+    let params = json!({
+        "path": "app.log", "pattern": "ERROR", "from": "start", "from_state": "log",
+        "max_duration_ms": 0,
+    });
+    let first = executor.execute(&task("follow", params.clone())).await.unwrap().output.unwrap();
+    assert_eq!(first["matches"].as_array().unwrap().len(), 2);
+    assert_eq!(first["stopped"], "max_duration");
+    store.set("log", first["resume"].clone()).await.unwrap();
+
+    append(&path, "ERROR c\n");
+    let second = executor.execute(&task("follow", params.clone())).await.unwrap().output.unwrap();
+    assert_eq!(second["matches"], json!([{ "offset": 16, "line": "ERROR c" }]));
+    store.set("log", second["resume"].clone()).await.unwrap();
+
+    // Rotated between runs: the new file is read from the beginning
+    std::fs::rename(&path, path.with_extension("log.1")).unwrap();
+    std::fs::write(&path, "ERROR new file with more bytes than before\n").unwrap();
+    let third = executor.execute(&task("follow", params)).await.unwrap().output.unwrap();
+    assert_eq!(third["matches"][0]["offset"], 0);
+}
+
+#[tokio::test]
+async fn test_follow_requires_stop_condition() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("app.log"), "").unwrap();
+
+    let result = executor
+        .execute(&task("follow", json!({ "path": "app.log", "pattern": "x" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_follow_bounds_buffered_matches_and_lines() {
+    let (dir, executor) = temp_executor();
+    let lines: String = (0..10_005).map(|i| format!("ERROR {}\n", i)).collect();
+    std::fs::write(dir.path().join("app.log"), &lines).unwrap();
+    std::fs::write(dir.path().join("long.log"), "x".repeat(2 * 1024 * 1024)).unwrap();
+
+    let output = executor
+        .execute(&task("follow", json!({ "path": "app.log", "pattern": "^ERROR", "from": "start", "max_matches": 20_000 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["stopped"], "match_limit");
+    assert_eq!(output["matches"].as_array().unwrap().len(), 10_000);
+    assert_eq!(output["resume"]["offset"], lines.find("ERROR 10000").unwrap());
+
+    let result = executor
+        .execute(&task("follow", json!({ "path": "long.log", "pattern": "x", "from": "start", "max_duration_ms": 1000 })))
+        .await;
+    assert!(matches!(result, Err(Error::Io(ref e)) if e.kind() == std::io::ErrorKind::InvalidData), "{:?}", result);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_glob_matches_files_and_dirs() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir_all(dir.path().join("reports/2024/q1")).unwrap();
+    std::fs::create_dir_all(dir.path().join("reports/archive.csv")).unwrap();
+    std::fs::write(dir.path().join("reports/top.csv"), "a").unwrap();
+    std::fs::write(dir.path().join("reports/2024/jan.csv"), "a").unwrap();
+    std::fs::write(dir.path().join("reports/2024/q1/feb.csv"), "a").unwrap();
+    std::fs::write(dir.path().join("reports/2024/notes.txt"), "a").unwrap();
+
+    let output = executor
+        .execute(&task("glob", json!({ "pattern": "reports/**/*.csv" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["matches"], json!([
+        { "path": "reports/2024/jan.csv", "is_dir": false },
+        { "path": "reports/2024/q1/feb.csv", "is_dir": false },
+        { "path": "reports/archive.csv", "is_dir": true },
+        { "path": "reports/top.csv", "is_dir": false },
+    ]));
+    assert_eq!(output["truncated"], false);
+
+    // '*' does not cross directories
+    let shallow = executor
+        .execute(&task("glob", json!({ "pattern": "reports/*.csv", "max_results": 1 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(shallow["matches"], json!([{ "path": "reports/archive.csv", "is_dir": true }]));
+    assert_eq!(shallow["total"], 2);
+    assert_eq!(shallow["truncated"], true);
+}
+
+#[tokio::test]
+async fn test_glob_cannot_escape_base_path() {
+    let (_dir, executor) = temp_executor();
+
+    for pattern in ["../*.csv", "reports/../../**", "/etc/*"] {
+        let result = executor.execute(&task("glob", json!({ "pattern": pattern }))).await;
+        assert!(matches!(result, Err(Error::PermissionDenied(_))), "{}", pattern);
+    }
+}
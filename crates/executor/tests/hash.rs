@@ -0,0 +1,50 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn digest(executor: &FileExecutor, path: &str, algorithm: Option<&str>) -> Value {
+    let mut params = json!({ "path": path });
+    if let Some(algorithm) = algorithm {
+        params["algorithm"] = json!(algorithm);
+    }
+    executor.execute(&task("hash", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_known_digests() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("abc.txt"), "abc").unwrap();
+    std::fs::write(dir.path().join("empty.txt"), "").unwrap();
+
+    let sha256 = digest(&executor, "abc.txt", None).await;
+    assert_eq!(sha256["algorithm"], "sha256");
+    assert_eq!(sha256["digest"], "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(sha256["size"], 3);
+    assert_eq!(digest(&executor, "abc.txt", Some("sha1")).await["digest"], "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(digest(&executor, "abc.txt", Some("MD5")).await["digest"], "900150983cd24fb0d6963f7d28e17f72");
+
+    let empty = digest(&executor, "empty.txt", None).await;
+    assert_eq!(empty["digest"], "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    assert_eq!(empty["size"], 0);
+    assert_eq!(digest(&executor, "empty.txt", Some("md5")).await["digest"], "d41d8cd98f00b204e9800998ecf8427e");
+}
+
+#[tokio::test]
+async fn test_large_file_and_unknown_algorithm() {
+    let (dir, executor) = temp_executor();
+    // Spans several read chunks
+    std::fs::write(dir.path().join("big.bin"), vec![b'a'; 1_000_000]).unwrap();
+
+    let output = digest(&executor, "big.bin", Some("sha1")).await;
+    assert_eq!(output["digest"], "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+    assert_eq!(output["size"], 1_000_000);
+
+    let result = executor
+        .execute(&task("hash", json!({ "path": "big.bin", "algorithm": "crc32" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(message)) if message.contains("sha256, sha1, md5")));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::task;
+use local_automation_common::Error;
+use local_automation_executor::file::{FileExecutor, ImpactLimits};
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_absolute_threshold_and_confirmation() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("big.bin"), vec![0u8; 5000]).unwrap();
+    std::fs::write(dir.path().join("small.txt"), "tiny").unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf())
+        .with_impact_limits(ImpactLimits { max_bytes: Some(1000), ..Default::default() });
+
+    executor.execute(&task("delete", json!({ "path": "small.txt" }))).await.unwrap();
+
+    let preview = executor
+        .execute(&task("delete", json!({ "path": "big.bin", "dry_run": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(preview["impact"], json!({ "files": 1, "bytes": 5000 }));
+    assert!(dir.path().join("big.bin").exists());
+
+    let refused = executor.execute(&task("delete", json!({ "path": "big.bin" }))).await;
+    match refused {
+        Err(Error::PermissionDenied(message)) => {
+            assert!(message.contains("1 files (5000 bytes)"), "{}", message);
+            assert!(message.contains("max_bytes 1000"), "{}", message);
+        }
+        other => panic!("expected a refusal, got {:?}", other.map(|r| r.output)),
+    }
+    assert!(dir.path().join("big.bin").exists());
+
+    // A confirmation smaller than the preview is still refused
+    let short = executor
+        .execute(&task("delete", json!({ "path": "big.bin", "confirm_impact": { "max_files": 1, "max_bytes": 4999 } })))
+        .await;
+    assert!(matches!(short, Err(Error::PermissionDenied(_))));
+
+    executor
+        .execute(&task("delete", json!({ "path": "big.bin", "confirm_impact": { "max_files": 1, "max_bytes": 5000 } })))
+        .await
+        .unwrap();
+    assert!(!dir.path().join("big.bin").exists());
+}
+
+#[tokio::test]
+async fn test_relative_threshold_of_base_path() {
+    let dir = tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("output")).unwrap();
+    std::fs::write(dir.path().join("output/report.csv"), vec![b'x'; 300]).unwrap();
+    std::fs::write(dir.path().join("output/summary.csv"), vec![b'x'; 500]).unwrap();
+    std::fs::write(dir.path().join("output/notes.txt"), vec![b'x'; 200]).unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf())
+        .with_impact_limits(ImpactLimits { max_fraction: Some(0.4), ..Default::default() });
+
+    // 300 of 1000 bytes and 1 of 3 files: under 40%
+    executor.execute(&task("delete", json!({ "path": "output/report.csv" }))).await.unwrap();
+
+    // Now 500 of 700 bytes
+    let refused = executor.execute(&task("delete", json!({ "path": "output/summary.csv" }))).await;
+    match refused {
+        Err(Error::PermissionDenied(message)) => assert!(message.contains("71% of base_path"), "{}", message),
+        other => panic!("expected a refusal, got {:?}", other.map(|r| r.output)),
+    }
+
+    // Without limits nothing is measured or refused
+    let unguarded = FileExecutor::new(dir.path().to_path_buf());
+    unguarded.execute(&task("delete", json!({ "path": "output/summary.csv" }))).await.unwrap();
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+fn read(dir: &std::path::Path) -> String {
+    std::fs::read_to_string(dir.join("config.json")).unwrap()
+}
+
+async fn setup() -> (tempfile::TempDir, FileExecutor) {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("config.json"), r#"{"server":{"port":8080,"host":"localhost"},"debug":true}"#).unwrap();
+    (dir, executor)
+}
+
+#[tokio::test]
+async fn test_apply_json_patch() {
+    let (dir, executor) = setup().await;
+
+    let result = executor
+        .execute(&task("apply_json_patch", json!({
+            "path": "config.json",
+            "patch": [
+                { "op": "test", "path": "/server/port", "value": 8080 },
+                { "op": "replace", "path": "/server/port", "value": 8081 },
+                { "op": "remove", "path": "/debug" },
+            ],
+        })))
+        .await
+        .unwrap();
+    assert!(result.success);
+    assert_eq!(result.output.unwrap()["operations"], 3);
+    let expected = json!({ "server": { "port": 8081, "host": "localhost" } });
+    // Same formatting write_json produces
+    assert_eq!(read(dir.path()), serde_json::to_string_pretty(&expected).unwrap());
+}
+
+#[tokio::test]
+async fn test_apply_json_merge_patch() {
+    let (dir, executor) = setup().await;
+
+    let result = executor
+        .execute(&task("apply_json_patch", json!({
+            "path": "config.json",
+            "merge": true,
+            "patch": { "server": { "port": 9000, "host": null }, "tls": true },
+        })))
+        .await
+        .unwrap();
+    assert!(result.success);
+    let doc: Value = serde_json::from_str(&read(dir.path())).unwrap();
+    assert_eq!(doc, json!({ "server": { "port": 9000 }, "debug": true, "tls": true }));
+}
+
+#[tokio::test]
+async fn test_failed_test_op_leaves_file_alone() {
+    let (dir, executor) = setup().await;
+    let before = read(dir.path());
+
+    let result = executor
+        .execute(&task("apply_json_patch", json!({
+            "path": "config.json",
+            "patch": [
+                { "op": "replace", "path": "/debug", "value": false },
+                { "op": "test", "path": "/server/port", "value": 1 },
+            ],
+        })))
+        .await
+        .unwrap();
+    assert!(!result.success);
+    let output = result.output.unwrap();
+    assert_eq!(output["failed_operation"], 1);
+    assert_eq!(output["failed_path"], "/server/port");
+    assert!(result.error.unwrap().contains("operation 1"));
+    assert_eq!(read(dir.path()), before);
+
+    let err = executor
+        .execute(&task("apply_json_patch", json!({
+            "path": "config.json",
+            "patch": [{ "op": "remove", "path": "/missing" }],
+        })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+}
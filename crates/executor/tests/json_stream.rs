@@ -0,0 +1,169 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Tracks live and peak heap usage so the bounded-memory claim can be checked
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(now, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[tokio::test]
+async fn test_read_json_stream_pages() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("items.json"),
+        r#"[ {"id": 0, "name": "a,]\"b"}, {"id": 1}, [1, [2]], "x", 4.5, null ]"#,
+    )
+    .unwrap();
+
+    let page = executor
+        .execute(&task("read_json_stream", json!({ "path": "items.json", "limit": 2 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(page["items"], json!([{ "id": 0, "name": "a,]\"b" }, { "id": 1 }]));
+    assert_eq!(page["has_more"], true);
+
+    let rest = executor
+        .execute(&task("read_json_stream", json!({ "path": "items.json", "offset": 2, "limit": 10 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(rest["items"], json!([[1, [2]], "x", 4.5, null]));
+    assert_eq!(rest["count"], 4);
+    assert_eq!(rest["has_more"], false);
+
+    let length = executor
+        .execute(&task("json_array_length", json!({ "path": "items.json" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(length["length"], 6);
+}
+
+#[tokio::test]
+async fn test_read_json_stream_with_pointer_to_dest() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("doc.json"),
+        r#"{"meta": {"skip": [1, {"a": "}"}]}, "data": {"rows": [{"n": 1}, {"n": 2}, {"n": 3}]}}"#,
+    )
+    .unwrap();
+
+    let output = executor
+        .execute(&task(
+            "read_json_stream",
+            json!({ "path": "doc.json", "pointer": "/data/rows", "offset": 1, "dest": "rows.ndjson" }),
+        ))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["written"], 2);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("rows.ndjson")).unwrap(),
+        "{\"n\":2}\n{\"n\":3}\n"
+    );
+
+    let length = executor
+        .execute(&task("json_array_length", json!({ "path": "doc.json", "pointer": "/meta/skip" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(length["length"], 2);
+
+    let missing = executor
+        .execute(&task("json_array_length", json!({ "path": "doc.json", "pointer": "/nope" })))
+        .await;
+    assert!(matches!(missing, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_malformed_content_reports_byte_offset() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("trailing.json"), "[1, 2, 3] garbage").unwrap();
+    std::fs::write(dir.path().join("broken.json"), "[1, 2, tru]").unwrap();
+
+    let err = executor
+        .execute(&task("json_array_length", json!({ "path": "trailing.json" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("byte offset 10"), "{}", err);
+
+    let err = executor
+        .execute(&task("read_json_stream", json!({ "path": "broken.json" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("byte offset 7"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_large_array_stays_bounded() {
+    let (dir, executor) = temp_executor();
+    let path = dir.path().join("large.json");
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        file.write_all(b"[").unwrap();
+        for i in 0..200_000 {
+            if i > 0 {
+                file.write_all(b",\n").unwrap();
+            }
+            write!(file, r#"{{"id": {}, "payload": "{}"}}"#, i, "x".repeat(64)).unwrap();
+        }
+        file.write_all(b"]").unwrap();
+    }
+    let file_size = std::fs::metadata(&path).unwrap().len() as usize;
+    assert!(file_size > 15_000_000);
+
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let length = executor
+        .execute(&task("json_array_length", json!({ "path": "large.json" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(length["length"], 200_000);
+
+    let page = executor
+        .execute(&task("read_json_stream", json!({ "path": "large.json", "offset": 199_998 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(page["items"][1]["id"], 199_999);
+
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    assert!(peak < file_size / 10, "peak {} bytes for {} byte file", peak, file_size);
+}
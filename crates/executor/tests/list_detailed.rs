@@ -0,0 +1,115 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+fn paths(output: &Value) -> Vec<&str> {
+    output["entries"].as_array().unwrap().iter().map(|e| e["relative_path"].as_str().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_list_detailed_metadata_and_recursion() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir_all(dir.path().join("data/a/b")).unwrap();
+    std::fs::create_dir(dir.path().join("data/.cache")).unwrap();
+    std::fs::write(dir.path().join("data/top.csv"), "12345").unwrap();
+    std::fs::write(dir.path().join("data/.hidden"), "x").unwrap();
+    std::fs::write(dir.path().join("data/.cache/c.bin"), "x").unwrap();
+    std::fs::write(dir.path().join("data/a/b/deep.txt"), "deep").unwrap();
+    let before = chrono::Utc::now() - chrono::Duration::seconds(5);
+
+    let flat = executor
+        .execute(&task("list_detailed", json!({ "path": "data" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(paths(&flat), ["a", "top.csv"]);
+    let top = &flat["entries"][1];
+    assert_eq!(top["name"], "top.csv");
+    assert_eq!(top["path"], "data/top.csv");
+    assert_eq!(top["is_dir"], false);
+    assert_eq!(top["size"], 5);
+    let modified: chrono::DateTime<chrono::Utc> = top["modified"].as_str().unwrap().parse().unwrap();
+    assert!(modified > before);
+    assert_eq!(flat["entries"][0]["is_dir"], true);
+
+    let deep = executor
+        .execute(&task("list_detailed", json!({ "path": "data", "recursive": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(paths(&deep), ["a", "a/b", "a/b/deep.txt", "top.csv"]);
+
+    let limited = executor
+        .execute(&task("list_detailed", json!({ "path": "data", "recursive": true, "max_depth": 2, "include_hidden": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(paths(&limited), [".cache", ".cache/c.bin", ".hidden", "a", "a/b", "top.csv"]);
+
+    // The old list shape is untouched
+    let listed = executor
+        .execute(&task("list_dir", json!({ "path": "data" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert!(listed["files"].as_array().unwrap().iter().all(Value::is_string));
+}
+
+#[tokio::test]
+async fn test_list_detailed_rejects_files_and_zero_depth() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("file.txt"), "x").unwrap();
+
+    for params in [
+        json!({ "path": "file.txt" }),
+        json!({ "path": ".", "recursive": true, "max_depth": 0 }),
+    ] {
+        let result = executor.execute(&task("list_detailed", params)).await;
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_listings_skip_open_txn_and_edit_session() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("notes.txt"), "x").unwrap();
+
+    let txn = executor.execute(&task("txn_begin", json!({}))).await.unwrap().output.unwrap();
+    executor
+        .execute(&task("write", json!({ "txn": txn["txn_id"], "path": "staged.txt", "content": "y" })))
+        .await
+        .unwrap();
+    executor
+        .execute(&task("edit_begin", json!({ "path": "notes.txt" })))
+        .await
+        .unwrap();
+    // The bookkeeping is really there on disk
+    let on_disk: Vec<String> = std::fs::read_dir(dir.path()).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert!(on_disk.iter().any(|n| n == ".txn"), "{:?}", on_disk);
+    assert!(on_disk.iter().any(|n| n == ".locks"), "{:?}", on_disk);
+    assert!(on_disk.iter().any(|n| n.starts_with(".notes.txt.edit-")), "{:?}", on_disk);
+
+    let listed = executor.execute(&task("list_dir", json!({ "path": "." }))).await.unwrap().output.unwrap();
+    assert_eq!(listed["files"], json!(["notes.txt"]));
+
+    let globbed = executor.execute(&task("glob", json!({ "pattern": "**" }))).await.unwrap().output.unwrap();
+    let matched: Vec<&str> = globbed["matches"].as_array().unwrap().iter().map(|m| m["path"].as_str().unwrap()).collect();
+    assert_eq!(matched, ["notes.txt"]);
+
+    let zipped = executor
+        .execute(&task("zip", json!({ "sources": ["."], "dest": "all.zip" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(zipped["entries"], json!(["notes.txt"]));
+}
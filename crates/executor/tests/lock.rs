@@ -0,0 +1,151 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_two_executors_contend_for_lock() {
+    let dir = tempdir().unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    let second = FileExecutor::new(dir.path().to_path_buf());
+
+    let acquired = first
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+    let output = acquired.output.unwrap();
+    let lock_id = output["lock_id"].as_str().unwrap().to_string();
+    assert_eq!(output["pid"], std::process::id());
+
+    // Sidecar records the holder
+    let sidecar = std::fs::read_to_string(dir.path().join("job.lock.holder.json")).unwrap();
+    let holder: serde_json::Value = serde_json::from_str(&sidecar).unwrap();
+    assert_eq!(holder["lock_id"], lock_id.as_str());
+
+    // Second instance times out while the first holds the lock
+    let contended = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock", "timeout_ms": 100 })))
+        .await;
+    assert!(matches!(contended, Err(Error::Timeout)));
+
+    first
+        .execute(&task("release_lock", json!({ "lock_id": lock_id })))
+        .await
+        .unwrap();
+    assert!(!dir.path().join("job.lock.holder.json").exists());
+
+    let reacquired = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+    assert!(reacquired.success);
+}
+
+#[tokio::test]
+async fn test_waiter_acquires_after_release() {
+    let dir = tempdir().unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    let second = FileExecutor::new(dir.path().to_path_buf());
+
+    let output = first
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    let lock_id = output["lock_id"].clone();
+
+    let waiter = tokio::spawn(async move {
+        second
+            .execute(&task("acquire_lock", json!({ "path": "job.lock", "timeout_ms": 5000 })))
+            .await
+            .map(|r| r.success)
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    first
+        .execute(&task("release_lock", json!({ "lock_id": lock_id })))
+        .await
+        .unwrap();
+
+    assert!(waiter.await.unwrap().unwrap());
+}
+
+#[tokio::test]
+async fn test_lock_released_when_executor_dropped() {
+    let dir = tempdir().unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    first
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+    drop(first);
+
+    let second = FileExecutor::new(dir.path().to_path_buf());
+    let result = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+    assert!(result.success);
+}
+
+#[tokio::test]
+async fn test_break_stale_lock_is_opt_in() {
+    let dir = tempdir().unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    let second = FileExecutor::new(dir.path().to_path_buf());
+
+    first
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+
+    // Without the opt-in a held lock is never broken
+    let refused = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock", "timeout_ms": 50 })))
+        .await;
+    assert!(matches!(refused, Err(Error::Timeout)));
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let broken = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock", "break_if_stale_secs": 1 })))
+        .await
+        .unwrap();
+    assert_eq!(broken.output.unwrap()["broke_stale"], true);
+}
+
+#[tokio::test]
+async fn test_held_lock_without_sidecar_is_not_stale() {
+    let dir = tempdir().unwrap();
+    let first = FileExecutor::new(dir.path().to_path_buf());
+    let second = FileExecutor::new(dir.path().to_path_buf());
+
+    // An old lock file must not make a fresh lock look stale
+    std::fs::write(dir.path().join("job.lock"), "").unwrap();
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    std::fs::File::options().write(true).open(dir.path().join("job.lock")).unwrap().set_modified(old).unwrap();
+    first
+        .execute(&task("acquire_lock", json!({ "path": "job.lock" })))
+        .await
+        .unwrap();
+
+    // Like a holder that writes no sidecar, or hasn't written it yet
+    std::fs::remove_file(dir.path().join("job.lock.holder.json")).unwrap();
+    let refused = second
+        .execute(&task("acquire_lock", json!({ "path": "job.lock", "timeout_ms": 50, "break_if_stale_secs": 0 })))
+        .await;
+    assert!(matches!(refused, Err(Error::Timeout)), "{:?}", refused);
+}
+
+#[tokio::test]
+async fn test_release_unknown_lock() {
+    let (_dir, executor) = temp_executor();
+    let result = executor
+        .execute(&task("release_lock", json!({ "lock_id": "nope" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
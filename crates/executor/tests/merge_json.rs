@@ -0,0 +1,80 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn merge(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("merge_json", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_merge_json_nested_files() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("base.json"), json!({
+        "service": {
+            "http": { "port": 80, "tls": { "enabled": false, "cert": "none" } },
+            "name": "api",
+        },
+        "hosts": ["a", "b"],
+    }).to_string()).unwrap();
+    std::fs::write(dir.path().join("prod.json"), json!({
+        "service": { "http": { "tls": { "enabled": true } } },
+        "hosts": ["c"],
+    }).to_string()).unwrap();
+
+    let output = merge(&executor, json!({ "base": "base.json", "overlay": "prod.json" })).await;
+    assert_eq!(output["data"], json!({
+        "service": {
+            "http": { "port": 80, "tls": { "enabled": true, "cert": "none" } },
+            "name": "api",
+        },
+        "hosts": ["c"],
+    }));
+    assert_eq!(output["warnings"], json!([]));
+
+    let output = merge(&executor, json!({
+        "base": "base.json",
+        "overlay": "prod.json",
+        "array_strategy": "concat",
+        "dest": "merged.json",
+    }))
+    .await;
+    assert_eq!(output["dest"], "merged.json");
+    assert!(output.get("data").is_none());
+    let written: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join("merged.json")).unwrap()).unwrap();
+    assert_eq!(written["hosts"], json!(["a", "b", "c"]));
+    assert_eq!(written["service"]["http"]["tls"]["cert"], "none");
+}
+
+#[tokio::test]
+async fn test_merge_json_null_vs_missing() {
+    let (_dir, executor) = temp_executor();
+
+    let output = merge(&executor, json!({
+        "base": { "data": { "a": 1, "b": 2, "c": { "d": 3 } } },
+        "overlay": { "data": { "a": null, "c": null } },
+    }))
+    .await;
+    // Explicit nulls replace, missing keys keep the base value; neither warns
+    assert_eq!(output["data"], json!({ "a": null, "b": 2, "c": null }));
+    assert_eq!(output["warnings"], json!([]));
+}
+
+#[tokio::test]
+async fn test_merge_json_type_conflicts_warn() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("base.json"), r#"{"log": {"level": "info"}, "a/b": [1], "n": 1}"#).unwrap();
+
+    let output = merge(&executor, json!({
+        "base": "base.json",
+        "overlay": { "data": { "log": "debug", "a/b": { "x": 1 }, "n": "one" } },
+    }))
+    .await;
+    assert_eq!(output["data"], json!({ "log": "debug", "a/b": { "x": 1 }, "n": "one" }));
+    assert_eq!(output["warnings"], json!([
+        { "path": "/a~1b", "base": "array", "overlay": "object" },
+        { "path": "/log", "base": "object", "overlay": "string" },
+    ]));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_write_then_read_ndjson() {
+    let (dir, executor) = temp_executor();
+    let data = json!([{ "level": "info", "msg": "start\nup" }, { "level": "warn", "n": [1, 2] }, "bare", 3]);
+
+    let output = executor
+        .execute(&task("write_ndjson", json!({ "path": "log.ndjson", "data": data })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["lines"], 4);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("log.ndjson")).unwrap(),
+        "{\"level\":\"info\",\"msg\":\"start\\nup\"}\n{\"level\":\"warn\",\"n\":[1,2]}\n\"bare\"\n3\n"
+    );
+
+    let output = executor
+        .execute(&task("read_ndjson", json!({ "path": "log.ndjson" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["items"], data);
+    assert_eq!(output["has_more"], false);
+    assert!(output.get("warnings").is_none());
+}
+
+#[tokio::test]
+async fn test_read_ndjson_pages_and_skips_blank_lines() {
+    let (dir, executor) = temp_executor();
+    let content: String = (0..10).map(|i| format!("{{\"i\":{}}}\n\n", i)).collect();
+    std::fs::write(dir.path().join("n.jsonl"), content).unwrap();
+
+    let output = executor
+        .execute(&task("read_ndjson", json!({ "path": "n.jsonl", "offset": 3, "limit": 2 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["items"], json!([{ "i": 3 }, { "i": 4 }]));
+    assert_eq!(output["has_more"], true);
+
+    let output = executor
+        .execute(&task("read_ndjson", json!({ "path": "n.jsonl", "offset": 8, "limit": 5 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["count"], 2);
+    assert_eq!(output["has_more"], false);
+}
+
+#[tokio::test]
+async fn test_read_ndjson_reports_or_skips_bad_lines() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("bad.ndjson"), "{\"a\":1}\n{\"a\":\n{\"a\":3}\nnot json\n").unwrap();
+
+    let err = executor
+        .execute(&task("read_ndjson", json!({ "path": "bad.ndjson" })))
+        .await
+        .unwrap_err();
+    match err {
+        Error::Io(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+            assert!(e.to_string().contains("line 2"), "{}", e);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let output = executor
+        .execute(&task("read_ndjson", json!({ "path": "bad.ndjson", "skip_invalid": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["items"], json!([{ "a": 1 }, { "a": 3 }]));
+    let lines: Vec<_> = output["warnings"].as_array().unwrap().iter().map(|w| w["line"].clone()).collect();
+    assert_eq!(lines, vec![json!(2), json!(4)]);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn preview(executor: &FileExecutor, operation: &str, params: Value) -> Value {
+    executor.execute(&task(operation, params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_read_preview_respects_byte_budget() {
+    let (dir, executor) = temp_executor();
+    // 'é' is two bytes, so a 5-byte cut lands inside a character
+    std::fs::write(dir.path().join("notes.txt"), "ééééé and more").unwrap();
+
+    let output = preview(&executor, "read", json!({ "path": "notes.txt", "preview": { "bytes": 5 } })).await;
+    assert_eq!(output["content"], "éé");
+    assert_eq!(output["preview"]["truncated"], true);
+    assert_eq!(output["preview"]["total_bytes"], 19);
+
+    let whole = preview(&executor, "read", json!({ "path": "notes.txt", "preview": true })).await;
+    assert_eq!(whole["content"], "ééééé and more");
+    assert_eq!(whole["preview"]["truncated"], false);
+}
+
+#[tokio::test]
+async fn test_csv_preview_samples_rows_and_estimates_total() {
+    let (dir, executor) = temp_executor();
+    let mut content = String::from("id,name\n");
+    for i in 0..10_000 {
+        content.push_str(&format!("{:05},item\n", i));
+    }
+    std::fs::write(dir.path().join("big.csv"), &content).unwrap();
+
+    let output = preview(&executor, "read_csv", json!({ "path": "big.csv", "preview": { "rows": 3 } })).await;
+    assert_eq!(output["headers"], json!(["id", "name"]));
+    assert_eq!(output["rows"], json!([["00000", "item"], ["00001", "item"], ["00002", "item"]]));
+    assert_eq!(output["preview"]["truncated"], true);
+    assert_eq!(output["preview"]["estimated_rows"], 10_000);
+
+    std::fs::write(dir.path().join("small.csv"), "id\n1\n2\n").unwrap();
+    let small = preview(&executor, "read_csv", json!({ "path": "small.csv", "preview": true })).await;
+    assert_eq!(small["rows"].as_array().unwrap().len(), 2);
+    assert_eq!(small["preview"]["truncated"], false);
+    assert_eq!(small["preview"]["estimated_rows"], 2);
+}
+
+#[tokio::test]
+async fn test_listing_preview_caps_entries() {
+    let (dir, executor) = temp_executor();
+    for i in 0..5 {
+        std::fs::write(dir.path().join(format!("{}.txt", i)), "x").unwrap();
+    }
+
+    let listed = preview(&executor, "list_dir", json!({ "path": ".", "preview": { "entries": 2 } })).await;
+    assert_eq!(listed["files"].as_array().unwrap().len(), 2);
+    assert_eq!(listed["preview"]["total_entries"], 5);
+
+    let globbed = preview(&executor, "glob", json!({ "pattern": "*.txt", "preview": { "entries": 4 } })).await;
+    assert_eq!(globbed["matches"].as_array().unwrap().len(), 4);
+    assert_eq!(globbed["preview"]["truncated"], true);
+}
+
+#[tokio::test]
+async fn test_preview_never_mutates() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("keep.txt"), "keep").unwrap();
+
+    // Destructive operations fall back to their dry run
+    let deleted = preview(&executor, "delete", json!({ "path": "keep.txt", "preview": true })).await;
+    assert_eq!(deleted["dry_run"], true);
+    assert!(dir.path().join("keep.txt").exists());
+
+    for (operation, params) in [
+        ("write", json!({ "path": "new.txt", "content": "x", "preview": true })),
+        ("append", json!({ "path": "keep.txt", "content": "x", "preview": true })),
+    ] {
+        let result = executor.execute(&task(operation, params)).await;
+        assert!(matches!(result, Err(Error::InvalidConfig(_))), "{}", operation);
+    }
+    assert!(!dir.path().join("new.txt").exists());
+    assert_eq!(std::fs::read_to_string(dir.path().join("keep.txt")).unwrap(), "keep");
+}
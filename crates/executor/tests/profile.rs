@@ -0,0 +1,182 @@
+mod common;
+
+use common::temp_executor;
+use local_automation_common::{Error, Task};
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::path::PathBuf;
+
+fn fixtures() -> FileExecutor {
+    FileExecutor::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data"))
+}
+
+fn profile(params: serde_json::Value) -> Task {
+    Task::new("file".to_string(), "profile".to_string(), params)
+}
+
+#[tokio::test]
+async fn test_profile_csv_report_shape() {
+    let result = fixtures()
+        .execute(&profile(json!({ "path": "profile.csv" })))
+        .await
+        .unwrap();
+    let output = result.output.unwrap();
+
+    assert_eq!(output["format"], "csv");
+    assert_eq!(output["rows_profiled"], 5);
+    assert_eq!(output["truncated"], false);
+    assert_eq!(
+        output["columns"],
+        json!([
+            {
+                "name": "customer_id",
+                "count": 5,
+                "null_count": 0,
+                "null_rate": 0.0,
+                "types": { "integer": 5 },
+                "distinct_estimate": 4,
+                "numeric": { "min": 1.0, "max": 4.0 },
+                "date": null,
+                "top_values": [
+                    { "value": "2", "count": 2 },
+                    { "value": "1", "count": 1 },
+                    { "value": "3", "count": 1 },
+                    { "value": "4", "count": 1 }
+                ],
+                "examples": ["1", "2", "3"]
+            },
+            {
+                "name": "amount",
+                "count": 5,
+                "null_count": 1,
+                "null_rate": 0.2,
+                "types": { "float": 2, "integer": 2, "null": 1 },
+                "distinct_estimate": 4,
+                "numeric": { "min": 3.0, "max": 100.0 },
+                "date": null,
+                "top_values": [
+                    { "value": "10.5", "count": 1 },
+                    { "value": "100", "count": 1 },
+                    { "value": "3", "count": 1 },
+                    { "value": "7.25", "count": 1 }
+                ],
+                "examples": ["10.5", "3", "7.25"]
+            },
+            {
+                "name": "signup",
+                "count": 5,
+                "null_count": 0,
+                "null_rate": 0.0,
+                "types": { "date": 5 },
+                "distinct_estimate": 4,
+                "numeric": null,
+                "date": { "min": "2023-12-31", "max": "2024-03-01" },
+                "top_values": [
+                    { "value": "2024-01-15", "count": 2 },
+                    { "value": "2023-12-31", "count": 1 },
+                    { "value": "2024-02-10", "count": 1 },
+                    { "value": "2024-03-01", "count": 1 }
+                ],
+                "examples": ["2024-01-15", "2024-03-01", "2023-12-31"]
+            },
+            {
+                "name": "active",
+                "count": 5,
+                "null_count": 0,
+                "null_rate": 0.0,
+                "types": { "boolean": 5 },
+                "distinct_estimate": 3,
+                "numeric": null,
+                "date": null,
+                "top_values": [
+                    { "value": "false", "count": 2 },
+                    { "value": "true", "count": 2 },
+                    { "value": "TRUE", "count": 1 }
+                ],
+                "examples": ["true", "false", "TRUE"]
+            },
+            {
+                "name": "note",
+                "count": 5,
+                "null_count": 2,
+                "null_rate": 0.4,
+                "types": { "null": 2, "string": 3 },
+                "distinct_estimate": 2,
+                "numeric": null,
+                "date": null,
+                "top_values": [
+                    { "value": "vip", "count": 2 },
+                    { "value": "new", "count": 1 }
+                ],
+                "examples": ["vip", "new"]
+            }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_profile_ndjson_missing_fields() {
+    let result = fixtures()
+        .execute(&profile(json!({ "path": "profile.ndjson" })))
+        .await
+        .unwrap();
+    let output = result.output.unwrap();
+    let columns = output["columns"].as_array().unwrap();
+
+    let names: Vec<&str> = columns.iter().map(|c| c["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["id", "score", "seen", "tags", "extra"]);
+
+    for column in columns {
+        assert_eq!(column["count"], 3, "column {}", column["name"]);
+    }
+    let score = &columns[1];
+    assert_eq!(score["types"], json!({ "float": 1, "integer": 1, "null": 1 }));
+    let extra = &columns[4];
+    assert_eq!(extra["null_count"], 2);
+    assert_eq!(extra["types"], json!({ "null": 2, "object": 1 }));
+    assert_eq!(columns[2]["types"], json!({ "date": 1, "null": 2 }));
+}
+
+#[tokio::test]
+async fn test_profile_bounded_on_high_cardinality() {
+    let (dir, executor) = temp_executor();
+    let mut content = String::from("id,bucket\n");
+    for i in 0..50_000 {
+        content.push_str(&format!("{},{}\n", i, i % 7));
+    }
+    std::fs::write(dir.path().join("big.csv"), content).unwrap();
+
+    let output = executor
+        .execute(&profile(json!({ "path": "big.csv", "top_n": 3 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+
+    let id = &output["columns"][0];
+    let estimate = id["distinct_estimate"].as_u64().unwrap() as f64;
+    assert!((estimate - 50_000.0).abs() / 50_000.0 < 0.05, "estimate {}", estimate);
+    assert_eq!(id["top_values"].as_array().unwrap().len(), 3);
+
+    let bucket = &output["columns"][1];
+    assert_eq!(bucket["distinct_estimate"], 7);
+    assert_eq!(bucket["top_values"][0], json!({ "value": "0", "count": 7143 }));
+
+    let sampled = executor
+        .execute(&profile(json!({ "path": "big.csv", "sample_rows": 100 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(sampled["rows_profiled"], 100);
+    assert_eq!(sampled["truncated"], true);
+}
+
+#[tokio::test]
+async fn test_profile_requires_known_format() {
+    let result = fixtures()
+        .execute(&profile(json!({ "path": "hello.txt" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+async fn list(executor: &FileExecutor) -> serde_json::Value {
+    executor
+        .execute(&task("list_quarantine", json!({})))
+        .await
+        .unwrap()
+        .output
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_ingestion_quarantines_only_bad_files() {
+    let dir = tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("drop")).unwrap();
+    std::fs::write(dir.path().join("drop/a.csv"), "id,qty\n1,2\n").unwrap();
+    std::fs::write(dir.path().join("drop/b.csv"), "id,qty\n1,2,3\n").unwrap();
+    std::fs::write(dir.path().join("drop/c.csv"), "id,qty\n3,4\n").unwrap();
+    std::fs::write(dir.path().join("drop/d.csv"), b"id\n\xff\xfe\n").unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf()).with_quarantine_dir("drop/.quarantine");
+
+    // A for-each over the drop folder: each item that fails to parse is quarantined
+    let files = executor
+        .execute(&task("list", json!({ "path": "drop" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    let mut names: Vec<String> = files["files"].as_array().unwrap().iter()
+        .map(|f| f.as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    for name in &names {
+        let path = format!("drop/{}", name);
+        let item = task("read_csv", json!({ "path": path }));
+        if let Err(e) = executor.execute(&item).await {
+            executor
+                .execute(&task("quarantine", json!({
+                    "path": path, "error": e.to_string(), "task_id": item.id.to_string(),
+                })))
+                .await
+                .unwrap();
+        }
+    }
+
+    let mut remaining: Vec<String> = std::fs::read_dir(dir.path().join("drop")).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    remaining.sort();
+    assert_eq!(remaining, vec![".quarantine", "a.csv", "c.csv"]);
+
+    let quarantined = list(&executor).await;
+    let items = quarantined["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["file"], "b.csv");
+    assert_eq!(items[0]["original_path"], "drop/b.csv");
+    assert!(items[0]["error"].as_str().unwrap().contains("found record with 3 fields"));
+    assert!(items[0]["task_id"].is_string());
+
+    // The quarantine is hidden from list and glob
+    let listed = executor
+        .execute(&task("list", json!({ "path": "drop" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(listed["files"].as_array().unwrap().len(), 2);
+    let globbed = executor
+        .execute(&task("concat", json!({ "pattern": "drop/**/*.csv", "dest": "all.csv" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(globbed["sources"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_collisions_and_requeue() {
+    let dir = tempdir().unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf()).with_quarantine_dir("quarantine");
+
+    for _ in 0..3 {
+        std::fs::write(dir.path().join("feed.json"), "{broken").unwrap();
+        executor
+            .execute(&task("quarantine", json!({ "path": "feed.json", "error": "bad json" })))
+            .await
+            .unwrap();
+    }
+    let items = list(&executor).await["items"].as_array().unwrap().clone();
+    assert_eq!(items.len(), 3);
+    let names: Vec<&str> = items.iter().map(|i| i["file"].as_str().unwrap()).collect();
+    assert!(names.contains(&"feed.json"));
+    assert!(names.iter().all(|n| n.starts_with("feed") && n.ends_with(".json")));
+
+    let requeued = executor
+        .execute(&task("requeue_from_quarantine", json!({ "file": "feed.json" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(requeued["requeued"].as_array().unwrap().len(), 1);
+    assert!(dir.path().join("feed.json").exists());
+    assert!(!dir.path().join("quarantine/feed.json.quarantine.json").exists());
+
+    // The original path is occupied again, so the rest needs overwrite or a dest
+    let result = executor
+        .execute(&task("requeue_from_quarantine", json!({ "all": true })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    executor
+        .execute(&task("requeue_from_quarantine", json!({ "all": true, "overwrite": true })))
+        .await
+        .unwrap();
+    assert!(list(&executor).await["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_quarantine_requires_configuration() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("x.txt"), "x").unwrap();
+
+    let result = executor
+        .execute(&task("quarantine", json!({ "path": "x.txt" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+fn setup() -> (tempfile::TempDir, FileExecutor) {
+    let (dir, executor) = temp_executor();
+    let doc = json!({
+        "items": [{ "id": 7, "tags": ["a"] }, { "id": 8 }],
+        "server": { "port": 8080, "a/b": "slash" },
+    });
+    std::fs::write(dir.path().join("doc.json"), doc.to_string()).unwrap();
+    (dir, executor)
+}
+
+async fn query(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("query_json", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_query_json_single_value() {
+    let (_dir, executor) = setup();
+
+    assert_eq!(query(&executor, json!({ "path": "doc.json", "pointer": "/items/0/id" })).await, json!(7));
+    assert_eq!(query(&executor, json!({ "path": "doc.json", "pointer": "/server/a~1b" })).await, json!("slash"));
+    assert_eq!(query(&executor, json!({ "path": "doc.json", "select": "items[1].id" })).await, json!(8));
+    assert_eq!(query(&executor, json!({ "path": "doc.json", "select": "server.port" })).await, json!(8080));
+    assert_eq!(
+        query(&executor, json!({ "path": "doc.json", "select": "items.5.id", "default": 0 })).await,
+        json!(0)
+    );
+}
+
+#[tokio::test]
+async fn test_query_json_selections() {
+    let (_dir, executor) = setup();
+
+    let output = query(&executor, json!({
+        "path": "doc.json",
+        "selections": {
+            "first": "/items/0/id",
+            "tag": "items.0.tags.0",
+            "timeout": { "select": "server.timeout", "default": 30 },
+        },
+    }))
+    .await;
+    assert_eq!(output, json!({ "first": 7, "tag": "a", "timeout": 30 }));
+}
+
+#[tokio::test]
+async fn test_query_json_missing_names_segment() {
+    let (_dir, executor) = setup();
+
+    let err = executor
+        .execute(&task("query_json", json!({ "path": "doc.json", "pointer": "/items/1/tags/0" })))
+        .await
+        .unwrap_err();
+    match err {
+        Error::Io(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+            assert!(e.to_string().contains("segment 'tags'"), "{}", e);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    for params in [
+        json!({ "path": "doc.json" }),
+        json!({ "path": "doc.json", "pointer": "items" }),
+        json!({ "path": "doc.json", "pointer": "/items", "selections": { "a": "/items" } }),
+    ] {
+        let err = executor.execute(&task("query_json", params)).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+const USERS: &str = "\
+id,name,email,status
+1,Ada,ada@example.com,active
+2,Bob,,inactive
+3,Cleo,cleo@example.org,active
+4,Dan,dan@example.com,active
+";
+
+async fn read(params: Value) -> Result<Value, Error> {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("users.csv"), USERS).unwrap();
+    let mut params = params;
+    params["path"] = json!("users.csv");
+    executor.execute(&task("read_csv", params)).await.map(|r| r.output.unwrap())
+}
+
+#[tokio::test]
+async fn test_read_csv_projects_columns_by_name_and_index() {
+    let output = read(json!({ "columns": ["email", 1] })).await.unwrap();
+    assert_eq!(output["headers"], json!(["email", "name"]));
+    assert_eq!(output["rows"][0], json!(["ada@example.com", "Ada"]));
+    assert_eq!(output["total_rows"], 4);
+    assert_eq!(output["matched_rows"], 4);
+}
+
+#[tokio::test]
+async fn test_read_csv_filters_rows() {
+    let output = read(json!({ "filter": { "column": "status", "equals": "active" }, "columns": ["id"] })).await.unwrap();
+    assert_eq!(output["rows"], json!([["1"], ["3"], ["4"]]));
+    assert_eq!(output["total_rows"], 4);
+    assert_eq!(output["matched_rows"], 3);
+
+    let output = read(json!({ "filter": { "column": "email", "contains": ".org" } })).await.unwrap();
+    assert_eq!(output["rows"], json!([["3", "Cleo", "cleo@example.org", "active"]]));
+
+    let output = read(json!({ "filter": { "column": 2, "not_empty": true } })).await.unwrap();
+    assert_eq!(output["matched_rows"], 3);
+}
+
+#[tokio::test]
+async fn test_read_csv_pages_filtered_rows() {
+    let params = |offset: usize| json!({
+        "filter": { "column": "status", "equals": "active" },
+        "columns": ["name"],
+        "offset": offset,
+        "limit": 2,
+    });
+    let first = read(params(0)).await.unwrap();
+    assert_eq!(first["rows"], json!([["Ada"], ["Cleo"]]));
+    let second = read(params(2)).await.unwrap();
+    assert_eq!(second["rows"], json!([["Dan"]]));
+    assert_eq!(second["matched_rows"], 3);
+}
+
+#[tokio::test]
+async fn test_read_csv_unknown_column_lists_headers() {
+    let err = read(json!({ "columns": ["mail"] })).await.unwrap_err();
+    match err {
+        Error::InvalidConfig(message) => {
+            assert!(message.contains("'mail'"), "{}", message);
+            assert!(message.contains("id, name, email, status"), "{}", message);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    assert!(matches!(read(json!({ "columns": [9] })).await, Err(Error::InvalidConfig(_))));
+    let both = json!({ "filter": { "column": "id", "equals": "1", "not_empty": true } });
+    assert!(matches!(read(both).await, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_read_csv_headerless_selection_uses_synthesized_names() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("raw.csv"), "x,1,a\ny,2,b\n").unwrap();
+
+    let output = executor
+        .execute(&task("read_csv", json!({
+            "path": "raw.csv",
+            "has_headers": false,
+            "columns": ["col_2", 0],
+            "filter": { "column": "col_1", "equals": "2" },
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["headers"], json!(["col_2", "col_0"]));
+    assert_eq!(output["rows"], json!([["b", "y"]]));
+}
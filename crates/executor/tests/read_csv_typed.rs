@@ -0,0 +1,181 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn typed(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("read_csv_typed", params)).await.unwrap().output.unwrap()
+}
+
+fn orders_schema() -> Value {
+    json!([
+        { "name": "id", "type": "int", "nullable": false },
+        { "name": "amount", "type": "float" },
+        { "name": "paid", "type": "bool" },
+        { "name": "day", "type": "date" },
+        { "name": "note", "type": "string" },
+    ])
+}
+
+#[tokio::test]
+async fn test_locale_numbers_and_dates() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(
+        dir.path().join("eu.csv"),
+        "id,amount,paid,day,note\n1.001,\"1.234,5\",yes,01.06.2024,first\n2,\"0,25\",false,31.12.2023,\n",
+    ).unwrap();
+    std::fs::write(
+        dir.path().join("us.csv"),
+        "id,amount,paid,day,note\n7,\"1,234.5\",1,06/01/2024 09:30,x\n",
+    ).unwrap();
+
+    let output = typed(&executor, json!({ "path": "eu.csv", "schema": orders_schema(), "locale": "de-DE" })).await;
+    assert_eq!(output["rows"], json!([
+        { "id": 1001, "amount": 1234.5, "paid": true, "day": "2024-06-01", "note": "first" },
+        { "id": 2, "amount": 0.25, "paid": false, "day": "2023-12-31", "note": null },
+    ]));
+
+    // A column format beats the locale's date format
+    let mut schema = orders_schema();
+    schema[3]["format"] = json!("%m/%d/%Y %H:%M");
+    let output = typed(&executor, json!({ "path": "us.csv", "schema": schema, "locale": "en-US" })).await;
+    assert_eq!(output["rows"][0]["amount"], 1234.5);
+    assert_eq!(output["rows"][0]["day"], "2024-06-01T09:30:00");
+    assert_eq!(output["rows"][0]["paid"], true);
+}
+
+#[tokio::test]
+async fn test_empty_strings_nulls_and_defaults() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "id,note,qty\n1,,\n2,NULL,5\n").unwrap();
+    let schema = json!([
+        { "name": "id", "type": "int" },
+        { "name": "note", "type": "string" },
+        { "name": "qty", "type": "int", "nullable": false, "default": 0 },
+    ]);
+
+    let output = typed(&executor, json!({ "path": "data.csv", "schema": schema })).await;
+    assert_eq!(output["rows"], json!([
+        { "id": 1, "note": null, "qty": 0 },
+        { "id": 2, "note": "NULL", "qty": 5 },
+    ]));
+
+    // Empty strings kept as text; only "NULL" means null
+    let output = typed(&executor, json!({
+        "path": "data.csv",
+        "schema": schema,
+        "null_values": ["NULL"],
+        "on_error": "null",
+    })).await;
+    assert_eq!(output["rows"][0]["note"], "");
+    assert_eq!(output["rows"][1]["note"], Value::Null);
+    assert_eq!(output["violation_count"], 1);
+    assert_eq!(output["violations"][0]["column"], "qty");
+    assert_eq!(output["rows"][0]["qty"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_violation_policies() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "id,amount,paid,day,note\n1,2.5,true,2024-01-01,a\nx,abc,maybe,2024-13-01,b\n3,,false,,c\n").unwrap();
+
+    let err = executor
+        .execute(&task("read_csv_typed", json!({ "path": "data.csv", "schema": orders_schema() })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    assert!(err.to_string().contains("Row 2 column 'id'"), "{}", err);
+
+    let output = typed(&executor, json!({ "path": "data.csv", "schema": orders_schema(), "on_error": "skip" })).await;
+    assert_eq!(output["rows_read"], 3);
+    assert_eq!(output["rows_skipped"], 1);
+    assert_eq!(output["rows"].as_array().unwrap().len(), 2);
+    // The rest of a skipped row is not inspected
+    assert_eq!(output["violations"], json!([{ "row": 2, "column": "id", "value": "x", "error": "not an integer" }]));
+
+    let output = typed(&executor, json!({
+        "path": "data.csv",
+        "schema": orders_schema(),
+        "on_error": "null",
+        "max_violations": 2,
+    })).await;
+    assert_eq!(output["rows"][1], json!({ "id": null, "amount": null, "paid": null, "day": null, "note": "b" }));
+    assert_eq!(output["violation_count"], 4);
+    assert_eq!(output["violations"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_extra_and_missing_columns() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "id,extra\n1,e\n").unwrap();
+    let schema = json!([{ "name": "id", "type": "int" }, { "name": "score", "type": "float", "default": 1.5 }]);
+
+    let err = executor
+        .execute(&task("read_csv_typed", json!({ "path": "data.csv", "schema": schema, "extra_columns": "ignore" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Missing declared columns: score"), "{}", err);
+    let err = executor
+        .execute(&task("read_csv_typed", json!({ "path": "data.csv", "schema": schema, "missing_columns": "null" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Undeclared columns: extra"), "{}", err);
+
+    let output = typed(&executor, json!({
+        "path": "data.csv",
+        "schema": schema,
+        "missing_columns": "null",
+        "extra_columns": "keep",
+    })).await;
+    assert_eq!(output["rows"], json!([{ "id": 1, "score": 1.5, "extra": "e" }]));
+    let output = typed(&executor, json!({
+        "path": "data.csv",
+        "schema": schema,
+        "missing_columns": "null",
+        "extra_columns": "ignore",
+    })).await;
+    assert_eq!(output["rows"], json!([{ "id": 1, "score": 1.5 }]));
+}
+
+#[tokio::test]
+async fn test_profiled_schema_pinned_to_file_and_ndjson_dest() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("data.csv"), "id,price,active,day,name\n1,2.5,true,2024-01-01,a\n2,3,false,,b\n").unwrap();
+
+    let profile = executor
+        .execute(&task("profile", json!({ "path": "data.csv" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(profile["schema"], json!([
+        { "name": "id", "type": "int", "nullable": false },
+        { "name": "price", "type": "float", "nullable": false },
+        { "name": "active", "type": "bool", "nullable": false },
+        { "name": "day", "type": "date", "nullable": true },
+        { "name": "name", "type": "string", "nullable": false },
+    ]));
+    executor
+        .execute(&task("write_json", json!({ "path": "schema.json", "data": { "columns": profile["schema"] } })))
+        .await
+        .unwrap();
+
+    let output = typed(&executor, json!({ "path": "data.csv", "schema_path": "schema.json", "dest": "typed.ndjson" })).await;
+    assert_eq!(output["rows_written"], 2);
+    assert!(output.get("rows").is_none());
+    let lines: Vec<Value> = std::fs::read_to_string(dir.path().join("typed.ndjson"))
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[1], json!({ "id": 2, "price": 3.0, "active": false, "day": null, "name": "b" }));
+
+    let err = executor
+        .execute(&task("read_csv_typed", json!({ "path": "data.csv" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+async fn read_lines(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("read_lines", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_head_tail_and_range() {
+    let (dir, executor) = temp_executor();
+    // Well over one backwards-read chunk
+    let log: String = (1..=20_000).map(|i| format!("line {}\n", i)).collect();
+    std::fs::write(dir.path().join("app.log"), &log).unwrap();
+
+    let head = read_lines(&executor, json!({ "path": "app.log", "head": 2 })).await;
+    assert_eq!(head["lines"], json!(["line 1", "line 2"]));
+    assert_eq!(head["total_lines"], 20_000);
+
+    let tail = read_lines(&executor, json!({ "path": "app.log", "tail": 3 })).await;
+    assert_eq!(tail["lines"], json!(["line 19998", "line 19999", "line 20000"]));
+    assert_eq!(tail["start"], 19_998);
+    assert_eq!(tail["total_lines"], 20_000);
+
+    let tail = read_lines(&executor, json!({ "path": "app.log", "tail": 15_000 })).await;
+    let lines = tail["lines"].as_array().unwrap();
+    assert_eq!(lines.len(), 15_000);
+    assert_eq!(lines[0], "line 5001");
+    assert_eq!(tail["start"], 5001);
+
+    let all = read_lines(&executor, json!({ "path": "app.log", "tail": 50_000 })).await;
+    assert_eq!(all["lines"].as_array().unwrap().len(), 20_000);
+    assert_eq!(all["start"], 1);
+
+    let range = read_lines(&executor, json!({ "path": "app.log", "range": { "start": 10, "end": 12 } })).await;
+    assert_eq!(range["lines"], json!(["line 10", "line 11", "line 12"]));
+}
+
+#[tokio::test]
+async fn test_crlf_and_unterminated_last_line() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("win.txt"), "one\r\ntwo\r\nthree").unwrap();
+
+    let head = read_lines(&executor, json!({ "path": "win.txt", "head": 10 })).await;
+    assert_eq!(head["lines"], json!(["one", "two", "three"]));
+    assert_eq!(head["total_lines"], 3);
+    let tail = read_lines(&executor, json!({ "path": "win.txt", "tail": 2 })).await;
+    assert_eq!(tail["lines"], json!(["two", "three"]));
+    assert_eq!(tail["total_lines"], 3);
+}
+
+#[tokio::test]
+async fn test_invalid_selection() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+
+    for params in [
+        json!({ "path": "a.txt" }),
+        json!({ "path": "a.txt", "head": 1, "tail": 1 }),
+        json!({ "path": "a.txt", "range": { "start": 0, "end": 2 } }),
+        json!({ "path": "a.txt", "range": { "start": 3, "end": 2 } }),
+    ] {
+        let err = executor.execute(&task("read_lines", params.clone())).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{}", params);
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+
+const FEED: &str = r#"<?xml version="1.0"?>
+<feed lang="en">
+  <title>Releases &amp; notes</title>
+  <entry id="1"><name>alpha</name><tag>a</tag><tag>b</tag></entry>
+  <entry id="2"><name>beta</name><summary><![CDATA[<b>bold</b>]]></summary></entry>
+  <empty/>
+</feed>
+"#;
+
+#[tokio::test]
+async fn test_read_xml_maps_attributes_text_and_repeats() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("feed.xml"), FEED).unwrap();
+
+    let output = executor
+        .execute(&task("read_xml", json!({ "path": "feed.xml" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    let feed = &output["feed"];
+    assert_eq!(feed["@attrs"], json!({ "lang": "en" }));
+    assert_eq!(feed["title"], "Releases & notes");
+    assert_eq!(feed["empty"], Value::Null);
+    assert_eq!(feed["entry"][0], json!({ "@attrs": { "id": "1" }, "name": "alpha", "tag": ["a", "b"] }));
+    assert_eq!(feed["entry"][1]["summary"], "<b>bold</b>");
+}
+
+#[tokio::test]
+async fn test_read_xml_custom_keys_and_mixed_text() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("p.xml"), r#"<p class="x">hello<br/></p>"#).unwrap();
+
+    let output = executor
+        .execute(&task("read_xml", json!({ "path": "p.xml", "attrs_key": "_attrs", "text_key": "_text" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output, json!({ "p": { "_attrs": { "class": "x" }, "_text": "hello", "br": null } }));
+}
+
+#[tokio::test]
+async fn test_read_xml_select_subtrees() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("feed.xml"), FEED).unwrap();
+
+    let read = |select: &str| {
+        let task = task("read_xml", json!({ "path": "feed.xml", "select": select }));
+        let executor = &executor;
+        async move { executor.execute(&task).await }
+    };
+
+    let names = read("/feed/entry/name").await.unwrap().output.unwrap();
+    assert_eq!(names, json!(["alpha", "beta"]));
+
+    let second = read("/feed/entry[2]/name").await.unwrap().output.unwrap();
+    assert_eq!(second, json!(["beta"]));
+
+    let tags = read("//tag").await.unwrap().output.unwrap();
+    assert_eq!(tags, json!(["a", "b"]));
+
+    let wildcard = read("feed/*/name").await.unwrap().output.unwrap();
+    assert_eq!(wildcard, json!(["alpha", "beta"]));
+
+    let nothing = read("/feed/missing").await.unwrap().output.unwrap();
+    assert_eq!(nothing, json!([]));
+
+    assert!(matches!(read("/feed/entry[0]").await, Err(Error::InvalidConfig(_))));
+    assert!(matches!(read("/feed/@id").await, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_read_xml_malformed_reports_byte_offset() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("bad.xml"), "<a><b>text</c></a>").unwrap();
+    std::fs::write(dir.path().join("open.xml"), "<a><b>text</b>").unwrap();
+
+    let err = executor
+        .execute(&task("read_xml", json!({ "path": "bad.xml" })))
+        .await
+        .unwrap_err();
+    match err {
+        Error::Io(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+            assert!(e.to_string().contains("bad.xml at byte 10"), "{}", e);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let err = executor
+        .execute(&task("read_xml", json!({ "path": "open.xml" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unclosed element <a>"), "{}", err);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_literal_replace_with_count() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("app.toml"), "version = \"1.2.0\"\n# was 1.2.0\nold = 1.2.0\n").unwrap();
+
+    let output = executor
+        .execute(&task("replace", json!({ "path": "app.toml", "find": "1.2.0", "replace": "1.3.0", "count": 2 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["replacements"], 2);
+    assert_eq!(output["written"], true);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("app.toml")).unwrap(),
+        "version = \"1.3.0\"\n# was 1.3.0\nold = 1.2.0\n"
+    );
+    // Regex metacharacters are literal without `regex`
+    let err = executor
+        .execute(&task("replace", json!({ "path": "app.toml", "find": "1.2.*", "replace": "x" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("No match")));
+    assert!(!dir.path().join("app.toml.tmp").exists());
+}
+
+#[tokio::test]
+async fn test_regex_replace_with_captures() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("Cargo.toml"), "[package]\nversion = \"0.4.1\"\n").unwrap();
+
+    let output = executor
+        .execute(&task("replace", json!({
+            "path": "Cargo.toml",
+            "find": r#"version = "(\d+)\.(\d+)\.\d+""#,
+            "replace": r#"version = "$1.${2}.9""#,
+            "regex": true,
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["replacements"], 1);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap(),
+        "[package]\nversion = \"0.4.9\"\n"
+    );
+
+    let err = executor
+        .execute(&task("replace", json!({ "path": "Cargo.toml", "find": "(unclosed", "replace": "", "regex": true })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("unclosed group")), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_no_match_allowed() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("a.txt"), "abc").unwrap();
+    let before = std::fs::metadata(dir.path().join("a.txt")).unwrap().modified().unwrap();
+
+    let output = executor
+        .execute(&task("replace", json!({ "path": "a.txt", "find": "zzz", "replace": "y", "allow_no_match": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["replacements"], 0);
+    assert_eq!(output["written"], false);
+    assert_eq!(std::fs::metadata(dir.path().join("a.txt")).unwrap().modified().unwrap(), before);
+}
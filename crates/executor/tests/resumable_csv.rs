@@ -0,0 +1,168 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+fn rows(n: usize) -> Vec<Value> {
+    (0..n).map(|i| json!([i, format!("name \"{}\"", i), i as f64 * 1.5, i % 7 == 0])).collect()
+}
+
+async fn reference(executor: &FileExecutor, dir: &Path, rows: &[Value]) -> Vec<u8> {
+    executor
+        .execute(&task("write_csv", json!({
+            "path": "reference.csv", "headers": ["id", "name", "amount", "flag"], "rows": rows,
+        })))
+        .await
+        .unwrap();
+    std::fs::read(dir.join("reference.csv")).unwrap()
+}
+
+fn params(rows: &[Value], extra: Value) -> Value {
+    let mut params = json!({
+        "path": "export.csv",
+        "headers": ["id", "name", "amount", "flag"],
+        "rows": rows,
+        "resume": true,
+        "checkpoint_rows": 100,
+    });
+    params.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    params
+}
+
+fn append(path: &Path, bytes: &[u8]) {
+    std::fs::OpenOptions::new().append(true).open(path).unwrap().write_all(bytes).unwrap();
+}
+
+#[tokio::test]
+async fn test_resume_after_torn_tail_matches_uninterrupted_run() {
+    let (dir, executor) = temp_executor();
+    let all = rows(1000);
+    let expected = reference(&executor, dir.path(), &all).await;
+    let export = dir.path().join("export.csv");
+
+    // Killed after 450 rows: progress recorded at 450, then two more rows made it
+    // to disk unrecorded and a third was torn halfway
+    executor
+        .execute(&task("write_csv", params(&all[..450], json!({ "finalize": false }))))
+        .await
+        .unwrap();
+    assert!(dir.path().join("export.csv.progress.json").exists());
+    append(&export, b"450,\"name \"\"450\"\"\",675,false\n451,\"name \"\"451\"\"\",676.5,false\n452,\"na");
+
+    let output = executor
+        .execute(&task("write_csv", params(&all, json!({ "expected_total_rows": 1000 }))))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["resumed_from"], 450);
+    assert_eq!(output["rows_written"], 550);
+    assert_eq!(output["total_rows"], 1000);
+    assert_eq!(std::fs::read(&export).unwrap(), expected);
+    assert!(!dir.path().join("export.csv.progress.json").exists());
+}
+
+#[tokio::test]
+async fn test_killed_before_first_checkpoint_starts_over() {
+    let (dir, executor) = temp_executor();
+    let all = rows(250);
+    let expected = reference(&executor, dir.path(), &all).await;
+    let export = dir.path().join("export.csv");
+
+    // Header and a few rows on disk, but no progress file yet
+    std::fs::write(&export, &expected[..200]).unwrap();
+
+    let output = executor
+        .execute(&task("write_csv", params(&all, json!({}))))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["resumed_from"], Value::Null);
+    assert_eq!(std::fs::read(&export).unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_chunked_producer_with_offsets() {
+    let (dir, executor) = temp_executor();
+    let all = rows(700);
+    let expected = reference(&executor, dir.path(), &all).await;
+
+    for (start, end) in [(0, 300), (300, 600)] {
+        executor
+            .execute(&task("write_csv", params(&all[start..end], json!({ "rows_offset": start, "finalize": false }))))
+            .await
+            .unwrap();
+    }
+    // A retried batch that was already written is skipped
+    let retried = executor
+        .execute(&task("write_csv", params(&all[300..600], json!({ "rows_offset": 300, "finalize": false }))))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(retried["resumed_from"], 600);
+    assert_eq!(retried["rows_written"], 0);
+
+    executor
+        .execute(&task("write_csv", params(&all[600..], json!({ "rows_offset": 600 }))))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(dir.path().join("export.csv")).unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_mismatched_progress_is_rejected() {
+    let (_dir, executor) = temp_executor();
+    let all = rows(300);
+
+    executor
+        .execute(&task("write_csv", params(&all[..200], json!({ "finalize": false }))))
+        .await
+        .unwrap();
+
+    // A batch starting past what was written would leave a gap
+    let result = executor
+        .execute(&task("write_csv", params(&all[250..], json!({ "rows_offset": 250 }))))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+    let mut other = params(&all, json!({}));
+    other["headers"] = json!(["a", "b", "c", "d"]);
+    let result = executor.execute(&task("write_csv", other)).await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+    // Total does not match what the producer announced
+    let result = executor
+        .execute(&task("write_csv", params(&all, json!({ "expected_total_rows": 301 }))))
+        .await;
+    assert!(matches!(result, Err(Error::Io(_))));
+}
+
+#[tokio::test]
+async fn test_headerless_empty_first_batch_resumes() {
+    let (dir, executor) = temp_executor();
+    let batch = |rows: Vec<Value>, finalize: bool| json!({
+        "path": "export.csv",
+        "has_headers": false,
+        "rows": rows,
+        "resume": true,
+        "finalize": finalize,
+    });
+
+    // Checkpoints zero bytes, which the next batch must accept
+    executor.execute(&task("write_csv", batch(vec![], false))).await.unwrap();
+    let output = executor
+        .execute(&task("write_csv", batch(vec![json!([1, "a"])], true)))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["resumed_from"], 0);
+    assert_eq!(std::fs::read_to_string(dir.path().join("export.csv")).unwrap(), "1,a\n");
+}
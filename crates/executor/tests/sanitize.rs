@@ -0,0 +1,124 @@
+mod common;
+
+use common::temp_executor;
+use local_automation_common::{Error, Task};
+use local_automation_executor::sanitize::{sanitize_filename, Platform, SanitizeOptions};
+use local_automation_executor::Executor;
+use serde_json::json;
+
+fn options(platform: Platform) -> SanitizeOptions {
+    SanitizeOptions { platform, ..SanitizeOptions::default() }
+}
+
+#[test]
+fn test_sanitize_table() {
+    let cases: &[(Platform, &str, &str)] = &[
+        (Platform::All, "report.csv", "report.csv"),
+        (Platform::All, "ACME/Corp\\Ltd.csv", "ACME_Corp_Ltd.csv"),
+        (Platform::All, "../../etc/passwd", ".._.._etc_passwd"),
+        (Platform::All, "con", "_con"),
+        (Platform::All, "NUL.txt", "_NUL.txt"),
+        (Platform::All, "COM1.tar.gz", "_COM1.tar.gz"),
+        (Platform::All, "console.txt", "console.txt"),
+        (Platform::All, "trailing. . ", "trailing"),
+        (Platform::All, "tab\there\n", "tab_here_"),
+        (Platform::All, "what?*<>|:\"", "what_______"),
+        (Platform::All, "", "_"),
+        (Platform::All, ".", "_"),
+        (Platform::All, "..", "_"),
+        (Platform::All, "...", "_"),
+        (Platform::All, "e\u{301}t\u{e9}.txt", "\u{e9}t\u{e9}.txt"),
+        (Platform::All, "😀 party.txt", "😀 party.txt"),
+        (Platform::Windows, "a:b", "a_b"),
+        (Platform::Unix, "what?.txt", "what?.txt"),
+        (Platform::Unix, "con", "con"),
+        (Platform::Unix, "trailing.", "trailing."),
+        (Platform::Unix, "a/b\0c", "a_b_c"),
+        (Platform::Unix, "..", "_"),
+    ];
+
+    for (platform, input, expected) in cases {
+        assert_eq!(
+            sanitize_filename(input, &options(*platform)),
+            *expected,
+            "{:?} on {:?}",
+            input,
+            platform
+        );
+    }
+}
+
+#[test]
+fn test_sanitize_ascii_only_and_custom_replacement() {
+    let opts = SanitizeOptions {
+        allow_unicode: false,
+        replacement: "-".to_string(),
+        ..SanitizeOptions::default()
+    };
+    assert_eq!(sanitize_filename("café/menü.txt", &opts), "caf--men-.txt");
+}
+
+#[test]
+fn test_sanitize_length_cap_keeps_extension_and_uniqueness() {
+    let opts = SanitizeOptions::default();
+    let first = sanitize_filename(&format!("{}1.csv", "a".repeat(300)), &opts);
+    let second = sanitize_filename(&format!("{}2.csv", "a".repeat(300)), &opts);
+
+    assert_eq!(first.len(), 255);
+    assert!(first.ends_with(".csv"));
+    assert_ne!(first, second);
+
+    // Multi-byte characters are never split
+    let wide = sanitize_filename(&"é".repeat(200), &opts);
+    assert!(wide.len() <= 255);
+}
+
+#[tokio::test]
+async fn test_sanitize_operation_disambiguates_collisions() {
+    let (_dir, executor) = temp_executor();
+
+    let output = executor
+        .execute(&Task::new(
+            "file".to_string(),
+            "sanitize_filename".to_string(),
+            json!({ "names": ["a/b.txt", "a_b.txt", "A_B.txt", "c.txt"] }),
+        ))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    let outputs: Vec<&str> = output["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["output"].as_str().unwrap())
+        .collect();
+    assert_eq!(outputs, ["a_b.txt", "a_b-2.txt", "A_B-3.txt", "c.txt"]);
+    assert_eq!(output["results"][1]["collided"], true);
+    assert_eq!(output["results"][3]["changed"], false);
+
+    let single = executor
+        .execute(&Task::new(
+            "file".to_string(),
+            "sanitize_filename".to_string(),
+            json!({ "name": "x:y", "platform": "unix" }),
+        ))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(single["output"], "x:y");
+}
+
+#[tokio::test]
+async fn test_sanitize_rejects_unsafe_replacement() {
+    let (_dir, executor) = temp_executor();
+    let result = executor
+        .execute(&Task::new(
+            "file".to_string(),
+            "sanitize_filename".to_string(),
+            json!({ "name": "a/b", "replacement": "/" }),
+        ))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
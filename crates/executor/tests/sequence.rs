@@ -0,0 +1,149 @@
+mod common;
+
+use common::executor_task;
+use local_automation_common::Error;
+use local_automation_executor::sequence::format_sequence;
+use local_automation_executor::{Executor, SequenceExecutor, StateStore};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_next_batch_and_formatting() {
+    let dir = tempdir().unwrap();
+    let sequence = SequenceExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+
+    let first = sequence
+        .execute(&executor_task("sequence", "next", json!({ "name": "invoices", "start": 1000, "step": 5, "format": "INV-{:06}" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(first, json!({ "name": "invoices", "value": 1000, "formatted": "INV-001000" }));
+
+    // Defaults only apply on first use
+    let batch = sequence
+        .execute(&executor_task("sequence", "next_batch", json!({ "name": "invoices", "count": 3, "start": 1 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(batch["values"], json!([1005, 1010, 1015]));
+    assert_eq!(batch["formatted"], json!(["INV-001005", "INV-001010", "INV-001015"]));
+
+    let peek = sequence
+        .execute(&executor_task("sequence", "peek", json!({ "name": "invoices" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(peek["next"], 1020);
+    assert_eq!(peek["formatted"], "INV-001020");
+    let missing = sequence
+        .execute(&executor_task("sequence", "peek", json!({ "name": "other" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(missing["exists"], false);
+}
+
+#[tokio::test]
+async fn test_values_from_failed_tasks_are_never_reissued() {
+    let dir = tempdir().unwrap();
+    let sequence = SequenceExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+
+    let taken = sequence
+        .execute(&executor_task("sequence", "next", json!({ "name": "batch" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(taken["value"], 1);
+    // Whatever used value 1 fails here; the next caller still gets 2, leaving a gap
+    let next = sequence
+        .execute(&executor_task("sequence", "next", json!({ "name": "batch" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(next["value"], 2);
+}
+
+#[tokio::test]
+async fn test_concurrent_stores_never_repeat() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    // Separate store instances coordinate only through the file lock, like separate processes
+    let mut handles = Vec::new();
+    for _ in 0..8 {
+        let sequence = SequenceExecutor::new(Arc::new(StateStore::new(path.clone())));
+        handles.push(tokio::spawn(async move {
+            let mut values = Vec::new();
+            for _ in 0..10 {
+                let output = sequence
+                    .execute(&executor_task("sequence", "next_batch", json!({ "name": "ids", "count": 2 })))
+                    .await
+                    .unwrap()
+                    .output
+                    .unwrap();
+                values.extend(output["values"].as_array().unwrap().iter().map(|v| v.as_i64().unwrap()));
+            }
+            values
+        }));
+    }
+
+    let mut all = Vec::new();
+    for handle in handles {
+        all.extend(handle.await.unwrap());
+    }
+    let unique: HashSet<i64> = all.iter().copied().collect();
+    assert_eq!(unique.len(), 160);
+    assert_eq!(unique, (1..=160).collect());
+}
+
+#[tokio::test]
+async fn test_admin_set() {
+    let dir = tempdir().unwrap();
+    let sequence = SequenceExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+
+    let set = sequence
+        .execute(&executor_task("sequence", "set", json!({ "name": "n", "next": 50, "format": "B{}" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(set["previous"], json!(null));
+    let next = sequence
+        .execute(&executor_task("sequence", "next", json!({ "name": "n" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(next["formatted"], "B50");
+
+    let set = sequence
+        .execute(&executor_task("sequence", "set", json!({ "name": "n", "format": null })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(set["next"], 51);
+    assert_eq!(set["format"], json!(null));
+
+    let result = sequence.execute(&executor_task("sequence", "set", json!({ "name": "n", "step": 0 }))).await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    let result = sequence.execute(&executor_task("sequence", "set", json!({ "name": "n", "format": "no placeholder" }))).await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
+
+#[test]
+fn test_format_sequence() {
+    assert_eq!(format_sequence("INV-{:06}", 42).unwrap(), "INV-000042");
+    assert_eq!(format_sequence("{:4}|", 7).unwrap(), "   7|");
+    assert_eq!(format_sequence("#{}", -3).unwrap(), "#-3");
+    assert!(format_sequence("{:x}", 1).is_err());
+    assert!(format_sequence("{", 1).is_err());
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+use std::path::Path;
+
+async fn snapshot(executor: &FileExecutor, at: &str, extra: Value) -> Value {
+    let mut params = json!({ "source": "data", "snapshots_dir": "snapshots", "at": at });
+    params.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    executor.execute(&task("snapshot", params)).await.unwrap().output.unwrap()
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[cfg(unix)]
+fn links(path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).unwrap().nlink()
+}
+
+#[tokio::test]
+async fn test_three_snapshots_over_a_mutating_tree() {
+    let (dir, executor) = temp_executor();
+    let data = dir.path().join("data");
+    std::fs::create_dir_all(data.join("sub")).unwrap();
+    std::fs::write(data.join("a.txt"), "alpha").unwrap();
+    std::fs::write(data.join("sub/b.txt"), "bravo").unwrap();
+
+    let first = snapshot(&executor, "2024-03-01T02:00:00Z", json!({})).await;
+    assert_eq!(first["snapshot"], "2024-03-01T02-00-00Z");
+    assert_eq!(first["previous"], Value::Null);
+    assert_eq!(first["copied"], 2);
+    assert_eq!(first["linked"], 0);
+
+    std::fs::write(data.join("a.txt"), "alpha, edited").unwrap();
+    std::fs::write(data.join("c.txt"), "charlie").unwrap();
+    let second = snapshot(&executor, "2024-03-02T02:00:00Z", json!({})).await;
+    assert_eq!(second["previous"], "2024-03-01T02-00-00Z");
+    assert_eq!(second["linked"], 1);
+    assert_eq!(second["copied"], 2);
+    assert_eq!(second["new_bytes"], "alpha, edited".len() + "charlie".len());
+
+    std::fs::remove_file(data.join("sub/b.txt")).unwrap();
+    let third = snapshot(&executor, "2024-03-03T02:00:00Z", json!({ "compare": "hash" })).await;
+    assert_eq!(third["linked"], 2);
+    assert_eq!(third["copied"], 0);
+
+    let snaps = dir.path().join("snapshots");
+    assert_eq!(read(&snaps.join("2024-03-01T02-00-00Z/a.txt")), "alpha");
+    assert_eq!(read(&snaps.join("2024-03-02T02-00-00Z/a.txt")), "alpha, edited");
+    assert_eq!(read(&snaps.join("2024-03-03T02-00-00Z/c.txt")), "charlie");
+    assert!(!snaps.join("2024-03-03T02-00-00Z/sub/b.txt").exists());
+    assert!(snaps.join("2024-03-03T02-00-00Z/sub").is_dir());
+
+    #[cfg(unix)]
+    {
+        assert_eq!(links(&snaps.join("2024-03-01T02-00-00Z/a.txt")), 1);
+        assert_eq!(links(&snaps.join("2024-03-01T02-00-00Z/sub/b.txt")), 2);
+        assert_eq!(links(&snaps.join("2024-03-03T02-00-00Z/a.txt")), 2);
+        assert_eq!(links(&snaps.join("2024-03-03T02-00-00Z/c.txt")), 2);
+    }
+}
+
+#[tokio::test]
+async fn test_retention_prunes_expired_snapshots() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("data/a.txt"), "alpha").unwrap();
+    let keep = json!({ "keep_daily": 2, "keep_weekly": 2 });
+
+    // Two snapshots on the last day, one a day for the rest of two weeks
+    let mut last = Value::Null;
+    for at in [
+        "2024-02-26T02:00:00Z", "2024-02-28T02:00:00Z", "2024-03-04T02:00:00Z",
+        "2024-03-05T02:00:00Z", "2024-03-06T02:00:00Z", "2024-03-06T14:00:00Z",
+    ] {
+        last = snapshot(&executor, at, keep.clone()).await;
+    }
+    assert!(last["prune_errors"].as_array().unwrap().is_empty());
+
+    let mut remaining: Vec<String> = std::fs::read_dir(dir.path().join("snapshots")).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    remaining.sort();
+    // Latest two days, plus the newest of the previous ISO week
+    assert_eq!(remaining, [
+        "2024-02-28T02-00-00Z", "2024-03-05T02-00-00Z", "2024-03-06T14-00-00Z",
+    ]);
+    assert_eq!(read(&dir.path().join("snapshots/2024-03-06T14-00-00Z/a.txt")), "alpha");
+}
+
+#[tokio::test]
+async fn test_leftovers_are_swept_and_names_are_unique() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("data/a.txt"), "alpha").unwrap();
+    std::fs::create_dir_all(dir.path().join("snapshots/.incomplete-2024-03-09T00-00-00Z")).unwrap();
+
+    let output = snapshot(&executor, "2024-03-08T00:00:00Z", json!({})).await;
+    // A half-built snapshot is never used as the base
+    assert_eq!(output["previous"], Value::Null);
+    assert!(!dir.path().join("snapshots/.incomplete-2024-03-09T00-00-00Z").exists());
+
+    let again = executor
+        .execute(&task("snapshot", json!({
+            "source": "data", "snapshots_dir": "snapshots", "at": "2024-03-08T00:00:00Z",
+        })))
+        .await;
+    assert!(matches!(again, Err(Error::InvalidConfig(_))));
+
+    let nested = executor
+        .execute(&task("snapshot", json!({ "source": "data", "snapshots_dir": "data/snaps" })))
+        .await;
+    assert!(matches!(nested, Err(Error::InvalidConfig(_))));
+}
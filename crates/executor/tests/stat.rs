@@ -0,0 +1,98 @@
+mod common;
+
+use common::{task, temp_executor};
+use chrono::{DateTime, Duration, Utc};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_stat_reports_metadata() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+    let before = Utc::now();
+    executor
+        .execute(&task("write", json!({ "path": "data.txt", "content": "hello world" })))
+        .await
+        .unwrap();
+    let after = Utc::now();
+
+    let output = executor
+        .execute(&task("stat", json!({ "path": "data.txt" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["exists"], true);
+    assert_eq!(output["size"], 11);
+    assert_eq!(output["is_file"], true);
+    assert_eq!(output["is_dir"], false);
+    assert_eq!(output["is_symlink"], false);
+    // Filesystem clocks can lag the system clock slightly and output is in millis
+    let modified: DateTime<Utc> = output["modified"].as_str().unwrap().parse().unwrap();
+    assert!(modified >= before - Duration::milliseconds(50), "{} < {}", modified, before);
+    assert!(modified <= after + Duration::milliseconds(50), "{} > {}", modified, after);
+    assert!(output["accessed"].is_string());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir.path().join("data.txt"), std::fs::Permissions::from_mode(0o640)).unwrap();
+        let output = executor
+            .execute(&task("stat", json!({ "path": "data.txt" })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(output["mode"], 0o640);
+    }
+
+    let output = executor
+        .execute(&task("stat", json!({ "path": "sub" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["is_dir"], true);
+
+    let err = executor
+        .execute(&task("stat", json!({ "path": "missing.txt" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    let output = executor
+        .execute(&task("stat", json!({ "path": "missing.txt", "allow_missing": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output, json!({ "path": "missing.txt", "exists": false }));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stat_symlink() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("target.txt"), "abc").unwrap();
+    std::os::unix::fs::symlink("target.txt", dir.path().join("link.txt")).unwrap();
+    std::os::unix::fs::symlink("gone.txt", dir.path().join("dangling.txt")).unwrap();
+
+    let output = executor
+        .execute(&task("stat", json!({ "path": "link.txt" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["is_symlink"], true);
+    assert_eq!(output["is_file"], true);
+    assert_eq!(output["size"], 3);
+
+    let output = executor
+        .execute(&task("stat", json!({ "path": "dangling.txt" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["is_symlink"], true);
+    assert_eq!(output["is_file"], false);
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use common::{executor_task, task};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::{Executor, StateExecutor, StateStore};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn write_with_mtime(path: &Path, secs_ago: u64) {
+    std::fs::write(path, "data").unwrap();
+    let mtime = SystemTime::now() - Duration::from_secs(secs_ago);
+    std::fs::File::options().write(true).open(path).unwrap().set_modified(mtime).unwrap();
+}
+
+#[tokio::test]
+async fn test_get_set_delete_state() {
+    let dir = tempdir().unwrap();
+    let store = Arc::new(StateStore::new(dir.path().join("state.json")));
+    let state = StateExecutor::new(store);
+
+    let missing = state
+        .execute(&executor_task("state", "get_state", json!({ "key": "k", "default": 0 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(missing, json!({ "key": "k", "found": false, "value": 0 }));
+
+    state
+        .execute(&executor_task("state", "set_state", json!({ "key": "k", "value": { "a": 1 } })))
+        .await
+        .unwrap();
+    let found = state
+        .execute(&executor_task("state", "get_state", json!({ "key": "k" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(found["value"], json!({ "a": 1 }));
+
+    // The store survives a fresh instance
+    let reopened = StateExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+    let deleted = reopened
+        .execute(&executor_task("state", "delete_state", json!({ "key": "k" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(deleted["deleted"], true);
+}
+
+#[tokio::test]
+async fn test_set_state_only_if_greater_and_expect() {
+    let dir = tempdir().unwrap();
+    let state = StateExecutor::new(Arc::new(StateStore::new(dir.path().join("state.json"))));
+
+    for (value, expected) in [(5, 5), (3, 5), (9, 9)] {
+        let output = state
+            .execute(&executor_task("state", "set_state", json!({ "key": "mark", "value": value, "only_if_greater": true })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(output["value"], expected);
+    }
+
+    let stale_cas = state
+        .execute(&executor_task("state", "set_state", json!({ "key": "mark", "value": 1, "expect": 5 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(stale_cas["updated"], false);
+    assert_eq!(stale_cas["value"], 9);
+
+    let cas = state
+        .execute(&executor_task("state", "set_state", json!({ "key": "mark", "value": 1, "expect": 9 })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(cas["updated"], true);
+}
+
+#[tokio::test]
+async fn test_concurrent_updates_are_not_lost() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    let mut handles = Vec::new();
+    for _ in 0..32 {
+        // Separate store instances behave like separate processes
+        let store = StateStore::new(path.clone());
+        handles.push(tokio::spawn(async move {
+            store
+                .update("counter", |v| Ok(Some(json!(v.and_then(|v| v.as_u64()).unwrap_or(0) + 1))))
+                .await
+                .unwrap();
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    assert_eq!(StateStore::new(path).get("counter").await.unwrap(), Some(json!(32)));
+}
+
+#[tokio::test]
+async fn test_incremental_runs_process_each_file_once() {
+    let dir = tempdir().unwrap();
+    let inbox = dir.path().join("inbox");
+    std::fs::create_dir(&inbox).unwrap();
+    let store = Arc::new(StateStore::new(dir.path().join("state.json")));
+    let files = FileExecutor::new(dir.path().to_path_buf()).with_state_store(store.clone());
+    let state = StateExecutor::new(store);
+
+    let mut processed: HashMap<String, u32> = HashMap::new();
+    let batches: [&[(&str, u64)]; 4] = [
+        &[("a.csv", 300), ("b.csv", 290)],
+        &[("c.csv", 200)],
+        &[],
+        &[("d.csv", 100), ("e.csv", 90)],
+    ];
+
+    for batch in batches {
+        for (name, secs_ago) in batch {
+            write_with_mtime(&inbox.join(name), *secs_ago);
+        }
+
+        // One "run": list new files, process them, then advance the mark
+        let output = files
+            .execute(&task("list", json!({ "path": "inbox", "newer_than_state": "ingest.last_mtime" })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        for name in output["files"].as_array().unwrap() {
+            *processed.entry(name.as_str().unwrap().to_string()).or_default() += 1;
+        }
+        if !output["high_water_mark"].is_null() {
+            state
+                .execute(&executor_task(
+                    "state",
+                    "set_state",
+                    json!({ "key": "ingest.last_mtime", "value": output["high_water_mark"], "only_if_greater": true }),
+                ))
+                .await
+                .unwrap();
+        }
+    }
+
+    let mut names: Vec<_> = processed.keys().cloned().collect();
+    names.sort();
+    assert_eq!(names, ["a.csv", "b.csv", "c.csv", "d.csv", "e.csv"]);
+    assert!(processed.values().all(|&n| n == 1), "{:?}", processed);
+}
+
+#[tokio::test]
+async fn test_newer_than_state_requires_store() {
+    let dir = tempdir().unwrap();
+    let files = FileExecutor::new(dir.path().to_path_buf());
+    let result = files
+        .execute(&task("list", json!({ "path": ".", "newer_than_state": "x" })))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_import_existing_only_leaves_new_files() {
+    let dir = tempdir().unwrap();
+    let inbox = dir.path().join("inbox");
+    std::fs::create_dir(&inbox).unwrap();
+    let store = Arc::new(StateStore::new(dir.path().join("state.json")));
+    let files = FileExecutor::new(dir.path().to_path_buf()).with_state_store(store.clone());
+
+    for i in 0..20 {
+        write_with_mtime(&inbox.join(format!("old-{:02}.csv", i)), 3600 + i);
+    }
+    write_with_mtime(&inbox.join("notes.txt"), 7200);
+    write_with_mtime(&inbox.join("late.csv"), 60);
+    let cutoff = chrono::Utc::now() - chrono::Duration::seconds(600);
+    let params = json!({
+        "path": "inbox", "state_key": "ingest.last_mtime", "filter": "*.csv",
+        "mark_before": cutoff, "dry_run": true,
+    });
+
+    let preview = files
+        .execute(&task("import_existing", params.clone()))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(preview["registered"], 20);
+    assert_eq!(preview["left_new"], 1);
+    assert_eq!(preview["updated"], false);
+    assert_eq!(store.get("ingest.last_mtime").await.unwrap(), None);
+
+    let mut params = params;
+    params["dry_run"] = json!(false);
+    let imported = files
+        .execute(&task("import_existing", params.clone()))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(imported["updated"], true);
+
+    write_with_mtime(&inbox.join("fresh.csv"), 0);
+    let output = files
+        .execute(&task("list", json!({ "path": "inbox", "newer_than_state": "ingest.last_mtime" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    let mut names: Vec<&str> = output["files"].as_array().unwrap().iter().map(|f| f.as_str().unwrap()).collect();
+    names.sort();
+    assert_eq!(names, ["fresh.csv", "late.csv"]);
+
+    // Re-importing an older tree never moves the mark back
+    let again = files
+        .execute(&task("import_existing", json!({
+            "path": "inbox", "state_key": "ingest.last_mtime", "filter": "old-*",
+        })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(again["updated"], false);
+}
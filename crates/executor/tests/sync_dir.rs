@@ -0,0 +1,94 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn write(root: &Path, name: &str, content: &str) {
+    let path = root.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+async fn sync(executor: &FileExecutor, params: Value) -> Value {
+    executor.execute(&task("sync_dir", params)).await.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_sync_dir_mirrors_source() {
+    let (dir, executor) = temp_executor();
+    write(dir.path(), "src/a.txt", "a");
+    write(dir.path(), "src/nested/b.txt", "b");
+
+    let output = sync(&executor, json!({ "from": "src", "to": "dest" })).await;
+    assert_eq!(output["copied"], json!(["a.txt", "nested/b.txt"]));
+    assert_eq!(output["totals"]["bytes_copied"], 2);
+    assert_eq!(std::fs::read_to_string(dir.path().join("dest/nested/b.txt")).unwrap(), "b");
+
+    // A second run has nothing left to do
+    let output = sync(&executor, json!({ "from": "src", "to": "dest" })).await;
+    assert_eq!(output["copied"], json!([]));
+    assert_eq!(output["skipped"], json!(["a.txt", "nested/b.txt"]));
+
+    write(dir.path(), "src/a.txt", "changed");
+    write(dir.path(), "dest/extra.txt", "x");
+    write(dir.path(), "dest/old/stale.txt", "x");
+    let output = sync(&executor, json!({ "from": "src", "to": "dest" })).await;
+    assert_eq!(output["copied"], json!(["a.txt"]));
+    assert_eq!(output["deleted"], json!([]));
+    assert!(dir.path().join("dest/extra.txt").exists());
+
+    let output = sync(&executor, json!({ "from": "src", "to": "dest", "delete_extraneous": true })).await;
+    assert_eq!(output["deleted"], json!(["extra.txt", "old/stale.txt"]));
+    assert_eq!(output["deleted_dirs"], json!(["old"]));
+    assert_eq!(output["totals"]["deleted"], 2);
+    assert!(!dir.path().join("dest/old").exists());
+    assert_eq!(std::fs::read_to_string(dir.path().join("dest/a.txt")).unwrap(), "changed");
+}
+
+#[tokio::test]
+async fn test_sync_dir_hash_compare() {
+    let (dir, executor) = temp_executor();
+    write(dir.path(), "src/a.txt", "one");
+    write(dir.path(), "src/b.txt", "two");
+    write(dir.path(), "dest/a.txt", "one");
+    write(dir.path(), "dest/b.txt", "TWO");
+
+    // Same sizes but different mtimes: the fast path copies both
+    let output = sync(&executor, json!({ "from": "src", "to": "dest", "compare": "hash", "dry_run": true })).await;
+    assert_eq!(output["copied"], json!(["b.txt"]));
+    assert_eq!(output["skipped"], json!(["a.txt"]));
+    let output = sync(&executor, json!({ "from": "src", "to": "dest", "dry_run": true })).await;
+    assert_eq!(output["copied"], json!(["a.txt", "b.txt"]));
+}
+
+#[tokio::test]
+async fn test_sync_dir_dry_run_touches_nothing() {
+    let (dir, executor) = temp_executor();
+    write(dir.path(), "src/a.txt", "a");
+    write(dir.path(), "dest/extra.txt", "x");
+
+    let output = sync(&executor, json!({ "from": "src", "to": "dest", "delete_extraneous": true, "dry_run": true })).await;
+    assert_eq!(output["dry_run"], true);
+    assert_eq!(output["copied"], json!(["a.txt"]));
+    assert_eq!(output["deleted"], json!(["extra.txt"]));
+    assert!(!dir.path().join("dest/a.txt").exists());
+    assert!(dir.path().join("dest/extra.txt").exists());
+}
+
+#[tokio::test]
+async fn test_sync_dir_rejects_overlap() {
+    let (dir, executor) = temp_executor();
+    write(dir.path(), "src/a.txt", "a");
+
+    for (from, to) in [("src", "src/inner"), ("src", ".")] {
+        let err = executor
+            .execute(&task("sync_dir", json!({ "from": from, "to": to, "delete_extraneous": true })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+    }
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use tempfile::tempdir;
+
+fn build_tree(root: &std::path::Path) {
+    std::fs::create_dir_all(root.join("site/assets/empty")).unwrap();
+    std::fs::write(root.join("site/index.html"), "<h1>hi</h1>").unwrap();
+    std::fs::write(root.join("site/assets/run.sh"), "#!/bin/sh\necho hi\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(root.join("site/assets/run.sh"), std::fs::Permissions::from_mode(0o750)).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_tar_round_trips_nested_tree() {
+    for compression in ["none", "gzip"] {
+        let (dir, executor) = temp_executor();
+        build_tree(dir.path());
+    
+        let output = executor
+            .execute(&task("tar", json!({ "sources": ["site"], "dest": "site.tar.gz", "compression": compression })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(output["entries"], json!(["assets/run.sh", "index.html"]));
+        assert_eq!(output["uncompressed_bytes"], 11 + 18);
+
+        let output = executor
+            .execute(&task("untar", json!({ "path": "site.tar.gz", "dest_dir": "out", "compression": compression })))
+            .await
+            .unwrap()
+            .output
+            .unwrap();
+        assert_eq!(output["entries"], json!(["assets/run.sh", "index.html"]));
+        assert_eq!(output["skipped"], json!([]));
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("out/index.html")).unwrap(), "<h1>hi</h1>");
+        assert!(dir.path().join("out/assets/empty").is_dir());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join("out/assets/run.sh")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o750);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_tar_uses_shared_codecs() {
+    let (dir, executor) = temp_executor();
+    build_tree(dir.path());
+
+    // Codec from the extension, then sniffed back from the content on untar
+    let output = executor
+        .execute(&task("tar", json!({ "sources": ["site"], "dest": "site.tar.gz" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["compression"], "gzip");
+    assert_eq!(output["dest"], json!(dir.path().join("site.tar.gz")));
+    std::fs::rename(dir.path().join("site.tar.gz"), dir.path().join("site.archive")).unwrap();
+    let output = executor
+        .execute(&task("untar", json!({ "path": "site.archive", "dest_dir": "out" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["entries"], json!(["assets/run.sh", "index.html"]));
+    assert_eq!(output["dest_dir"], json!(dir.path().join("out")));
+
+    #[cfg(feature = "zstd")]
+    {
+        executor
+            .execute(&task("tar", json!({ "sources": ["site"], "dest": "site.tar.zst", "compression": "zstd" })))
+            .await
+            .unwrap();
+        assert!(std::fs::read(dir.path().join("site.tar.zst")).unwrap().starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+        executor
+            .execute(&task("untar", json!({ "path": "site.tar.zst", "dest_dir": "zst" })))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("zst/index.html")).unwrap(), "<h1>hi</h1>");
+    }
+
+    let err = executor
+        .execute(&task("tar", json!({ "sources": ["site"], "dest": "x.tar", "compression": "rar" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_untar_conflicts_need_overwrite() {
+    let (dir, executor) = temp_executor();
+    build_tree(dir.path());
+
+    executor
+        .execute(&task("tar", json!({ "sources": ["site/index.html"], "dest": "one.tar" })))
+        .await
+        .unwrap();
+    std::fs::create_dir(dir.path().join("out")).unwrap();
+    std::fs::write(dir.path().join("out/index.html"), "old").unwrap();
+
+    let err = executor
+        .execute(&task("untar", json!({ "path": "one.tar", "dest_dir": "out" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("already exists")), "{:?}", err);
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/index.html")).unwrap(), "old");
+
+    executor
+        .execute(&task("untar", json!({ "path": "one.tar", "dest_dir": "out", "overwrite": true })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/index.html")).unwrap(), "<h1>hi</h1>");
+}
+
+fn raw_entry(builder: &mut tar::Builder<std::fs::File>, name: &str, kind: tar::EntryType, link: Option<&str>, data: &[u8]) {
+    let mut header = tar::Header::new_old();
+    header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+    header.set_entry_type(kind);
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    if let Some(link) = link {
+        header.as_old_mut().linkname[..link.len()].copy_from_slice(link.as_bytes());
+    }
+    header.set_cksum();
+    builder.append(&header, data).unwrap();
+}
+
+#[tokio::test]
+async fn test_untar_rejects_traversal() {
+    let dir = tempdir().unwrap();
+    let work = dir.path().join("work");
+    std::fs::create_dir(&work).unwrap();
+    let mut builder = tar::Builder::new(std::fs::File::create(work.join("evil.tar")).unwrap());
+    raw_entry(&mut builder, "ok.txt", tar::EntryType::Regular, None, b"fine");
+    raw_entry(&mut builder, "../escape.txt", tar::EntryType::Regular, None, b"bad");
+    raw_entry(&mut builder, "link", tar::EntryType::Symlink, Some("/etc"), b"");
+    builder.finish().unwrap();
+    let executor = FileExecutor::new(work.clone());
+
+    let err = executor
+        .execute(&task("untar", json!({ "path": "evil.tar", "dest_dir": "out" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(ref m) if m.contains("../escape.txt")), "{:?}", err);
+    assert!(!work.join("out").exists());
+
+    let output = executor
+        .execute(&task("untar", json!({ "path": "evil.tar", "dest_dir": "out", "on_unsafe": "skip" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["entries"], json!(["ok.txt"]));
+    assert_eq!(output["skipped"], json!(["../escape.txt", "link"]));
+    assert!(!dir.path().join("escape.txt").exists());
+    assert!(work.join("out/link").symlink_metadata().is_err());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_read_modify_write_round_trip() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("Cargo.toml"), r#"
+[package]
+name = "demo"
+version = "0.1.0"
+released = 2024-03-01T10:00:00Z
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+
+[[bin]]
+name = "cli"
+"#).unwrap();
+
+    let mut manifest = executor
+        .execute(&task("read_toml", json!({ "path": "Cargo.toml" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(manifest["package"]["released"], "2024-03-01T10:00:00Z");
+    assert_eq!(manifest["dependencies"]["serde"]["features"], json!(["derive"]));
+    assert_eq!(manifest["bin"], json!([{ "name": "cli" }]));
+
+    manifest["package"]["version"] = json!("0.2.0");
+    executor
+        .execute(&task("write_toml", json!({ "path": "Cargo.toml", "data": manifest })))
+        .await
+        .unwrap();
+
+    let reread = executor
+        .execute(&task("read_toml", json!({ "path": "Cargo.toml" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(reread["package"]["version"], "0.2.0");
+    assert_eq!(reread["package"]["name"], "demo");
+    assert_eq!(reread["bin"], json!([{ "name": "cli" }]));
+}
+
+#[tokio::test]
+async fn test_unrepresentable_values() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("bad.toml"), "a = 1\na = 2\n").unwrap();
+
+    for data in [json!([1, 2]), json!({ "a": { "b": null } }), json!("text")] {
+        let err = executor
+            .execute(&task("write_toml", json!({ "path": "out.toml", "data": data })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{:?}", err);
+    }
+    assert!(!dir.path().join("out.toml").exists());
+
+    let err = executor
+        .execute(&task("read_toml", json!({ "path": "bad.toml" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    assert!(err.to_string().contains("line 2"), "{}", err);
+}
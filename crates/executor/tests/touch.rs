@@ -0,0 +1,52 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+
+#[tokio::test]
+async fn test_touch_creates_and_bumps_mtime() {
+    let (dir, executor) = temp_executor();
+
+    let output = executor
+        .execute(&task("touch", json!({ "path": "stage1.done" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["created"], true);
+    assert_eq!(std::fs::metadata(dir.path().join("stage1.done")).unwrap().len(), 0);
+
+    // Existing content is kept; only the mtime moves
+    let marker = dir.path().join("stage1.done");
+    std::fs::write(&marker, "keep").unwrap();
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    std::fs::File::options().write(true).open(&marker).unwrap().set_modified(old).unwrap();
+
+    let output = executor
+        .execute(&task("touch", json!({ "path": "stage1.done" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["created"], false);
+    let modified = std::fs::metadata(&marker).unwrap().modified().unwrap();
+    assert!(modified > old + Duration::from_secs(3000));
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "keep");
+
+    let err = executor
+        .execute(&task("touch", json!({ "path": "runs/2024/stage2.done" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    let output = executor
+        .execute(&task("touch", json!({ "path": "runs/2024/stage2.done", "create_parents": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["created"], true);
+    assert!(dir.path().join("runs/2024/stage2.done").is_file());
+}
@@ -0,0 +1,227 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+async fn begin(executor: &FileExecutor) -> String {
+    let output = executor.execute(&task("txn_begin", json!({}))).await.unwrap().output.unwrap();
+    output["txn_id"].as_str().unwrap().to_string()
+}
+
+// index.json first, declaring that both data files must land before it
+async fn stage_publish(executor: &FileExecutor, txn: &str) {
+    executor
+        .execute(&task("write_json", json!({
+            "txn": txn,
+            "path": "index.json",
+            "data": { "files": ["data/a.json", "data/b.json"] },
+            "txn_depends_on": ["data/a.json", "./data/b.json"],
+        })))
+        .await
+        .unwrap();
+    for name in ["a", "b"] {
+        executor
+            .execute(&task("write", json!({
+                "txn": txn,
+                "path": format!("data/{}.json", name),
+                "content": format!("\"new {}\"", name),
+            })))
+            .await
+            .unwrap();
+    }
+}
+
+fn read(base: &Path, path: &str) -> Option<String> {
+    std::fs::read_to_string(base.join(path)).ok()
+}
+
+#[tokio::test]
+async fn test_commit_orders_dependencies_first() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("data/a.json"), "\"old a\"").unwrap();
+
+    let txn = begin(&executor).await;
+    stage_publish(&executor, &txn).await;
+    // Nothing is visible before the commit
+    assert_eq!(read(dir.path(), "data/a.json").as_deref(), Some("\"old a\""));
+    assert!(read(dir.path(), "index.json").is_none());
+
+    let output = executor
+        .execute(&task("txn_commit", json!({ "txn_id": txn })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["committed"], json!(["data/a.json", "data/b.json", "index.json"]));
+    assert_eq!(output["journal"]["entries"][0], json!({ "path": "data/a.json", "existed": true }));
+    assert_eq!(output["journal"]["entries"][2], json!({ "path": "index.json", "existed": false }));
+    assert_eq!(read(dir.path(), "data/a.json").as_deref(), Some("\"new a\""));
+    assert!(read(dir.path(), "index.json").is_some());
+    assert!(!dir.path().join(".txn").join(&txn).exists());
+
+    // A finished transaction is gone
+    let err = executor.execute(&task("txn_commit", json!({ "txn_id": txn }))).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+}
+
+#[tokio::test]
+async fn test_rollback_and_invalid_use() {
+    let (dir, executor) = temp_executor();
+
+    let txn = begin(&executor).await;
+    stage_publish(&executor, &txn).await;
+    let output = executor
+        .execute(&task("txn_rollback", json!({ "txn_id": txn })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["discarded"], json!(["index.json", "data/a.json", "data/b.json"]));
+    assert!(!dir.path().join(".txn").join(&txn).exists());
+    assert!(read(dir.path(), "index.json").is_none());
+
+    let txn = begin(&executor).await;
+    let err = executor
+        .execute(&task("append", json!({ "txn": txn, "path": "log.txt", "content": "x" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("cannot be part of a transaction")));
+
+    for (path, depends) in [("x.txt", "y.txt"), ("y.txt", "x.txt")] {
+        executor
+            .execute(&task("write", json!({ "txn": txn, "path": path, "content": "", "txn_depends_on": [depends] })))
+            .await
+            .unwrap();
+    }
+    let err = executor.execute(&task("txn_commit", json!({ "txn_id": txn }))).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("cycle")));
+    assert!(read(dir.path(), "x.txt").is_none());
+
+    let err = executor
+        .execute(&task("write", json!({ "txn": "nope", "path": "a.txt", "content": "" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+
+    // An absolute target would be "staged" onto the live file itself
+    let live = dir.path().join("live.txt");
+    std::fs::write(&live, "keep").unwrap();
+    let txn = begin(&executor).await;
+    for path in [live.to_string_lossy().into_owned(), ".".to_string()] {
+        let err = executor
+            .execute(&task("write", json!({ "txn": txn, "path": path, "content": "clobbered" })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "{}: {:?}", path, err);
+    }
+    executor.execute(&task("txn_commit", json!({ "txn_id": txn }))).await.unwrap();
+    assert_eq!(read(dir.path(), "live.txt").as_deref(), Some("keep"));
+}
+
+// A directory where data/b.json should go makes the commit stop after
+// data/a.json has been moved, like a crash between renames.
+async fn interrupted_commit(base: &Path) {
+    std::fs::create_dir_all(base.join("data/b.json")).unwrap();
+    std::fs::write(base.join("data/a.json"), "\"old a\"").unwrap();
+    let executor = FileExecutor::new(base.to_path_buf());
+    let txn = begin(&executor).await;
+    stage_publish(&executor, &txn).await;
+    let err = executor.execute(&task("txn_commit", json!({ "txn_id": txn }))).await.unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.to_string().contains("txn_recover")), "{:?}", err);
+    assert_eq!(read(base, "data/a.json").as_deref(), Some("\"new a\""));
+    assert!(read(base, "index.json").is_none());
+}
+
+#[tokio::test]
+async fn test_recover_interrupted_commit() {
+    let dir = tempdir().unwrap();
+    interrupted_commit(dir.path()).await;
+
+    // A fresh executor, as after a restart
+    let executor = FileExecutor::new(dir.path().to_path_buf());
+    let output = executor
+        .execute(&task("txn_recover", json!({ "action": "rollback" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["recovered"][0]["paths"], json!(["data/a.json"]));
+    assert_eq!(read(dir.path(), "data/a.json").as_deref(), Some("\"old a\""));
+    assert!(read(dir.path(), "index.json").is_none());
+    assert_eq!(std::fs::read_dir(dir.path().join(".txn")).unwrap().count(), 0);
+
+    let dir = tempdir().unwrap();
+    interrupted_commit(dir.path()).await;
+    std::fs::remove_dir(dir.path().join("data/b.json")).unwrap();
+    let executor = FileExecutor::new(dir.path().to_path_buf());
+    let output = executor
+        .execute(&task("txn_recover", json!({})))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["recovered"][0]["paths"], json!(["data/b.json", "index.json"]));
+    assert_eq!(read(dir.path(), "data/a.json").as_deref(), Some("\"new a\""));
+    assert_eq!(read(dir.path(), "data/b.json").as_deref(), Some("\"new b\""));
+    assert!(read(dir.path(), "index.json").is_some());
+
+    // Staging from a transaction that never committed is just discarded
+    let txn = begin(&executor).await;
+    executor
+        .execute(&task("write", json!({ "txn": txn, "path": "orphan.txt", "content": "x" })))
+        .await
+        .unwrap();
+    let restarted = FileExecutor::new(dir.path().to_path_buf());
+    let output = restarted.execute(&task("txn_recover", json!({}))).await.unwrap().output.unwrap();
+    assert_eq!(output["discarded"], 1);
+    assert!(read(dir.path(), "orphan.txt").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_readers_never_see_missing_references() {
+    let dir = tempdir().unwrap();
+    let executor = Arc::new(FileExecutor::new(dir.path().to_path_buf()));
+    let base = dir.path().to_path_buf();
+
+    let writer = {
+        let executor = executor.clone();
+        tokio::spawn(async move {
+            for version in 0..30 {
+                let txn = begin(&executor).await;
+                let data = format!("data/v{}.json", version);
+                executor
+                    .execute(&task("write_json", json!({
+                        "txn": txn,
+                        "path": "index.json",
+                        "data": { "current": data },
+                        "txn_depends_on": [data],
+                    })))
+                    .await
+                    .unwrap();
+                executor
+                    .execute(&task("write", json!({ "txn": txn, "path": data, "content": "payload" })))
+                    .await
+                    .unwrap();
+                executor.execute(&task("txn_commit", json!({ "txn_id": txn }))).await.unwrap();
+            }
+        })
+    };
+
+    while !writer.is_finished() {
+        if let Some(index) = read(&base, "index.json") {
+            let index: Value = serde_json::from_str(&index).unwrap();
+            let current = index["current"].as_str().unwrap();
+            assert_eq!(read(&base, current).as_deref(), Some("payload"), "{} missing", current);
+        }
+        tokio::task::yield_now().await;
+    }
+    writer.await.unwrap();
+    assert!(read(&base, "index.json").unwrap().contains("v29"));
+}
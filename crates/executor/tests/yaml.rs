@@ -0,0 +1,81 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::Executor;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_read_yaml_resolves_anchors_and_nesting() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("deploy.yaml"), "\
+defaults: &defaults
+  replicas: 2
+  image: { name: app, tag: \"1.4\" }
+services:
+  web:
+    <<: *defaults
+    replicas: 4
+  worker: *defaults
+ports: [80, 443]
+200: ok
+").unwrap();
+
+    let output = executor
+        .execute(&task("read_yaml", json!({ "path": "deploy.yaml" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["services"]["web"], json!({ "replicas": 4, "image": { "name": "app", "tag": "1.4" } }));
+    assert_eq!(output["services"]["worker"], output["defaults"]);
+    assert_eq!(output["ports"], json!([80, 443]));
+    assert_eq!(output["200"], "ok");
+}
+
+#[tokio::test]
+async fn test_multi_doc_and_errors() {
+    let (dir, executor) = temp_executor();
+    std::fs::write(dir.path().join("multi.yaml"), "a: 1\n---\nb: 2\n").unwrap();
+    std::fs::write(dir.path().join("bad.yaml"), "a: 1\nb: [1, 2\nc: 3\n").unwrap();
+
+    let output = executor
+        .execute(&task("read_yaml", json!({ "path": "multi.yaml", "multi_doc": true })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output, json!([{ "a": 1 }, { "b": 2 }]));
+    let err = executor
+        .execute(&task("read_yaml", json!({ "path": "multi.yaml" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("multi_doc"), "{}", err);
+
+    let err = executor
+        .execute(&task("read_yaml", json!({ "path": "bad.yaml" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    assert!(err.to_string().contains("at line 3, column"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_write_yaml_round_trips() {
+    let (dir, executor) = temp_executor();
+    let data = json!({ "name": "nightly", "steps": [{ "run": "backup", "retries": 3 }], "enabled": true });
+
+    executor
+        .execute(&task("write_yaml", json!({ "path": "out.yaml", "data": data })))
+        .await
+        .unwrap();
+    let text = std::fs::read_to_string(dir.path().join("out.yaml")).unwrap();
+    assert!(text.contains("name: nightly"), "{}", text);
+    let output = executor
+        .execute(&task("read_yaml", json!({ "path": "out.yaml" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output, data);
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::{task, temp_executor};
+use local_automation_common::Error;
+use local_automation_executor::file::FileExecutor;
+use local_automation_executor::Executor;
+use serde_json::json;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn write_archive(path: &std::path::Path, entries: &[(&str, &str)]) {
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    for (name, content) in entries {
+        writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[tokio::test]
+async fn test_zip_and_unzip_round_trip() {
+    let (dir, executor) = temp_executor();
+    std::fs::create_dir_all(dir.path().join("site/assets/empty")).unwrap();
+    std::fs::write(dir.path().join("site/index.html"), "<h1>hi</h1>".repeat(100)).unwrap();
+    std::fs::write(dir.path().join("site/assets/app.js"), "console.log(1)").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    let output = executor
+        .execute(&task("zip", json!({ "sources": ["site", "notes.txt"], "dest": "bundle.zip" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["entries"], json!(["site/assets/app.js", "site/index.html", "notes.txt"]));
+    assert_eq!(output["uncompressed_bytes"], 1100 + 14 + 5);
+    assert!(output["compressed_bytes"].as_u64().unwrap() < 1119);
+
+    let output = executor
+        .execute(&task("unzip", json!({ "path": "bundle.zip", "dest_dir": "out" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["entries"].as_array().unwrap().len(), 3);
+    assert_eq!(output["skipped"], json!([]));
+    assert_eq!(output["uncompressed_bytes"], 1119);
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/site/assets/app.js")).unwrap(), "console.log(1)");
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/notes.txt")).unwrap(), "notes");
+    assert!(dir.path().join("out/site/assets/empty").is_dir());
+
+    // A second extraction refuses to clobber without overwrite
+    let err = executor
+        .execute(&task("unzip", json!({ "path": "bundle.zip", "dest_dir": "out" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("already exists")), "{:?}", err);
+    executor
+        .execute(&task("unzip", json!({ "path": "bundle.zip", "dest_dir": "out", "overwrite": true })))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_unzip_rejects_zip_slip_entries() {
+    let dir = tempdir().unwrap();
+    write_archive(&dir.path().join("evil.zip"), &[
+        ("ok.txt", "fine"),
+        ("../escape.txt", "bad"),
+        ("/etc/absolute.txt", "bad"),
+        ("nested/../../up.txt", "bad"),
+    ]);
+    let executor = FileExecutor::new(dir.path().join("work"));
+    std::fs::create_dir(dir.path().join("work")).unwrap();
+    std::fs::rename(dir.path().join("evil.zip"), dir.path().join("work/evil.zip")).unwrap();
+
+    let err = executor
+        .execute(&task("unzip", json!({ "path": "evil.zip", "dest_dir": "out" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(ref m) if m.contains("../escape.txt")), "{:?}", err);
+    assert!(!dir.path().join("work/out").exists());
+
+    let output = executor
+        .execute(&task("unzip", json!({ "path": "evil.zip", "dest_dir": "out", "on_unsafe": "skip" })))
+        .await
+        .unwrap()
+        .output
+        .unwrap();
+    assert_eq!(output["entries"], json!(["ok.txt"]));
+    assert_eq!(output["skipped"], json!(["../escape.txt", "/etc/absolute.txt", "nested/../../up.txt"]));
+    assert!(!dir.path().join("escape.txt").exists());
+    assert!(!dir.path().join("work/up.txt").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_unzip_never_writes_through_symlinks() {
+    let dir = tempdir().unwrap();
+    let (work, outside) = (dir.path().join("work"), dir.path().join("outside"));
+    std::fs::create_dir_all(work.join("out")).unwrap();
+    std::fs::create_dir(&outside).unwrap();
+    std::fs::write(outside.join("target.txt"), "untouched").unwrap();
+    std::os::unix::fs::symlink(&outside, work.join("out/sub")).unwrap();
+    std::os::unix::fs::symlink(outside.join("target.txt"), work.join("out/file.txt")).unwrap();
+    std::os::unix::fs::symlink(outside.join("missing.txt"), work.join("out/dangling.txt")).unwrap();
+    let executor = FileExecutor::new(work.clone());
+
+    write_archive(&work.join("dir.zip"), &[("sub/evil.txt", "bad")]);
+    let err = executor
+        .execute(&task("unzip", json!({ "path": "dir.zip", "dest_dir": "out", "overwrite": true })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied(ref m) if m.contains("sub/evil.txt")), "{:?}", err);
+    assert!(!outside.join("evil.txt").exists());
+
+    // A dangling link still counts as something already there
+    write_archive(&work.join("files.zip"), &[("file.txt", "new"), ("dangling.txt", "new")]);
+    let err = executor
+        .execute(&task("unzip", json!({ "path": "files.zip", "dest_dir": "out" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(ref m) if m.contains("already exists")), "{:?}", err);
+
+    executor
+        .execute(&task("unzip", json!({ "path": "files.zip", "dest_dir": "out", "overwrite": true })))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(outside.join("target.txt")).unwrap(), "untouched");
+    assert!(!outside.join("missing.txt").exists());
+    for name in ["file.txt", "dangling.txt"] {
+        let metadata = work.join("out").join(name).symlink_metadata().unwrap();
+        assert!(metadata.is_file(), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_zip_missing_source_fails() {
+    let (dir, executor) = temp_executor();
+
+    let err = executor
+        .execute(&task("zip", json!({ "sources": ["missing"], "dest": "a.zip" })))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(ref e) if e.kind() == std::io::ErrorKind::NotFound), "{:?}", err);
+    assert!(!dir.path().join("a.zip").exists());
+}